@@ -18,8 +18,9 @@ pub use run_timer::*;
 
 /// Registers systems and observers required for long running actions.
 pub fn continue_run_plugin(app: &mut App) {
+	let schedule = app.tick_schedule();
 	app.add_systems(
-		Update,
+		schedule,
 		(
 			tick_run_timers.never_param_warn(),
 			// return_in_duration must be after tick_run_timers
@@ -29,6 +29,13 @@ pub struct ActionObservers(pub Vec<Entity>);
 #[derive(Debug, Default, Resource, Deref, DerefMut)]
 pub struct ActionObserverMap(pub HashMap<ComponentId, Entity>);
 
+/// Insert into a [`World`] that only stores tree data, like the world
+/// of a [`Scene`](bevy::scene::Scene) used as a hot reload source,
+/// so that actions can be spawned without the [`BeetFlowPlugin`](crate::prelude::BeetFlowPlugin).
+/// Observers are not attached to actions spawned in this world.
+#[derive(Debug, Default, Clone, Copy, Resource)]
+pub struct DisableActionObservers;
+
 impl ActionObserverMap {
 	fn get_or_spawn(
 		world: &mut DeferredWorld,
@@ -56,6 +63,14 @@ impl ActionObservers {
 		cid: ComponentId,
 		on_spawn_observer: impl FnMut(&mut DeferredWorld, Entity),
 	) {
+		if world.contains_resource::<DisableActionObservers>() {
+			return;
+		}
+		if !world.contains_resource::<ActionObserverMap>() {
+			panic!(
+				"ActionObserverMap not found, the BeetFlowPlugin must be added before spawning actions, or DisableActionObservers for data only worlds"
+			);
+		}
 		let observer_entity =
 			ActionObserverMap::get_or_spawn(world, cid, on_spawn_observer);
 
@@ -105,4 +120,13 @@ mod test {
 		let observers = world.get::<ActionObservers>(entity).unwrap();
 		expect(observers.len()).to_be(2);
 	}
+
+	#[test]
+	fn disabled() {
+		let mut world = World::new();
+		world.init_resource::<DisableActionObservers>();
+		let entity = world.spawn(Sequence).id();
+		world.flush();
+		expect(world.get::<ActionObservers>(entity).unwrap().len()).to_be(0);
+	}
 }
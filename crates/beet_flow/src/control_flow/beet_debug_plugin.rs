@@ -10,6 +10,8 @@ use std::borrow::Cow;
 /// If they have a [`Name`] that will be used instead of the entity id.
 /// It emits [OnLogMessage] events, and also
 /// will print to stdout if [`Self::log_to_stdout`] is true.
+/// When using a custom [`TickSchedule`] this plugin should be added
+/// after the [`BeetFlowPlugin`].
 #[derive(Debug, Clone)]
 pub struct BeetDebugPlugin {
 	/// Log whenever [OnRunAction] is triggered.
@@ -86,6 +88,7 @@ impl BeetDebugPlugin {
 
 impl Plugin for BeetDebugPlugin {
 	fn build(&self, app: &mut App) {
+		let schedule = app.tick_schedule();
 		// TODO when resolved: [Observers::run_if](https://github.com/bevyengine/bevy/issues/14195)
 		app
 			// maybe log_user_message belongs elsewhere
@@ -94,7 +97,7 @@ impl Plugin for BeetDebugPlugin {
 			.add_observer(log_on_run_result.never_param_warn())
 			.add_event::<OnLogMessage>()
 			.add_systems(
-				Update,
+				schedule,
 				// (
 				log_running
 					.run_if(resource_exists::<DebugRunning>)
@@ -261,10 +264,14 @@ fn log_on_run_result(
 	_m: Res<DebugOnResult>,
 	stdout: Option<Res<DebugToStdOut>>,
 ) {
+	let prefix = match &ev.reason {
+		Some(reason) => format!("{:?} ({reason})", &ev.payload),
+		None => format!("{:?}", &ev.payload),
+	};
 	let msg = OnLogMessage::new_with_query(
 		ev.resolve_action(),
 		&query,
-		&format!("{:?}", &ev.payload),
+		&prefix,
 		OnLogMessage::FLOW_COLOR,
 	);
 	if stdout.is_some() {
@@ -12,6 +12,8 @@ use crate::prelude::*;
 pub use action_event::*;
 pub use action_observers::*;
 pub use beet_debug_plugin::*;
+use bevy::ecs::schedule::InternedScheduleLabel;
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
 pub use on_result::*;
 pub use on_run::*;
@@ -22,20 +24,60 @@ use interrupt_on_result::*;
 pub use interrupt_on_run::*;
 use std::fmt::Debug;
 
-/// Sets up the base functionality for [`OnRun`] and [`OnResult`] routing.
-pub(crate) fn control_flow_plugin(app: &mut App) {
-	app.init_resource::<ActionObserverMap>()
-		.add_plugins((
-			run_plugin::<(), RunResult>,
-			run_plugin::<RequestScore, ScoreValue>,
-		))
-		.configure_sets(Update, PreTickSet)
-		.configure_sets(Update, TickSet.after(PreTickSet))
-		.configure_sets(Update, PostTickSet.after(TickSet))
-		.add_systems(
-			Update,
-			run_on_spawn.never_param_warn().in_set(PreTickSet),
-		);
+/// Sets up the base functionality for [`OnRun`] and [`OnResult`] routing,
+/// configuring the tick sets in the provided [`TickSchedule`].
+pub(crate) struct ControlFlowPlugin {
+	pub schedule: InternedScheduleLabel,
+}
+
+impl Plugin for ControlFlowPlugin {
+	fn build(&self, app: &mut App) {
+		let schedule = self.schedule;
+		app.insert_resource(TickSchedule(schedule))
+			.init_resource::<ActionObserverMap>()
+			.add_plugins((
+				run_plugin::<(), RunResult>,
+				run_plugin::<RequestScore, ScoreValue>,
+			))
+			.configure_sets(schedule, PreTickSet)
+			.configure_sets(schedule, TickSet.after(PreTickSet))
+			.configure_sets(schedule, PostTickSet.after(TickSet))
+			.add_systems(
+				schedule,
+				run_on_spawn.never_param_warn().in_set(PreTickSet),
+			);
+	}
+}
+
+/// The schedule in which the [`PreTickSet`], [`TickSet`] and [`PostTickSet`]
+/// are configured, this is [`Update`] by default and can be set via
+/// [`BeetFlowPlugin::new`].
+/// Plugins that add systems to the tick sets should use
+/// [`TickScheduleAppExt::tick_schedule`] so that they follow it.
+#[derive(Debug, Clone, Copy, Deref, Resource)]
+pub struct TickSchedule(pub InternedScheduleLabel);
+
+impl Default for TickSchedule {
+	fn default() -> Self { Self(Update.intern()) }
+}
+
+/// Helpers for adding systems to the [`TickSchedule`].
+#[extend::ext(name=TickScheduleAppExt)]
+pub impl App {
+	/// Get the schedule that beet_flow tick sets are configured in.
+	/// The [`BeetFlowPlugin`] should be added first, otherwise this
+	/// warns and falls back to [`Update`].
+	fn tick_schedule(&self) -> InternedScheduleLabel {
+		match self.world().get_resource::<TickSchedule>() {
+			Some(schedule) => schedule.0,
+			None => {
+				log::warn!(
+					"TickSchedule not found, defaulting to Update. Add the BeetFlowPlugin before plugins that use the tick sets."
+				);
+				TickSchedule::default().0
+			}
+		}
+	}
 }
 /// Any [RunTimer] will be ticked, runs before [`TickSet`]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
@@ -79,3 +121,31 @@ impl RunPayload for () {
 impl ResultPayload for RunResult {
 	type Run = ();
 }
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::ecs::schedule::ScheduleLabel;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[derive(Debug, Clone, PartialEq, Eq, Hash, ScheduleLabel)]
+	struct CustomTick;
+
+	#[test]
+	fn custom_schedule() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::new(CustomTick));
+		expect(app.tick_schedule()).to_be(CustomTick.intern());
+		let on_result = observe_triggers::<OnResult>(app.world_mut());
+		app.world_mut()
+			.spawn((ReturnWith(RunResult::Success), RunOnSpawn::default()));
+
+		app.update();
+		expect(&on_result).not().to_have_been_called();
+
+		app.world_mut().run_schedule(CustomTick);
+		expect(&on_result).to_have_been_called();
+	}
+}
@@ -1,5 +1,6 @@
 use crate::prelude::*;
 use bevy::prelude::*;
+use std::borrow::Cow;
 
 
 /// An event triggered on an [`ActionEntity`], propagated to the observers automatically
@@ -9,6 +10,9 @@ pub struct OnResultAction<T = RunResult> {
 	/// The payload of the result.
 	/// By analogy if an action is a function, this would be the returned value.
 	pub payload: T,
+	/// An optional explanation of the result, usually describing why
+	/// an action failed. See [Self::with_reason].
+	pub reason: Option<ResultReason>,
 	/// this is not exposed as it may be placeholder, instead use [Trigger::resolve_origin]
 	origin: Entity,
 	/// this is not exposed as it may be placeholder, instead use [Trigger::resolve_action]
@@ -36,6 +40,7 @@ impl<T> OnResultAction<T> {
 	pub fn new(action: Entity, origin: Entity, payload: T) -> Self {
 		Self {
 			payload,
+			reason: None,
 			origin,
 			action,
 		}
@@ -53,6 +58,7 @@ impl<T> OnResultAction<T> {
 	pub fn local(payload: T) -> Self {
 		Self {
 			payload,
+			reason: None,
 			origin: Entity::PLACEHOLDER,
 			action: Entity::PLACEHOLDER,
 		}
@@ -71,10 +77,27 @@ impl<T> OnResultAction<T> {
 	pub fn global(action: Entity, payload: T) -> Self {
 		Self {
 			payload,
+			reason: None,
 			origin: action,
 			action,
 		}
 	}
+	/// Attach a [`ResultReason`] to this result, it will be propagated
+	/// to [`OnResult`] and [`OnChildResult`], and bubbled up
+	/// alongside the payload.
+	/// ## Example
+	/// ```
+	/// # use beet_flow::doctest::*;
+	/// # let mut world = world();
+	/// world
+	/// 	.spawn(Remove::<OnResult, Running>::default())
+	/// 	.trigger(OnResultAction::local(RunResult::Failure)
+	/// 		.with_reason("out of battery"));
+	/// ```
+	pub fn with_reason(mut self, reason: impl Into<ResultReason>) -> Self {
+		self.reason = Some(reason.into());
+		self
+	}
 }
 
 /// An event triggered on an [`ActionObserver`] which can be listened to
@@ -92,6 +115,8 @@ pub struct OnResult<T = RunResult> {
 	pub origin: Entity,
 	/// The [ActionEntity] that triggered this event.
 	pub action: Entity,
+	/// The reason provided by [OnResultAction::with_reason], if any.
+	pub reason: Option<ResultReason>,
 	/// only [OnResultAction] is allowed to create this struct
 	_sealed: (),
 }
@@ -112,6 +137,8 @@ pub struct OnChildResult<T = RunResult> {
 	pub parent: Entity,
 	/// The child [ActionEntity] entity that triggered the result.
 	pub child: Entity,
+	/// The reason provided by [OnResultAction::with_reason], if any.
+	pub reason: Option<ResultReason>,
 	/// only [OnResultAction] is allowed to create this struct
 	_sealed: (),
 }
@@ -127,6 +154,7 @@ impl<T: ResultPayload> OnChildResult<T> {
 		action: Entity,
 		origin: Entity,
 		payload: T,
+		reason: Option<ResultReason>,
 	) {
 		if let Ok(parent) = parents.get(action) {
 			let parent = parent.get();
@@ -136,6 +164,7 @@ impl<T: ResultPayload> OnChildResult<T> {
 					origin,
 					parent,
 					child: action,
+					reason,
 					_sealed: (),
 				};
 				commands.trigger_targets(res, (*action_observers).clone());
@@ -143,16 +172,18 @@ impl<T: ResultPayload> OnChildResult<T> {
 		}
 	}
 	/// Create a new [`OnResultAction`] on [Self::parent] with the given payload.
-	/// This is essentially a bubble up.
+	/// This is essentially a bubble up, the [Self::reason] is preserved.
 	pub fn trigger_bubble(&self, mut commands: Commands) {
-		commands.trigger(OnResultAction::new(
-			self.parent,
-			self.origin,
-			self.payload.clone(),
-		));
+		commands.trigger(OnResultAction {
+			payload: self.payload.clone(),
+			reason: self.reason.clone(),
+			origin: self.origin,
+			action: self.parent,
+		});
 	}
 	/// Create a new [`OnRunAction`] on [Self::parent] with the given payload.
-	/// This is essentially a bubble up.
+	/// This is essentially a bubble up, because the payload is replaced
+	/// the [Self::reason] is discarded.
 	pub fn trigger_bubble_with(&self, mut commands: Commands, payload: T) {
 		commands.trigger(OnResultAction::new(
 			self.parent,
@@ -189,6 +220,41 @@ pub enum RunResult {
 	Failure,
 }
 
+/// An explanation attached to an [`OnResultAction`], most commonly
+/// used to describe why an action failed so that parents like
+/// [`Fallback`] can choose how to recover.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// let reason = ResultReason::new("timeout");
+/// assert_eq!(reason, "timeout".into());
+/// ```
+#[derive(
+	Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deref, Reflect,
+)]
+pub struct ResultReason(pub Cow<'static, str>);
+
+impl ResultReason {
+	/// Create a new [`ResultReason`].
+	pub fn new(reason: impl Into<Cow<'static, str>>) -> Self {
+		Self(reason.into())
+	}
+}
+
+impl From<&'static str> for ResultReason {
+	fn from(reason: &'static str) -> Self { Self(reason.into()) }
+}
+
+impl From<String> for ResultReason {
+	fn from(reason: String) -> Self { Self(reason.into()) }
+}
+
+impl std::fmt::Display for ResultReason {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
 /// Add this to an entity to prevent the run result from bubbling up.
 /// Any action that requires this needs to manually call OnChildResult
 /// on the parent entity. For an example, see [`Repeat`].
@@ -213,6 +279,7 @@ pub(super) fn propagate_on_result<T: ResultPayload>(
 			payload: ev.payload.clone(),
 			origin,
 			action,
+			reason: ev.reason.clone(),
 			_sealed: (),
 		};
 		commands.trigger_targets(res, (*action_observers).clone());
@@ -227,6 +294,7 @@ pub(super) fn propagate_on_result<T: ResultPayload>(
 			action,
 			ev.resolve_origin(),
 			ev.payload.clone(),
+			ev.reason.clone(),
 		);
 	}
 }
@@ -148,6 +148,19 @@ impl<T: RunPayload> OnRun<T> {
 			payload,
 		));
 	}
+	/// Call [`OnResultAction`] for this event's action with a
+	/// [`ResultReason`], cloning this event's origin.
+	pub fn trigger_result_with_reason(
+		&self,
+		commands: &mut Commands,
+		payload: T::Result,
+		reason: impl Into<ResultReason>,
+	) {
+		commands.trigger(
+			OnResultAction::new(self.action, self.origin, payload)
+				.with_reason(reason),
+		);
+	}
 }
 
 impl OnRun<()> {
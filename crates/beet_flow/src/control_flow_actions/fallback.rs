@@ -6,8 +6,10 @@ use bevy::prelude::*;
 /// - [ControlFlow](ActionTag::ControlFlow)
 /// ## Logic
 /// - If a child succeeds it succeed.
-/// - If a child fails it will run the next child.
-/// - If there are no more children to run it will succeed.
+/// - If a child fails it will run the next child, skipping any children
+/// 	with a [`RecoverFrom`] that does not match the failure [`ResultReason`].
+/// - If there are no more children to run it will bubble the last result,
+/// 	including its reason.
 /// ## Example
 /// This example will run the first child, then the second child.
 /// ```
@@ -24,6 +26,42 @@ use bevy::prelude::*;
 #[reflect(Default, Component)]
 pub struct Fallback;
 
+/// Add to a child of a [`Fallback`] to only run it if the previous
+/// child failed with one of the provided [`ResultReason`]s.
+/// Children without this component will always be run.
+/// ## Example
+/// Recharge if the first child failed because of a low battery,
+/// otherwise return to base.
+/// ```
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// world
+///		.spawn(Fallback)
+///		.with_child(Name::new("Patrol"))
+///		.with_child((
+///			Name::new("Recharge"),
+///			RecoverFrom::new(["low battery"]),
+///		))
+///		.with_child(Name::new("Return To Base"));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct RecoverFrom(pub Vec<ResultReason>);
+
+impl RecoverFrom {
+	/// Create a new [`RecoverFrom`] with the provided reasons.
+	pub fn new<R: Into<ResultReason>>(
+		reasons: impl IntoIterator<Item = R>,
+	) -> Self {
+		Self(reasons.into_iter().map(|r| r.into()).collect())
+	}
+	/// Whether this child should be run for a failure with
+	/// the provided reason.
+	pub fn matches(&self, reason: Option<&ResultReason>) -> bool {
+		reason.map(|r| self.0.contains(r)).unwrap_or(false)
+	}
+}
+
 fn on_start(
 	ev: Trigger<OnRun>,
 	mut commands: Commands,
@@ -43,6 +81,7 @@ fn on_next(
 	ev: Trigger<OnChildResult>,
 	commands: Commands,
 	query: Query<&Children>,
+	recover_from: Query<&RecoverFrom>,
 ) {
 	if ev.payload == RunResult::Success {
 		ev.trigger_bubble(commands);
@@ -56,10 +95,18 @@ fn on_next(
 		.iter()
 		.position(|&x| x == ev.child)
 		.expect(&expect_action::to_have_child(&ev, ev.child));
-	if index == children.len() - 1 {
-		ev.trigger_bubble(commands);
+
+	let next = children.iter().skip(index + 1).find(|child| {
+		recover_from
+			.get(**child)
+			.map(|recover| recover.matches(ev.reason.as_ref()))
+			.unwrap_or(true)
+	});
+
+	if let Some(next) = next {
+		ev.trigger_run(commands, *next, ());
 	} else {
-		ev.trigger_run(commands, children[index + 1], ());
+		ev.trigger_bubble(commands);
 	}
 }
 
@@ -97,4 +144,67 @@ mod test {
 			("root".to_string(), RunResult::Success),
 		]);
 	}
+
+	#[test]
+	fn recover_from() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+
+		let on_run = collect_on_run(world);
+
+		let mut child1 = Entity::PLACEHOLDER;
+		let root = world
+			.spawn((Name::new("root"), Fallback))
+			.with_children(|parent| {
+				child1 = parent.spawn(Name::new("child1")).id();
+				parent.spawn((
+					Name::new("child2"),
+					RecoverFrom::new(["blocked"]),
+					ReturnWith(RunResult::Success),
+				));
+				parent.spawn((
+					Name::new("child3"),
+					RecoverFrom::new(["timeout"]),
+					ReturnWith(RunResult::Success),
+				));
+			})
+			.id();
+		world.entity_mut(root).flush_trigger(OnRun::local());
+		world.entity_mut(child1).flush_trigger(
+			OnResultAction::local(RunResult::Failure).with_reason("timeout"),
+		);
+
+		expect(on_run()).to_be(vec![
+			"root".to_string(),
+			"child1".to_string(),
+			"child3".to_string(),
+		]);
+	}
+
+	#[test]
+	fn bubbles_reason() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default());
+		let world = app.world_mut();
+		let on_result = observe_triggers::<OnResultAction>(world);
+
+		let mut child = Entity::PLACEHOLDER;
+		let root = world
+			.spawn(Fallback)
+			.with_children(|parent| {
+				child = parent.spawn_empty().id();
+			})
+			.id();
+		world.entity_mut(root).flush_trigger(OnRun::local());
+		world.entity_mut(child).flush_trigger(
+			OnResultAction::local(RunResult::Failure).with_reason("blocked"),
+		);
+
+		expect(&on_result).to_have_returned_nth_with(
+			1,
+			&OnResultAction::new(root, child, RunResult::Failure)
+				.with_reason("blocked"),
+		);
+	}
 }
@@ -73,6 +73,7 @@ fn repeat(
 				ev.action,
 				ev.origin,
 				ev.payload.clone(),
+				ev.reason.clone(),
 			);
 			return;
 		}
@@ -0,0 +1,129 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use bevy::scene::SceneSpawnError;
+
+
+/// Registers the systems required for [`HotReloadTree`].
+/// The [`AssetPlugin`] should be configured to watch for changes.
+pub fn hot_reload_plugin(app: &mut App) {
+	app.add_systems(Update, hot_reload_trees);
+}
+
+/// Add to the root of a tree spawned from a [`Scene`] to apply any changes
+/// to the scene asset while the tree is running, see [`patch_tree`] for
+/// how the trees are diffed. Scenes containing actions should be created
+/// with [`tree_scene`].
+/// ## Warning
+/// [`hot_reload_plugin`] must be registered for this component to work.
+/// ## Example
+/// ```no_run
+/// # use beet_flow::doctest::*;
+/// # let mut world = world();
+/// let scene = world.resource::<AssetServer>().load("my_tree.scn.ron");
+/// world.spawn((Name::new("My Tree"), HotReloadTree::new(scene)));
+/// ```
+#[derive(Debug, Clone, Component)]
+pub struct HotReloadTree {
+	/// The scene containing the source tree.
+	pub scene: Handle<Scene>,
+}
+
+impl HotReloadTree {
+	/// Create a new [`HotReloadTree`] for the provided scene.
+	pub fn new(scene: Handle<Scene>) -> Self { Self { scene } }
+}
+
+/// Create a [`Scene`] from a [`DynamicScene`] containing a tree,
+/// like [`Scene::from_dynamic_scene`] but with [`DisableActionObservers`]
+/// so that its actions can be stored without the [`BeetFlowPlugin`].
+pub fn tree_scene(
+	dynamic_scene: &DynamicScene,
+	type_registry: &AppTypeRegistry,
+) -> Result<Scene, SceneSpawnError> {
+	let mut world = World::new();
+	world.init_resource::<DisableActionObservers>();
+	world.insert_resource(type_registry.clone());
+	let mut entity_map = default();
+	dynamic_scene.write_to_world_with(
+		&mut world,
+		&mut entity_map,
+		type_registry,
+	)?;
+	Ok(Scene::new(world))
+}
+
+fn hot_reload_trees(
+	mut events: EventReader<AssetEvent<Scene>>,
+	mut commands: Commands,
+	query: Query<(Entity, &HotReloadTree)>,
+) {
+	for ev in events.read() {
+		let AssetEvent::Modified { id } = *ev else {
+			continue;
+		};
+		for (live, _) in
+			query.iter().filter(|(_, reload)| reload.scene.id() == id)
+		{
+			commands.queue(move |world: &mut World| {
+				world.resource_scope(|world, scenes: Mut<Assets<Scene>>| {
+					let Some(scene) = scenes.get(id) else {
+						return;
+					};
+					let Some(source) = source_root(world, live, &scene.world)
+					else {
+						log::warn!(
+							"HotReloadTree: could not find a root in {id:?}"
+						);
+						return;
+					};
+					let patch = patch_tree(world, live, &scene.world, source);
+					log::info!("HotReloadTree: reloaded {live}\n{patch:#?}");
+				});
+			});
+		}
+	}
+}
+
+/// Find the root of the source tree, preferring a root with the same
+/// name as the live tree.
+fn source_root(world: &World, live: Entity, scene: &World) -> Option<Entity> {
+	let roots = scene
+		.iter_entities()
+		.filter(|entity| !entity.contains::<Parent>())
+		.collect::<Vec<_>>();
+	let name = world.get::<Name>(live);
+	roots
+		.iter()
+		.find(|root| root.get::<Name>() == name && name.is_some())
+		.or_else(|| {
+			if roots.len() == 1 {
+				roots.first()
+			} else {
+				None
+			}
+		})
+		.map(|root| root.id())
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn scene_from_dynamic() {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default())
+			.register_type::<Name>()
+			.register_type::<Sequence>();
+		let world = app.world_mut();
+		world.spawn((Name::new("root"), Sequence));
+		let dynamic_scene = DynamicScene::from_world(world);
+		let registry = world.resource::<AppTypeRegistry>();
+		let mut scene = tree_scene(&dynamic_scene, registry).unwrap();
+		let mut query = scene.world.query_filtered::<&Name, With<Sequence>>();
+		expect(query.iter(&scene.world).count()).to_be(1);
+	}
+}
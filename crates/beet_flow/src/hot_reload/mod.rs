@@ -0,0 +1,9 @@
+//! Apply edits to behavior tree assets while they are running.
+//! Trees are diffed by their [`Name`](bevy::prelude::Name) path,
+//! unchanged nodes are patched in place and changed branches are respawned.
+#[cfg(feature = "bevy_default")]
+mod hot_reload_tree;
+mod patch_tree;
+#[cfg(feature = "bevy_default")]
+pub use hot_reload_tree::*;
+pub use patch_tree::*;
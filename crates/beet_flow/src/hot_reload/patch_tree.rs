@@ -0,0 +1,492 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use std::any::TypeId;
+
+
+/// A summary of the changes made by [`patch_tree`], each node is
+/// identified by its [`Name`] path, ie `root/child`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TreePatch {
+	/// Nodes whose component values were updated in place.
+	pub patched: Vec<String>,
+	/// Branches that were added or whose components changed, and
+	/// so were despawned and spawned again from the source.
+	pub respawned: Vec<String>,
+	/// Branches that no longer exist in the source.
+	pub removed: Vec<String>,
+	/// Nodes that were run again because a running branch was
+	/// respawned or removed.
+	pub restarted: Vec<String>,
+}
+
+impl TreePatch {
+	/// Returns true if the live tree already matched the source.
+	pub fn is_empty(&self) -> bool {
+		self.patched.is_empty()
+			&& self.respawned.is_empty()
+			&& self.removed.is_empty()
+	}
+}
+
+/// Update a live tree to match a source tree in another [`World`],
+/// for example the world of a reloaded [`Scene`](bevy::scene::Scene).
+/// The source world should contain [`DisableActionObservers`].
+///
+/// Nodes are matched by their [`Name`] path:
+/// - If a node has the same set of reflected components its values are
+///   patched in place, preserving the entity and any runtime state.
+/// - If a node has different components, or does not exist yet, the
+///   branch is respawned from the source.
+/// - Branches that no longer exist are removed.
+///
+/// The root is always patched in place so that references to it remain valid,
+/// components on the root that are not in the source are kept as they may
+/// have been inserted at runtime.
+///
+/// Before a branch is despawned [`Running`] is removed from all of its
+/// descendants so that long running actions are cleanly interrupted.
+/// If the branch was running the tree is restarted from the closest point
+/// so that its parent is not left waiting for a result:
+/// - A respawned branch is run again with the same origin.
+/// - The parent of a removed branch is run again.
+///
+/// Only components registered in the [`AppTypeRegistry`] with
+/// [`ReflectComponent`] are considered, runtime components like
+/// [`Running`], [`RunTimer`], [`Parent`] and [`Children`] are ignored.
+/// Entity references inside components are not remapped.
+pub fn patch_tree(
+	world: &mut World,
+	live: Entity,
+	source_world: &World,
+	source: Entity,
+) -> TreePatch {
+	let registry = world.resource::<AppTypeRegistry>().clone();
+	let registry = registry.read();
+	let mut patcher = TreePatcher {
+		registry: &registry,
+		source_world,
+		ignored: [
+			TypeId::of::<Parent>(),
+			TypeId::of::<Children>(),
+			TypeId::of::<Running>(),
+			TypeId::of::<RunTimer>(),
+		],
+		patch: TreePatch::default(),
+		runs: Vec::new(),
+	};
+	let path = name_of(source_world, source);
+	patcher.patch_node(world, live, source, path);
+	world.flush();
+	for (action, origin) in patcher.runs {
+		world.trigger(OnRunAction::new(action, origin, ()));
+	}
+	world.flush();
+	patcher.patch
+}
+
+struct TreePatcher<'a> {
+	registry: &'a TypeRegistry,
+	source_world: &'a World,
+	ignored: [TypeId; 4],
+	patch: TreePatch,
+	/// The actions to run after patching, with their origin.
+	runs: Vec<(Entity, Entity)>,
+}
+
+impl TreePatcher<'_> {
+	fn patch_node(
+		&mut self,
+		world: &mut World,
+		live: Entity,
+		source: Entity,
+		path: String,
+	) {
+		if self.sync_components(world, live, source) {
+			self.patch.patched.push(path.clone());
+		}
+		let first_run = self.runs.len();
+		let first_restarted = self.patch.restarted.len();
+		let mut removed_origin = None;
+
+		let mut live_children = children_of(world, live);
+		let mut order = Vec::new();
+		for source_child in children_of(self.source_world, source) {
+			let name = name_of(self.source_world, source_child);
+			let child_path = format!("{path}/{name}");
+			let matched = live_children
+				.iter()
+				.position(|live_child| name_of(world, *live_child) == name)
+				.map(|index| live_children.remove(index));
+
+			match matched {
+				Some(live_child)
+					if self.component_types(world, live_child)
+						== self.component_types(
+							self.source_world,
+							source_child,
+						) =>
+				{
+					self.patch_node(
+						world,
+						live_child,
+						source_child,
+						child_path,
+					);
+					order.push(live_child);
+				}
+				Some(live_child) => {
+					let origin = interrupt_and_despawn(world, live_child);
+					let child = self.spawn(world, source_child);
+					order.push(child);
+					if let Some(origin) = origin {
+						self.runs.push((child, origin));
+						self.patch.restarted.push(child_path.clone());
+					}
+					self.patch.respawned.push(child_path);
+				}
+				None => {
+					order.push(self.spawn(world, source_child));
+					self.patch.respawned.push(child_path);
+				}
+			}
+		}
+
+		for removed in live_children {
+			let removed_path = format!("{path}/{}", name_of(world, removed));
+			if let Some(origin) = interrupt_and_despawn(world, removed) {
+				removed_origin.get_or_insert(origin);
+			}
+			self.patch.removed.push(removed_path);
+		}
+		if let Some(origin) = removed_origin {
+			// running this node interrupts its descendants,
+			// so any runs inside it are replaced
+			self.runs.truncate(first_run);
+			self.patch.restarted.truncate(first_restarted);
+			self.runs.push((live, origin));
+			self.patch.restarted.push(path.clone());
+		}
+
+		if !order.is_empty() && children_of(world, live) != order {
+			world.entity_mut(live).replace_children(&order);
+		}
+	}
+
+	/// Insert or apply reflected components so that the live entity
+	/// matches the source, returning true if anything changed.
+	/// Components that are not in the source are kept.
+	fn sync_components(
+		&self,
+		world: &mut World,
+		live: Entity,
+		source: Entity,
+	) -> bool {
+		let source_types = self.component_types(self.source_world, source);
+		let mut changed = false;
+
+		for type_id in source_types {
+			let reflect = self.reflect_component(type_id);
+			let Some(source_value) =
+				reflect.reflect(self.source_world.entity(source))
+			else {
+				continue;
+			};
+			let is_equal =
+				reflect.reflect(world.entity(live)).map(|live_value| {
+					live_value
+						.reflect_partial_eq(source_value.as_partial_reflect())
+						.unwrap_or(false)
+				});
+			match is_equal {
+				Some(true) => {}
+				Some(false) => {
+					reflect.apply(
+						world.entity_mut(live),
+						source_value.as_partial_reflect(),
+					);
+					changed = true;
+				}
+				None => {
+					reflect.copy(
+						self.source_world,
+						world,
+						source,
+						live,
+						self.registry,
+					);
+					changed = true;
+				}
+			}
+		}
+		changed
+	}
+
+	/// Recursively spawn a branch from the source world.
+	fn spawn(&self, world: &mut World, source: Entity) -> Entity {
+		let entity = world.spawn_empty().id();
+		for type_id in self.component_types(self.source_world, source) {
+			self.reflect_component(type_id).copy(
+				self.source_world,
+				world,
+				source,
+				entity,
+				self.registry,
+			);
+		}
+		for source_child in children_of(self.source_world, source) {
+			let child = self.spawn(world, source_child);
+			world.entity_mut(entity).add_child(child);
+		}
+		entity
+	}
+
+	/// The sorted type ids of all reflected components on an entity,
+	/// excluding runtime components.
+	fn component_types(&self, world: &World, entity: Entity) -> Vec<TypeId> {
+		let mut types = world
+			.inspect_entity(entity)
+			.filter_map(|info| info.type_id())
+			.filter(|type_id| !self.ignored.contains(type_id))
+			.filter(|type_id| {
+				self.registry
+					.get_type_data::<ReflectComponent>(*type_id)
+					.is_some()
+			})
+			.collect::<Vec<_>>();
+		types.sort();
+		types
+	}
+
+	fn reflect_component(&self, type_id: TypeId) -> &ReflectComponent {
+		self.registry
+			.get_type_data::<ReflectComponent>(type_id)
+			.expect("component types are filtered by ReflectComponent")
+	}
+}
+
+fn name_of(world: &World, entity: Entity) -> String {
+	world
+		.get::<Name>(entity)
+		.map(|name| name.to_string())
+		.unwrap_or_default()
+}
+
+fn children_of(world: &World, entity: Entity) -> Vec<Entity> {
+	world
+		.get::<Children>(entity)
+		.map(|children| children.to_vec())
+		.unwrap_or_default()
+}
+
+/// Remove [`Running`] from the entity and all descendants, then despawn it.
+/// Returns the origin of the first running entity, if any.
+fn interrupt_and_despawn(world: &mut World, entity: Entity) -> Option<Entity> {
+	let mut origin = None;
+	let mut stack = vec![entity];
+	while let Some(entity) = stack.pop() {
+		stack.extend(children_of(world, entity));
+		if let Some(running) = world.get::<Running>(entity) {
+			origin.get_or_insert(running.origin);
+		}
+		world.entity_mut(entity).remove::<Running>();
+	}
+	world.entity_mut(entity).despawn_recursive();
+	origin
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	fn app() -> App {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default())
+			.register_type::<Name>()
+			.register_type::<Sequence>()
+			.register_type::<Fallback>()
+			.register_type::<Repeat>()
+			.register_type::<SucceedTimes>()
+			.register_type::<Running>();
+		app
+	}
+
+	fn names(world: &World, entity: Entity) -> Vec<String> {
+		world
+			.get::<Children>(entity)
+			.map(|children| {
+				children
+					.iter()
+					.map(|child| world.get::<Name>(*child).unwrap().to_string())
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	#[test]
+	fn unchanged() {
+		let mut app = app();
+		let live = app
+			.world_mut()
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("child"), Repeat::default()))
+			.id();
+		let mut source_world = World::new();
+		source_world.init_resource::<DisableActionObservers>();
+		let source = source_world
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("child"), Repeat::default()))
+			.id();
+
+		let patch = patch_tree(app.world_mut(), live, &source_world, source);
+		expect(patch.is_empty()).to_be_true();
+	}
+
+	#[test]
+	fn keeps_root_components() {
+		let mut app = app();
+		let live = app
+			.world_mut()
+			.spawn((Name::new("root"), Sequence, Repeat::default()))
+			.id();
+		let mut source_world = World::new();
+		source_world.init_resource::<DisableActionObservers>();
+		let source = source_world.spawn((Name::new("root"), Sequence)).id();
+
+		let patch = patch_tree(app.world_mut(), live, &source_world, source);
+		expect(patch.is_empty()).to_be_true();
+		expect(app.world().get::<Repeat>(live)).to_be_some();
+	}
+
+	#[test]
+	fn patches() {
+		let mut app = app();
+		let world = app.world_mut();
+
+		let mut repeat = Entity::PLACEHOLDER;
+		let mut changed = Entity::PLACEHOLDER;
+		let mut grandchild = Entity::PLACEHOLDER;
+		let live = world
+			.spawn((Name::new("root"), Sequence))
+			.with_children(|parent| {
+				repeat =
+					parent.spawn((Name::new("repeat"), Repeat::default())).id();
+				changed = parent
+					.spawn((Name::new("changed"), Sequence, Running::default()))
+					.with_children(|parent| {
+						grandchild = parent
+							.spawn((
+								Name::new("grandchild"),
+								Running::default(),
+							))
+							.id();
+					})
+					.id();
+				parent.spawn((Name::new("removed"), Sequence));
+			})
+			.id();
+
+		let mut source_world = World::new();
+		source_world.init_resource::<DisableActionObservers>();
+		let source = source_world
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("repeat"), Repeat::if_success()))
+			.with_children(|parent| {
+				parent.spawn((Name::new("changed"), Fallback)).with_child((
+					Name::new("grandchild"),
+					SucceedTimes::new(0),
+				));
+			})
+			.with_child((Name::new("added"), Sequence))
+			.id();
+
+		let interrupted = mock_bucket();
+		let interrupted2 = interrupted.clone();
+		world.add_observer(move |ev: Trigger<OnRemove, Running>| {
+			interrupted2.call(ev.entity());
+		});
+		world.flush();
+		let patch = patch_tree(world, live, &source_world, source);
+
+		expect(patch).to_be(TreePatch {
+			patched: vec!["root/repeat".into()],
+			respawned: vec!["root/changed".into(), "root/added".into()],
+			removed: vec!["root/removed".into()],
+			restarted: vec!["root/changed".into()],
+		});
+		expect(interrupted.called.lock().unwrap().clone())
+			.to_be(vec![changed, grandchild]);
+		expect(world.get::<Repeat>(repeat)).to_be(Some(&Repeat::if_success()));
+		expect(world.get::<Name>(changed)).to_be_none();
+		expect(world.get::<Name>(grandchild)).to_be_none();
+		expect(names(world, live)).to_be(vec![
+			"repeat".to_string(),
+			"changed".to_string(),
+			"added".to_string(),
+		]);
+		let respawned = world.get::<Children>(live).unwrap()[1];
+		expect(world.get::<Fallback>(respawned).is_some()).to_be_true();
+	}
+
+	#[test]
+	fn restarts_respawned() {
+		let mut app = app();
+		let world = app.world_mut();
+		let live = world
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("wait"), ContinueRun))
+			.with_child((Name::new("next"), SucceedTimes::new(1)))
+			.flush_trigger(OnRun::local())
+			.id();
+		let wait = world.get::<Children>(live).unwrap()[0];
+		expect(world.get::<Running>(wait)).to_be_some();
+
+		let mut source_world = World::new();
+		source_world.init_resource::<DisableActionObservers>();
+		let source = source_world
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("wait"), SucceedTimes::new(1)))
+			.with_child((Name::new("next"), SucceedTimes::new(1)))
+			.id();
+
+		let on_result = collect_on_result(world);
+		let patch = patch_tree(world, live, &source_world, source);
+		expect(patch.restarted).to_be(vec!["root/wait".to_string()]);
+		expect(on_result()).to_be(vec![
+			("wait".to_string(), RunResult::Success),
+			("next".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+		]);
+	}
+
+	#[test]
+	fn restarts_parent_of_removed() {
+		let mut app = app();
+		let world = app.world_mut();
+		let live = world
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("first"), SucceedTimes::new(2)))
+			.with_child((Name::new("wait"), ContinueRun))
+			.flush_trigger(OnRun::local())
+			.id();
+
+		let mut source_world = World::new();
+		source_world.init_resource::<DisableActionObservers>();
+		let source = source_world
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("first"), SucceedTimes::new(2)))
+			.id();
+
+		let on_result = collect_on_result(world);
+		let patch = patch_tree(world, live, &source_world, source);
+		expect(patch.removed).to_be(vec!["root/wait".to_string()]);
+		expect(patch.restarted).to_be(vec!["root".to_string()]);
+		expect(on_result()).to_be(vec![
+			("first".to_string(), RunResult::Success),
+			("root".to_string(), RunResult::Success),
+		]);
+	}
+}
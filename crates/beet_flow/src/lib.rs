@@ -7,11 +7,19 @@ pub mod asset_actions;
 pub mod continue_run;
 pub mod control_flow;
 pub mod control_flow_actions;
+pub mod hot_reload;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "test-utils")]
+pub mod testing;
 pub mod tree;
 #[allow(unused, reason = "docs")]
 use crate::prelude::*;
 use bevy::app::PluginGroup;
 use bevy::app::PluginGroupBuilder;
+use bevy::app::Update;
+use bevy::ecs::schedule::InternedScheduleLabel;
+use bevy::ecs::schedule::ScheduleLabel;
 
 /// Include the kitchen sink for beet_flow.
 pub mod prelude {
@@ -24,6 +32,11 @@ pub mod prelude {
 	pub use crate::continue_run::*;
 	pub use crate::control_flow::*;
 	pub use crate::control_flow_actions::*;
+	pub use crate::hot_reload::*;
+	#[cfg(feature = "remote")]
+	pub use crate::remote::*;
+	#[cfg(feature = "test-utils")]
+	pub use crate::testing::*;
 	pub use crate::tree::*;
 	pub use beet_flow_macros::*;
 }
@@ -51,20 +64,42 @@ pub mod doctest {
 /// The primary role that this plugin plays is as a kind of
 /// observer router, ensuring the OnRun and OnResult events are propagated
 /// correctly.
-/// - [control_flow::control_flow_plugin]
+/// - [control_flow::ControlFlowPlugin]
 /// - [continue_run::continue_run_plugin]
-#[derive(Default)]
+///
+/// By default the tick sets run in [`Update`], for
+/// deterministic simulations another schedule can be used:
+/// ```
+/// # use beet_flow::doctest::*;
+/// App::new().add_plugins(BeetFlowPlugin::new(FixedUpdate));
+/// ```
 pub struct BeetFlowPlugin {
-	// lifecycle_plugin: lifecycle::LifecyclePlugin,
+	/// The schedule in which the [`PreTickSet`], [`TickSet`] and
+	/// [`PostTickSet`] are configured.
+	schedule: InternedScheduleLabel,
 }
 
-impl BeetFlowPlugin {}
+impl Default for BeetFlowPlugin {
+	fn default() -> Self { Self::new(Update) }
+}
+
+impl BeetFlowPlugin {
+	/// Create a new [`BeetFlowPlugin`] that runs all tick systems
+	/// in the provided schedule.
+	pub fn new(schedule: impl ScheduleLabel) -> Self {
+		Self {
+			schedule: schedule.intern(),
+		}
+	}
+}
 
 
 impl PluginGroup for BeetFlowPlugin {
 	fn build(self) -> PluginGroupBuilder {
 		PluginGroupBuilder::start::<Self>()
-			.add(control_flow::control_flow_plugin)
+			.add(control_flow::ControlFlowPlugin {
+				schedule: self.schedule,
+			})
 			.add(continue_run::continue_run_plugin)
 			.build()
 	}
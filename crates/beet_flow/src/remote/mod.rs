@@ -0,0 +1,9 @@
+//! A json protocol for inspecting and driving running trees,
+//! useful for debugging agents on headless servers and robots.
+//! See [`BeetRemotePlugin`] for usage.
+mod remote_client;
+mod remote_plugin;
+mod remote_protocol;
+pub use remote_client::*;
+pub use remote_plugin::*;
+pub use remote_protocol::*;
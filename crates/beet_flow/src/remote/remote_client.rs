@@ -0,0 +1,61 @@
+use crate::prelude::*;
+use anyhow::Result;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+
+/// A minimal blocking client for the [`BeetRemotePlugin`],
+/// useful for tests and command line tools.
+pub struct RemoteClient {
+	reader: BufReader<TcpStream>,
+	writer: TcpStream,
+	/// Partially received line, kept between calls to [`Self::try_recv`].
+	pending: String,
+}
+
+impl RemoteClient {
+	/// Connect to a [`BeetRemotePlugin`] server.
+	pub fn connect(address: impl ToSocketAddrs) -> Result<Self> {
+		let writer = TcpStream::connect(address)?;
+		writer.set_read_timeout(Some(Duration::from_millis(1)))?;
+		let reader = BufReader::new(writer.try_clone()?);
+		Ok(Self {
+			reader,
+			writer,
+			pending: String::new(),
+		})
+	}
+
+	/// Send a request to the server.
+	pub fn send(&mut self, request: &RemoteRequest) -> Result<()> {
+		let mut line = serde_json::to_string(request)?;
+		line.push('\n');
+		self.writer.write_all(line.as_bytes())?;
+		Ok(())
+	}
+
+	/// Check for a message without blocking, returning `None` if a
+	/// complete message has not yet been received.
+	pub fn try_recv(&mut self) -> Result<Option<RemoteMessage>> {
+		match self.reader.read_line(&mut self.pending) {
+			Ok(0) => anyhow::bail!("remote server closed the connection"),
+			Ok(_) => {}
+			Err(err)
+				if matches!(
+					err.kind(),
+					ErrorKind::WouldBlock | ErrorKind::TimedOut
+				) => {}
+			Err(err) => return Err(err.into()),
+		}
+		if !self.pending.ends_with('\n') {
+			return Ok(None);
+		}
+		let line = std::mem::take(&mut self.pending);
+		Ok(Some(serde_json::from_str(&line)?))
+	}
+}
@@ -0,0 +1,414 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use flume::Receiver;
+use flume::Sender;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+
+
+/// A plugin for inspecting and driving trees from another process,
+/// using newline delimited json over a local tcp socket.
+/// See [`RemoteRequest`] for the supported methods and [`RemoteClient`]
+/// for a minimal client.
+///
+/// The [`BeetDebugPlugin`] is required for log streaming, if it has not
+/// already been added it will be added with [`BeetDebugPlugin::with_none`],
+/// so if using it directly it should be added before this plugin.
+///
+/// If the address cannot be bound the error is logged and the plugin
+/// is inactive, so the [`RemoteServer`] resource will not exist.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// let mut app = App::new();
+/// app.add_plugins((
+/// 	BeetFlowPlugin::default(),
+/// 	// use port 0 to let the os pick a free port
+/// 	BeetRemotePlugin::new(([127, 0, 0, 1], 0)),
+/// ));
+/// let address = app.world().resource::<RemoteServer>().address();
+/// ```
+#[derive(Debug, Clone)]
+pub struct BeetRemotePlugin {
+	/// The address to listen on, this should usually be a loopback address.
+	pub address: SocketAddr,
+}
+
+impl Default for BeetRemotePlugin {
+	fn default() -> Self { Self::new(([127, 0, 0, 1], Self::DEFAULT_PORT)) }
+}
+
+impl BeetRemotePlugin {
+	/// The default port, one above the bevy remote protocol default.
+	pub const DEFAULT_PORT: u16 = 15703;
+
+	/// Create a new [`BeetRemotePlugin`] listening on the provided address.
+	pub fn new(address: impl Into<SocketAddr>) -> Self {
+		Self {
+			address: address.into(),
+		}
+	}
+}
+
+impl Plugin for BeetRemotePlugin {
+	fn build(&self, app: &mut App) {
+		let listener = match TcpListener::bind(self.address) {
+			Ok(listener) => listener,
+			Err(err) => {
+				log::error!(
+					"BeetRemotePlugin failed to bind {}, the plugin will be inactive: {err}",
+					self.address
+				);
+				return;
+			}
+		};
+		let address = listener.local_addr().unwrap_or(self.address);
+		let (send, recv) = flume::unbounded();
+		std::thread::spawn(move || listen(listener, send));
+
+		if !app.is_plugin_added::<BeetDebugPlugin>() {
+			app.add_plugins(BeetDebugPlugin::with_none());
+		}
+
+		app.insert_resource(RemoteServer {
+			address,
+			requests: recv,
+			subscribers: Vec::new(),
+		})
+		.add_systems(PreUpdate, handle_remote_requests)
+		.add_systems(Last, stream_log_messages)
+		.add_observer(stream_results);
+	}
+}
+
+/// Tracks the connection to all remote clients,
+/// added by the [`BeetRemotePlugin`].
+#[derive(Resource)]
+pub struct RemoteServer {
+	address: SocketAddr,
+	requests: Receiver<RemoteEnvelope>,
+	subscribers: Vec<Sender<RemoteMessage>>,
+}
+
+impl RemoteServer {
+	/// The address the server is listening on,
+	/// useful when binding to port 0.
+	pub fn address(&self) -> SocketAddr { self.address }
+
+	/// Send a message to every client that has sent a
+	/// [`RemoteRequest::Subscribe`], dropping any disconnected clients.
+	pub fn broadcast(&mut self, msg: RemoteMessage) {
+		self.subscribers
+			.retain(|subscriber| subscriber.send(msg.clone()).is_ok());
+	}
+}
+
+/// A request paired with the channel of the client that sent it.
+struct RemoteEnvelope {
+	request: RemoteRequest,
+	reply: Sender<RemoteMessage>,
+}
+
+fn listen(listener: TcpListener, requests: Sender<RemoteEnvelope>) {
+	for stream in listener.incoming() {
+		match stream {
+			Ok(stream) => {
+				let requests = requests.clone();
+				std::thread::spawn(move || handle_connection(stream, requests));
+			}
+			Err(err) => log::warn!("remote connection failed: {err}"),
+		}
+	}
+}
+
+/// Each connection has a reader on this thread and a writer
+/// on its own thread, so that streamed messages are not blocked
+/// waiting for requests.
+fn handle_connection(
+	stream: TcpStream,
+	requests: Sender<RemoteEnvelope>,
+) -> std::io::Result<()> {
+	let (reply, outgoing) = flume::unbounded::<RemoteMessage>();
+	let mut writer = stream.try_clone()?;
+	std::thread::spawn(move || {
+		for msg in outgoing.iter() {
+			let Ok(mut line) = serde_json::to_string(&msg) else {
+				continue;
+			};
+			line.push('\n');
+			if writer.write_all(line.as_bytes()).is_err() {
+				break;
+			}
+		}
+	});
+
+	for line in BufReader::new(stream).lines() {
+		let line = line?;
+		if line.trim().is_empty() {
+			continue;
+		}
+		match serde_json::from_str::<RemoteRequest>(&line) {
+			Ok(request) => {
+				let envelope = RemoteEnvelope {
+					request,
+					reply: reply.clone(),
+				};
+				if requests.send(envelope).is_err() {
+					// the app has exited
+					break;
+				}
+			}
+			Err(err) => {
+				reply
+					.send(RemoteMessage::Error(format!(
+						"invalid request: {err}"
+					)))
+					.ok();
+			}
+		}
+	}
+	Ok(())
+}
+
+fn handle_remote_requests(world: &mut World) {
+	let envelopes = world
+		.resource::<RemoteServer>()
+		.requests
+		.try_iter()
+		.collect::<Vec<_>>();
+
+	for RemoteEnvelope { request, reply } in envelopes {
+		let response = match request {
+			RemoteRequest::ListTrees => RemoteMessage::Trees(list_trees(world)),
+			RemoteRequest::Run { name } => run_named(world, &name),
+			RemoteRequest::SetDebug {
+				on_run,
+				on_result,
+				running,
+			} => {
+				toggle_resource::<DebugOnRun>(world, on_run);
+				toggle_resource::<DebugOnResult>(world, on_result);
+				toggle_resource::<DebugRunning>(world, running);
+				RemoteMessage::Ok
+			}
+			RemoteRequest::Subscribe => {
+				world
+					.resource_mut::<RemoteServer>()
+					.subscribers
+					.push(reply.clone());
+				RemoteMessage::Ok
+			}
+		};
+		reply.send(response).ok();
+	}
+}
+
+/// Collect every action without an action parent, sorted by name.
+fn list_trees(world: &mut World) -> Vec<TreeNode<RemoteNode>> {
+	let mut query = world
+		.query_filtered::<(Entity, Option<&Parent>), With<ActionObservers>>();
+	let world: &World = world;
+	let mut trees = query
+		.iter(world)
+		.filter(|(_, parent)| {
+			parent
+				.map(|parent| {
+					world.get::<ActionObservers>(parent.get()).is_none()
+				})
+				.unwrap_or(true)
+		})
+		.map(|(root, _)| {
+			EntityTree::new_with_world(root, world)
+				.map(|entity| remote_node(world, *entity))
+		})
+		.collect::<Vec<_>>();
+	trees.sort_by(|a, b| a.value.name.cmp(&b.value.name));
+	trees
+}
+
+fn remote_node(world: &World, entity: Entity) -> RemoteNode {
+	RemoteNode {
+		name: world
+			.get::<Name>(entity)
+			.map(|name| name.to_string())
+			.unwrap_or_else(|| entity.to_string()),
+		entity: entity.to_bits(),
+		running: world.get::<Running>(entity).is_some(),
+	}
+}
+
+fn run_named(world: &mut World, name: &str) -> RemoteMessage {
+	let actions = world
+		.query_filtered::<(Entity, &Name), With<ActionObservers>>()
+		.iter(world)
+		.filter(|(_, action_name)| action_name.as_str() == name)
+		.map(|(entity, _)| entity)
+		.collect::<Vec<_>>();
+	if actions.is_empty() {
+		return RemoteMessage::Error(format!("no action named {name}"));
+	}
+	for action in actions {
+		world.trigger(OnRun::global(action));
+	}
+	world.flush();
+	RemoteMessage::Ok
+}
+
+fn toggle_resource<T: Resource + Default>(world: &mut World, enabled: bool) {
+	if enabled {
+		world.init_resource::<T>();
+	} else {
+		world.remove_resource::<T>();
+	}
+}
+
+fn stream_log_messages(
+	mut events: EventReader<OnLogMessage>,
+	mut server: ResMut<RemoteServer>,
+) {
+	for ev in events.read() {
+		server.broadcast(RemoteMessage::Log(ev.msg.to_string()));
+	}
+}
+
+fn stream_results(
+	ev: Trigger<OnResultAction>,
+	names: Query<&Name>,
+	mut server: ResMut<RemoteServer>,
+) {
+	if server.subscribers.is_empty() {
+		return;
+	}
+	let action = ev.resolve_action();
+	server.broadcast(RemoteMessage::Result {
+		action: names
+			.get(action)
+			.map(|name| name.to_string())
+			.unwrap_or_else(|_| action.to_string()),
+		payload: format!("{:?}", ev.payload),
+		reason: ev.reason.as_ref().map(|reason| reason.to_string()),
+	});
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use sweet::prelude::*;
+
+	/// Update the app until the client receives a message.
+	fn recv(app: &mut App, client: &mut RemoteClient) -> RemoteMessage {
+		for _ in 0..1000 {
+			app.update();
+			if let Some(msg) = client.try_recv().unwrap() {
+				return msg;
+			}
+		}
+		panic!("timed out waiting for remote message");
+	}
+
+	fn setup() -> (App, RemoteClient) {
+		let mut app = App::new();
+		app.add_plugins((
+			BeetFlowPlugin::default(),
+			BeetRemotePlugin::new(([127, 0, 0, 1], 0)),
+		));
+		let address = app.world().resource::<RemoteServer>().address();
+		let client = RemoteClient::connect(address).unwrap();
+		(app, client)
+	}
+
+	#[test]
+	fn address_in_use() {
+		let (app, _client) = setup();
+		let address = app.world().resource::<RemoteServer>().address();
+		let mut app2 = App::new();
+		app2.add_plugins((
+			BeetFlowPlugin::default(),
+			BeetRemotePlugin::new(address),
+		));
+		expect(app2.world().contains_resource::<RemoteServer>()).to_be_false();
+	}
+
+	#[test]
+	fn list_trees() {
+		let (mut app, mut client) = setup();
+		let mut child = Entity::PLACEHOLDER;
+		let root = app
+			.world_mut()
+			.spawn((Name::new("root"), Sequence))
+			.with_children(|parent| {
+				child = parent
+					.spawn((Name::new("child"), ReturnWith(RunResult::Success)))
+					.id();
+			})
+			.id();
+
+		client.send(&RemoteRequest::ListTrees).unwrap();
+		expect(recv(&mut app, &mut client)).to_be(RemoteMessage::Trees(vec![
+			TreeNode::new(RemoteNode {
+				name: "root".into(),
+				entity: root.to_bits(),
+				running: false,
+			})
+			.with_leaf(RemoteNode {
+				name: "child".into(),
+				entity: child.to_bits(),
+				running: false,
+			}),
+		]));
+	}
+
+	#[test]
+	fn run_and_stream() {
+		let (mut app, mut client) = setup();
+		app.world_mut()
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("child"), ReturnWith(RunResult::Success)));
+
+		client.send(&RemoteRequest::Subscribe).unwrap();
+		expect(recv(&mut app, &mut client)).to_be(RemoteMessage::Ok);
+		client
+			.send(&RemoteRequest::SetDebug {
+				on_run: true,
+				on_result: false,
+				running: false,
+			})
+			.unwrap();
+		expect(recv(&mut app, &mut client)).to_be(RemoteMessage::Ok);
+
+		client
+			.send(&RemoteRequest::Run {
+				name: "root".into(),
+			})
+			.unwrap();
+		expect(recv(&mut app, &mut client)).to_be(RemoteMessage::Result {
+			action: "child".into(),
+			payload: "Success".into(),
+			reason: None,
+		});
+		expect(recv(&mut app, &mut client)).to_be(RemoteMessage::Result {
+			action: "root".into(),
+			payload: "Success".into(),
+			reason: None,
+		});
+		expect(recv(&mut app, &mut client)).to_be(RemoteMessage::Ok);
+		expect(recv(&mut app, &mut client))
+			.to_be(RemoteMessage::Log("OnRun: root".into()));
+		expect(recv(&mut app, &mut client))
+			.to_be(RemoteMessage::Log("OnRun: child".into()));
+
+		client
+			.send(&RemoteRequest::Run {
+				name: "missing".into(),
+			})
+			.unwrap();
+		expect(recv(&mut app, &mut client))
+			.to_be(RemoteMessage::Error("no action named missing".into()));
+	}
+}
@@ -0,0 +1,73 @@
+use crate::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+
+/// A request sent by a remote client, each request is a single line
+/// of json terminated by a newline.
+/// ## Example
+/// ```json
+/// {"method":"run","params":{"name":"My Behavior"}}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum RemoteRequest {
+	/// List every action tree by [`Name`](bevy::prelude::Name),
+	/// responds with [`RemoteMessage::Trees`].
+	ListTrees,
+	/// Trigger [`OnRunAction`] on every action with the provided name,
+	/// responds with [`RemoteMessage::Ok`] or [`RemoteMessage::Error`]
+	/// if no action was found.
+	Run {
+		/// The [`Name`](bevy::prelude::Name) of the action to run.
+		name: String,
+	},
+	/// Toggle the [`BeetDebugPlugin`] logging resources.
+	SetDebug {
+		/// Toggle [`DebugOnRun`]
+		on_run: bool,
+		/// Toggle [`DebugOnResult`]
+		on_result: bool,
+		/// Toggle [`DebugRunning`]
+		running: bool,
+	},
+	/// Start streaming [`RemoteMessage::Log`] and
+	/// [`RemoteMessage::Result`] messages to this client.
+	Subscribe,
+}
+
+/// A message sent from the app to a remote client, either in response
+/// to a [`RemoteRequest`] or streamed after a [`RemoteRequest::Subscribe`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum RemoteMessage {
+	/// The request was handled successfully.
+	Ok,
+	/// The request could not be parsed or handled.
+	Error(String),
+	/// Response to [`RemoteRequest::ListTrees`].
+	Trees(Vec<TreeNode<RemoteNode>>),
+	/// A streamed [`OnLogMessage`].
+	Log(String),
+	/// A streamed [`OnResultAction`].
+	Result {
+		/// The name of the action, or its entity if unnamed.
+		action: String,
+		/// The debug representation of the payload.
+		payload: String,
+		/// The [`ResultReason`], if any.
+		reason: Option<String>,
+	},
+}
+
+/// A summary of a single action in a [`RemoteMessage::Trees`] response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteNode {
+	/// The name of the action, or its entity if unnamed.
+	pub name: String,
+	/// The [`Entity::to_bits`](bevy::prelude::Entity::to_bits) representation
+	/// of the action.
+	pub entity: u64,
+	/// Whether the action currently has a [`Running`] component.
+	pub running: bool,
+}
@@ -0,0 +1,346 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+
+/// A headless [`App`] with the [`BeetFlowPlugin`] and a manual [`Time`],
+/// recording every [`OnRunAction`] and [`OnResultAction`] by [`Name`]
+/// so that actions can be tested deterministically.
+///
+/// Assertions panic with a line by line diff, where `-` is expected
+/// and `+` is received.
+/// ## Example
+/// ```
+/// # use beet_flow::doctest::*;
+/// let mut harness = FlowTestHarness::new();
+/// let root = harness
+/// 	.spawn((Name::new("root"), Sequence))
+/// 	.with_child((Name::new("child"), ReturnWith(RunResult::Success)))
+/// 	.id();
+/// harness
+/// 	.run(root)
+/// 	.tick()
+/// 	.assert_runs(&["root", "child"])
+/// 	.assert_running(&[])
+/// 	.assert_outcome(root, RunResult::Success);
+/// ```
+pub struct FlowTestHarness {
+	/// The app being tested, use this to add plugins or resources.
+	pub app: App,
+	/// The duration to advance [`Time`] by for each [`Self::tick`].
+	pub delta: Duration,
+	runs: Arc<Mutex<Vec<String>>>,
+	results: Arc<Mutex<Vec<(Entity, String, RunResult)>>>,
+}
+
+impl Default for FlowTestHarness {
+	fn default() -> Self { Self::new() }
+}
+
+impl FlowTestHarness {
+	/// Create a new harness with a tick delta of 1/60th of a second.
+	pub fn new() -> Self {
+		let mut app = App::new();
+		app.add_plugins(BeetFlowPlugin::default())
+			.insert_resource(Time::<()>::default());
+
+		let runs = Arc::<Mutex<Vec<String>>>::default();
+		let results = Arc::<Mutex<Vec<(Entity, String, RunResult)>>>::default();
+
+		let runs2 = runs.clone();
+		app.world_mut().add_observer(
+			move |ev: Trigger<OnRunAction>, names: Query<&Name>| {
+				let action = ev.resolve_action();
+				runs2.lock().unwrap().push(name_or_entity(action, &names));
+			},
+		);
+		let results2 = results.clone();
+		app.world_mut().add_observer(
+			move |ev: Trigger<OnResultAction>, names: Query<&Name>| {
+				let action = ev.resolve_action();
+				results2.lock().unwrap().push((
+					action,
+					name_or_entity(action, &names),
+					ev.payload.clone(),
+				));
+			},
+		);
+		app.world_mut().flush();
+
+		Self {
+			app,
+			delta: Duration::from_secs_f64(1. / 60.),
+			runs,
+			results,
+		}
+	}
+
+	/// Set the duration that [`Time`] advances by for each tick.
+	pub fn with_delta(mut self, delta: Duration) -> Self {
+		self.delta = delta;
+		self
+	}
+
+	/// Get the world of the app.
+	pub fn world(&self) -> &World { self.app.world() }
+	/// Get the world of the app mutably.
+	pub fn world_mut(&mut self) -> &mut World { self.app.world_mut() }
+
+	/// Spawn an entity, usually the root of a tree.
+	pub fn spawn(&mut self, bundle: impl Bundle) -> EntityWorldMut<'_> {
+		self.app.world_mut().spawn(bundle)
+	}
+
+	/// Trigger [`OnRunAction`] on the provided action and apply
+	/// any resulting commands.
+	pub fn run(&mut self, action: Entity) -> &mut Self {
+		let world = self.app.world_mut();
+		world.flush();
+		world.trigger(OnRun::global(action));
+		world.flush();
+		self
+	}
+
+	/// Trigger [`OnRunAction`] on the first action with the provided name.
+	/// # Panics
+	/// If no entity with the provided name exists.
+	#[track_caller]
+	pub fn run_named(&mut self, name: &str) -> &mut Self {
+		let action = self.find(name);
+		self.run(action)
+	}
+
+	/// Find the first entity with the provided name.
+	/// # Panics
+	/// If no entity with the provided name exists.
+	#[track_caller]
+	pub fn find(&mut self, name: &str) -> Entity {
+		self.app
+			.world_mut()
+			.query::<(Entity, &Name)>()
+			.iter(self.app.world())
+			.find(|(_, entity_name)| entity_name.as_str() == name)
+			.map(|(entity, _)| entity)
+			.unwrap_or_else(|| panic!("no entity named {name}"))
+	}
+
+	/// Advance [`Time`] by [`Self::delta`] and update the app.
+	pub fn tick(&mut self) -> &mut Self {
+		let delta = self.delta;
+		self.advance(delta)
+	}
+
+	/// Call [`Self::tick`] `n` times.
+	pub fn tick_n(&mut self, n: usize) -> &mut Self {
+		for _ in 0..n {
+			self.tick();
+		}
+		self
+	}
+
+	/// Advance [`Time`] by the provided seconds in a single update.
+	pub fn advance_secs(&mut self, secs: f32) -> &mut Self {
+		self.advance(Duration::from_secs_f32(secs))
+	}
+
+	/// Advance [`Time`] by the provided duration in a single update.
+	pub fn advance(&mut self, duration: Duration) -> &mut Self {
+		self.app
+			.world_mut()
+			.resource_mut::<Time>()
+			.advance_by(duration);
+		self.app.update();
+		self
+	}
+
+	/// The name of every action that has been run, in order.
+	pub fn runs(&self) -> Vec<String> { self.runs.lock().unwrap().clone() }
+
+	/// The name and payload of every result, in order.
+	pub fn results(&self) -> Vec<(String, RunResult)> {
+		self.results
+			.lock()
+			.unwrap()
+			.iter()
+			.map(|(_, name, result)| (name.clone(), result.clone()))
+			.collect()
+	}
+
+	/// The sorted names of every entity with a [`Running`] component.
+	pub fn running(&mut self) -> Vec<String> {
+		let world = self.app.world_mut();
+		let mut running = world
+			.query_filtered::<(Entity, Option<&Name>), With<Running>>()
+			.iter(world)
+			.map(|(entity, name)| {
+				name.map(|name| name.to_string())
+					.unwrap_or_else(|| entity.to_string())
+			})
+			.collect::<Vec<_>>();
+		running.sort();
+		running
+	}
+
+	/// The most recent result for the provided action, if any.
+	pub fn outcome(&self, action: Entity) -> Option<RunResult> {
+		self.results
+			.lock()
+			.unwrap()
+			.iter()
+			.rev()
+			.find(|(entity, _, _)| *entity == action)
+			.map(|(_, _, result)| result.clone())
+	}
+
+	/// Clear all recorded runs and results.
+	pub fn clear(&mut self) -> &mut Self {
+		self.runs.lock().unwrap().clear();
+		self.results.lock().unwrap().clear();
+		self
+	}
+
+	/// Assert the name of every action that has been run, in order.
+	#[track_caller]
+	pub fn assert_runs(&mut self, expected: &[&str]) -> &mut Self {
+		assert_diff("runs", expected, &self.runs());
+		self
+	}
+
+	/// Assert the name and payload of every result, in order.
+	#[track_caller]
+	pub fn assert_results(
+		&mut self,
+		expected: &[(&str, RunResult)],
+	) -> &mut Self {
+		let expected = expected
+			.iter()
+			.map(|(name, result)| (name.to_string(), result.clone()))
+			.collect::<Vec<_>>();
+		assert_diff("results", &expected, &self.results());
+		self
+	}
+
+	/// Assert the names of every entity with a [`Running`] component,
+	/// in any order.
+	#[track_caller]
+	pub fn assert_running(&mut self, expected: &[&str]) -> &mut Self {
+		let mut expected = expected.to_vec();
+		expected.sort();
+		assert_diff("running", &expected, &self.running());
+		self
+	}
+
+	/// Assert the most recent result of the provided action.
+	#[track_caller]
+	pub fn assert_outcome(
+		&mut self,
+		action: Entity,
+		expected: RunResult,
+	) -> &mut Self {
+		let received = self.outcome(action);
+		if received.as_ref() != Some(&expected) {
+			panic!(
+				"outcome of {action} did not match\n  - {:?}\n  + {:?}",
+				Some(expected),
+				received
+			);
+		}
+		self
+	}
+}
+
+fn name_or_entity(entity: Entity, names: &Query<&Name>) -> String {
+	names
+		.get(entity)
+		.map(|name| name.to_string())
+		.unwrap_or_else(|_| entity.to_string())
+}
+
+/// Panic with a line by line diff if the values do not match.
+#[track_caller]
+fn assert_diff<A, B>(label: &str, expected: &[A], received: &[B])
+where
+	A: Debug + PartialEq<B>,
+	B: Debug,
+{
+	let matches = expected.len() == received.len()
+		&& expected.iter().zip(received).all(|(a, b)| a == b);
+	if matches {
+		return;
+	}
+	let mut msg = format!("{label} did not match, - expected + received\n");
+	for i in 0..expected.len().max(received.len()) {
+		match (expected.get(i), received.get(i)) {
+			(Some(a), Some(b)) if a == b => {
+				msg.push_str(&format!("    {a:?}\n"));
+			}
+			(a, b) => {
+				if let Some(a) = a {
+					msg.push_str(&format!("  - {a:?}\n"));
+				}
+				if let Some(b) = b {
+					msg.push_str(&format!("  + {b:?}\n"));
+				}
+			}
+		}
+	}
+	panic!("{msg}");
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use bevy::prelude::*;
+	use std::time::Duration;
+	use sweet::prelude::*;
+
+	#[test]
+	fn works() {
+		let mut harness = FlowTestHarness::new();
+		let root = harness
+			.spawn((Name::new("root"), Sequence))
+			.with_child((Name::new("child1"), ReturnWith(RunResult::Success)))
+			.with_child((
+				Name::new("child2"),
+				ReturnInDuration::new(
+					RunResult::Failure,
+					Duration::from_secs(1),
+				),
+			))
+			.id();
+
+		harness
+			.run(root)
+			.assert_runs(&["root", "child1", "child2"])
+			.assert_running(&["child2"])
+			.tick_n(30)
+			.assert_running(&["child2"])
+			.advance_secs(1.)
+			.assert_running(&[])
+			.assert_results(&[
+				("child1", RunResult::Success),
+				("child2", RunResult::Failure),
+				("root", RunResult::Failure),
+			])
+			.assert_outcome(root, RunResult::Failure);
+	}
+
+	#[test]
+	fn diff() {
+		let mut harness = FlowTestHarness::new();
+		harness.spawn((Name::new("root"), ReturnWith(RunResult::Success)));
+		harness.run_named("root");
+		let err =
+			std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+				harness.assert_runs(&["root", "child"]);
+			}))
+			.unwrap_err();
+		expect(err.downcast_ref::<String>().unwrap().as_str()).to_be(
+			"runs did not match, - expected + received\n    \"root\"\n  - \"child\"\n",
+		);
+	}
+}
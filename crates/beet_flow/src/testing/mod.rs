@@ -0,0 +1,3 @@
+//! Utilities for testing actions without a window or real clock.
+mod flow_test_harness;
+pub use flow_test_harness::*;
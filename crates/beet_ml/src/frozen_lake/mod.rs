@@ -47,14 +47,15 @@ pub struct FrozenLakePlugin;
 
 impl Plugin for FrozenLakePlugin {
 	fn build(&self, app: &mut App) {
+		let schedule = app.tick_schedule();
 		app.add_plugins((
 			RlSessionPlugin::<FrozenLakeEpParams>::default(),
 		))
-		.add_systems(Update, (
+		.add_systems(schedule, (
 			translate_grid.in_set(TickSet),
 			reward_grid.in_set(PostTickSet)))
 		.add_systems(
-			Update,
+			schedule,
 			spawn_frozen_lake_episode
 			// (spawn_frozen_lake_session, spawn_frozen_lake_episode)
 			.in_set(PostTickSet),
@@ -47,8 +47,9 @@ pub struct RlSessionPlugin<T: EpisodeParams> {
 
 impl<T: EpisodeParams> Plugin for RlSessionPlugin<T> {
 	fn build(&self, app: &mut App) {
+		let schedule = app.tick_schedule();
 		app.add_systems(
-			Update,
+			schedule,
 			(
 				start_session::<T>.in_set(PreTickSet),
 				handle_episode_end::<T>.in_set(PostTickSet),
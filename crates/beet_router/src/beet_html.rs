@@ -25,12 +25,15 @@ impl BeetHtml {
 
 
 		// apply the template to the app
-		let fresh_app = template_map.apply_template(stale_app)?;
+		let mut fresh_app = template_map.apply_template(stale_app)?;
 
 		// build the doc and save it, the web server will detect a change
 		// and reload the page.
-		let mut doc = RsxToResumableHtml::default().map_root(&fresh_app);
-		doc.insert_wasm_script();
+		let mut doc = RsxToResumableHtml::default().map_root(&mut fresh_app);
+		// islands are loaded by their own wasm entry
+		if fresh_app.client_islands().is_empty() {
+			doc.insert_wasm_script();
+		}
 		let html = doc.render();
 		FsExt::write(dst, &html)?;
 		Ok(())
@@ -35,6 +35,11 @@ pub struct StaticFileRouter<T> {
 	/// The directory to save the html files to
 	pub dst_dir: PathBuf,
 	pub templates_src: PathBuf,
+	/// Extract styles into deduplicated css files in the [Self::dst_dir],
+	/// instead of inlining them in every page, see `StyleSheets`.
+	/// This requires the `css` feature, [Self::routes_to_html_files]
+	/// will return an error if it is set without it.
+	pub extract_styles: bool,
 }
 
 impl<T: Default> Default for StaticFileRouter<T> {
@@ -45,6 +50,7 @@ impl<T: Default> Default for StaticFileRouter<T> {
 			dst_dir: "target/client".into(),
 			// use the default from BuildRsxTemplateMap
 			templates_src: BuildRsxTemplateMap::DEFAULT_TEMPLATES_DST.into(),
+			extract_styles: false,
 		}
 	}
 }
@@ -75,11 +81,36 @@ impl<T: 'static> StaticFileRouter<T> {
 	}
 
 
-	/// try applying templates, otherwise warn and use
-	/// the compiled rsx
+	/// Calls [Self::routes_to_resolved_rsx] and builds each document
 	pub async fn routes_to_html(
 		&self,
 	) -> Result<Vec<(RouteInfo, HtmlDocument)>> {
+		self.routes_to_resolved_rsx()
+			.await?
+			.into_iter()
+			.map(|(route, root)| Ok((route, root.build_document()?)))
+			.collect()
+	}
+
+	/// Calls [Self::routes_to_resolved_rsx] and builds each document,
+	/// extracting styles into the [StyleSheets]
+	#[cfg(feature = "css")]
+	pub async fn routes_to_html_with_styles(
+		&self,
+		sheets: &mut StyleSheets,
+	) -> Result<Vec<(RouteInfo, HtmlDocument)>> {
+		self.routes_to_resolved_rsx()
+			.await?
+			.into_iter()
+			.map(|(route, root)| Ok((route, sheets.build_document(root)?)))
+			.collect()
+	}
+
+	/// try applying templates, otherwise warn and use
+	/// the compiled rsx, then resolve any suspended content
+	pub async fn routes_to_resolved_rsx(
+		&self,
+	) -> Result<Vec<(RouteInfo, RsxRoot)>> {
 		// if we can't load templates just warn and run without template reload
 		let mut template_map = RsxTemplateMap::load(&self.templates_src)
 			.map_err(|err| {
@@ -92,24 +123,22 @@ impl<T: 'static> StaticFileRouter<T> {
 			})
 			.ok();
 
-		let html = self
-			.routes_to_rsx()
-			.await?
-			.into_iter()
-			.map(|(route, mut root)| {
-				// only hydrate if we have templates
-				// we already warned otherwise
-				if let Some(map) = &mut template_map {
-					root = map.apply_template(root)?;
-				}
-				let doc = root.build_document()?;
-				Ok((route, doc))
-			})
-			.collect::<Result<Vec<(RouteInfo, HtmlDocument)>>>()?;
-		Ok(html)
+		let mut roots = Vec::new();
+		for (route, mut root) in self.routes_to_rsx().await? {
+			// only hydrate if we have templates
+			// we already warned otherwise
+			if let Some(map) = &mut template_map {
+				root = map.apply_template(root)?;
+			}
+			// components may await data, see [`AsyncComponent`]
+			let root = root.resolve_suspense().await;
+			roots.push((route, root));
+		}
+		Ok(roots)
 	}
 
-	/// Calls [Self::routes_to_html] and writes the html to disk
+	/// Calls [Self::routes_to_html] and writes the html to disk,
+	/// along with any extracted styles
 	pub async fn routes_to_html_files(&self) -> Result<()> {
 		let dst = &self.dst_dir;
 		// in debug mode removing a watched dir breaks FsWatcher
@@ -118,7 +147,26 @@ impl<T: 'static> StaticFileRouter<T> {
 		std::fs::create_dir_all(&dst)?;
 
 		let dst = dst.canonicalize()?;
-		for (info, doc) in self.routes_to_html().await? {
+
+		#[cfg(feature = "css")]
+		let docs = if self.extract_styles {
+			let mut sheets = StyleSheets::default();
+			let docs = self.routes_to_html_with_styles(&mut sheets).await?;
+			for (path, css) in sheets.iter() {
+				FsExt::write(&dst.join(path), css)?;
+			}
+			docs
+		} else {
+			self.routes_to_html().await?
+		};
+		#[cfg(not(feature = "css"))]
+		let docs = if self.extract_styles {
+			anyhow::bail!("extract_styles requires the css feature");
+		} else {
+			self.routes_to_html().await?
+		};
+
+		for (info, doc) in docs {
 			let mut path = info.path.clone();
 			// map foo/index.rs to foo/index.html
 			if path.file_stem().map(|s| s == "index").unwrap_or(false) {
@@ -0,0 +1,386 @@
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::parse_quote;
+use syn::DeriveInput;
+use syn::Expr;
+use syn::GenericArgument;
+use syn::Ident;
+use syn::PathArguments;
+use syn::Result;
+use syn::Type;
+
+pub fn derive_props(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as DeriveInput);
+	parse_derive_props(input)
+		.unwrap_or_else(|err| err.to_compile_error())
+		.into()
+}
+
+/// How a field is set by the builder
+enum PropKind {
+	/// Must be set, tracked by a generic state param
+	Required { state: Ident, marker: Ident },
+	/// An `Option<T>`, the setter accepts `T`
+	Optional { inner: Type },
+	/// Uses the default expression if not set
+	Default { expr: Expr },
+}
+
+struct PropField {
+	ident: Ident,
+	ty: Type,
+	into: bool,
+	kind: PropKind,
+	docs: Vec<syn::Attribute>,
+}
+
+impl PropField {
+	/// The type accepted by the setter
+	fn setter_ty(&self) -> TokenStream {
+		let ty = match &self.kind {
+			PropKind::Optional { inner } => inner,
+			_ => &self.ty,
+		};
+		if self.into {
+			quote!(impl Into<#ty>)
+		} else {
+			quote!(#ty)
+		}
+	}
+	fn setter_value(&self) -> TokenStream {
+		if self.into {
+			quote!(value.into())
+		} else {
+			quote!(value)
+		}
+	}
+}
+
+fn parse_derive_props(input: DeriveInput) -> Result<TokenStream> {
+	let ident = &input.ident;
+	let vis = &input.vis;
+	let fields = match &input.data {
+		syn::Data::Struct(data) => match &data.fields {
+			syn::Fields::Named(fields) => fields.named.iter().collect(),
+			syn::Fields::Unit => Vec::new(),
+			syn::Fields::Unnamed(_) => {
+				return Err(syn::Error::new_spanned(
+					&input.ident,
+					"Props can only be derived for structs with named fields, or unit structs",
+				));
+			}
+		},
+		_ => {
+			return Err(syn::Error::new_spanned(
+				&input.ident,
+				"Props can only be derived for structs",
+			));
+		}
+	};
+	let fields = fields
+		.into_iter()
+		.map(|field| parse_field(ident, field))
+		.collect::<Result<Vec<_>>>()?;
+
+	let builder = format_ident!("{}Builder", ident);
+	let (impl_generics, ty_generics, where_clause) =
+		input.generics.split_for_impl();
+	let generic_params = input.generics.params.iter().collect::<Vec<_>>();
+	let generic_args = input
+		.generics
+		.params
+		.iter()
+		.map(|param| match param {
+			syn::GenericParam::Lifetime(param) => {
+				let lifetime = &param.lifetime;
+				quote!(#lifetime)
+			}
+			syn::GenericParam::Type(param) => {
+				let ident = &param.ident;
+				quote!(#ident)
+			}
+			syn::GenericParam::Const(param) => {
+				let ident = &param.ident;
+				quote!(#ident)
+			}
+		})
+		.collect::<Vec<_>>();
+	let where_preds = where_clause
+		.map(|clause| clause.predicates.iter().collect::<Vec<_>>())
+		.unwrap_or_default();
+
+	let states = fields
+		.iter()
+		.filter_map(|field| match &field.kind {
+			PropKind::Required { state, .. } => Some(state.clone()),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+
+	// the builder type with each state replaced by the provided tokens
+	let builder_ty = |replace: &dyn Fn(&Ident) -> TokenStream| {
+		let states = states.iter().map(replace);
+		quote!(#builder<#(#generic_args,)* #(#states,)*>)
+	};
+
+	let builder_fields = fields.iter().map(|field| {
+		let PropField { ident, ty, .. } = field;
+		match &field.kind {
+			PropKind::Required { state, .. } => quote!(#ident: #state),
+			_ => quote!(#ident: Option<#ty>),
+		}
+	});
+
+	let markers = fields.iter().filter_map(|field| {
+		let PropKind::Required { marker, .. } = &field.kind else {
+			return None;
+		};
+		let message = format!(
+			"missing required prop `{}` for component `{}`",
+			field.ident, ident
+		);
+		let label = format!("missing prop `{}`", field.ident);
+		Some(quote! {
+			#[doc(hidden)]
+			#[allow(non_camel_case_types)]
+			#[diagnostic::on_unimplemented(message = #message, label = #label)]
+			#vis trait #marker<T> {
+				fn into_prop(self) -> T;
+			}
+			impl<T> #marker<T> for (T,) {
+				fn into_prop(self) -> T { self.0 }
+			}
+		})
+	});
+
+	let setters = fields.iter().map(|field| {
+		let PropField {
+			ident: field_ident,
+			ty,
+			docs,
+			..
+		} = field;
+		let setter_ty = field.setter_ty();
+		let value = field.setter_value();
+		match &field.kind {
+			PropKind::Required { state: current, .. } => {
+				let other_states =
+					states.iter().filter(|state| *state != current);
+				let unset = builder_ty(&|state| {
+					if state == current {
+						quote!(())
+					} else {
+						quote!(#state)
+					}
+				});
+				let set = builder_ty(&|state| {
+					if state == current {
+						quote!((#ty,))
+					} else {
+						quote!(#state)
+					}
+				});
+				let moved = fields.iter().map(|other| {
+					let other = &other.ident;
+					if other == field_ident {
+						quote!(#other: (#value,))
+					} else {
+						quote!(#other: self.#other)
+					}
+				});
+				quote! {
+					impl<#(#generic_params,)* #(#other_states,)*> #unset
+					where #(#where_preds,)*
+					{
+						#(#docs)*
+						pub fn #field_ident(self, value: #setter_ty) -> #set {
+							#builder {
+								#(#moved,)*
+								__props_phantom: std::marker::PhantomData,
+							}
+						}
+					}
+				}
+			}
+			kind => {
+				let value = match kind {
+					PropKind::Optional { .. } => quote!(Some(#value)),
+					_ => value,
+				};
+				let any = builder_ty(&|state| quote!(#state));
+				quote! {
+					impl<#(#generic_params,)* #(#states,)*> #any
+					where #(#where_preds,)*
+					{
+						#(#docs)*
+						pub fn #field_ident(mut self, value: #setter_ty) -> Self {
+							self.#field_ident = Some(#value);
+							self
+						}
+					}
+				}
+			}
+		}
+	});
+
+	let unset_builder = builder_ty(&|_| quote!(()));
+	let unset_fields = fields.iter().map(|field| {
+		let ident = &field.ident;
+		match &field.kind {
+			PropKind::Required { .. } => quote!(#ident: ()),
+			_ => quote!(#ident: None),
+		}
+	});
+
+	let any_builder = builder_ty(&|state| quote!(#state));
+	let state_bounds = fields.iter().filter_map(|field| {
+		let PropKind::Required { state, marker } = &field.kind else {
+			return None;
+		};
+		let ty = &field.ty;
+		Some(quote!(#state: #marker<#ty>))
+	});
+	let built_fields = fields.iter().map(|field| {
+		let ident = &field.ident;
+		match &field.kind {
+			PropKind::Required { .. } => {
+				quote!(#ident: self.#ident.into_prop())
+			}
+			PropKind::Optional { .. } => quote!(#ident: self.#ident.flatten()),
+			PropKind::Default { expr } => {
+				quote!(#ident: self.#ident.unwrap_or_else(|| #expr))
+			}
+		}
+	});
+	let build_body = if matches!(&input.data, syn::Data::Struct(data) if matches!(data.fields, syn::Fields::Unit))
+	{
+		quote!(#ident)
+	} else {
+		quote!(#ident { #(#built_fields,)* })
+	};
+
+	let builder_doc = format!(
+		"Builder for [`{}`] props, created with [`Props::builder`](beet::prelude::Props::builder).",
+		ident
+	);
+
+	Ok(quote! {
+		#[doc = #builder_doc]
+		#[allow(non_camel_case_types)]
+		#vis struct #builder<#(#generic_params,)* #(#states,)*>
+		where #(#where_preds,)*
+		{
+			#(#builder_fields,)*
+			__props_phantom: std::marker::PhantomData<fn() -> #ident #ty_generics>,
+		}
+
+		#(#markers)*
+
+		#(#setters)*
+
+		impl #impl_generics beet::prelude::Props for #ident #ty_generics #where_clause {
+			type Builder = #unset_builder;
+			fn builder() -> Self::Builder {
+				#builder {
+					#(#unset_fields,)*
+					__props_phantom: std::marker::PhantomData,
+				}
+			}
+		}
+
+		impl<#(#generic_params,)* #(#states,)*> beet::prelude::PropsBuilder for #any_builder
+		where
+			#(#where_preds,)*
+			#(#state_bounds,)*
+		{
+			type Component = #ident #ty_generics;
+			fn build(self) -> Self::Component {
+				#build_body
+			}
+		}
+	})
+}
+
+fn parse_field(component: &Ident, field: &syn::Field) -> Result<PropField> {
+	let ident = field.ident.clone().expect("named fields have idents");
+	let mut into = false;
+	let mut default = None;
+	for attr in field
+		.attrs
+		.iter()
+		.filter(|attr| attr.path().is_ident("prop"))
+	{
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("into") {
+				into = true;
+				Ok(())
+			} else if meta.path.is_ident("default") {
+				default = Some(if meta.input.peek(syn::Token![=]) {
+					meta.value()?.parse::<Expr>()?
+				} else {
+					parse_quote!(Default::default())
+				});
+				Ok(())
+			} else {
+				Err(meta.error("expected `into`, `default` or `default = ..`"))
+			}
+		})?;
+	}
+	let kind = if let Some(expr) = default {
+		PropKind::Default { expr }
+	} else if let Some(inner) = option_inner(&field.ty) {
+		PropKind::Optional {
+			inner: inner.clone(),
+		}
+	} else {
+		PropKind::Required {
+			state: format_ident!("__{}", pascal_case(&ident.to_string())),
+			marker: format_ident!("__{}_{}", component, ident),
+		}
+	};
+	Ok(PropField {
+		ty: field.ty.clone(),
+		into,
+		kind,
+		docs: field
+			.attrs
+			.iter()
+			.filter(|attr| attr.path().is_ident("doc"))
+			.cloned()
+			.collect(),
+		ident,
+	})
+}
+
+/// Returns the inner type if this is an `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+	let Type::Path(path) = ty else {
+		return None;
+	};
+	let segment = path.path.segments.last()?;
+	if segment.ident != "Option" {
+		return None;
+	}
+	let PathArguments::AngleBracketed(args) = &segment.arguments else {
+		return None;
+	};
+	match args.args.first()? {
+		GenericArgument::Type(ty) => Some(ty),
+		_ => None,
+	}
+}
+
+/// `my_field` -> `MyField`
+fn pascal_case(ident: &str) -> String {
+	ident
+		.split('_')
+		.map(|word| {
+			let mut chars = word.chars();
+			match chars.next() {
+				Some(first) => first.to_uppercase().chain(chars).collect(),
+				None => String::new(),
+			}
+		})
+		.collect()
+}
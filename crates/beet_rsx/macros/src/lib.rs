@@ -1,6 +1,7 @@
 use beet_rsx_parser::prelude::*;
 use proc_macro::TokenStream;
 mod derive_deref;
+mod derive_props;
 
 /// Demonstrates how to select a different reactive runtime
 #[allow(unused_mut)]
@@ -32,6 +33,7 @@ pub fn rsx(tokens: TokenStream) -> TokenStream {
 	RstmlToRsx {
 		// perhaps we can feature gate this if it proves expensive
 		idents: feature_flag_idents(),
+		validate_html: cfg!(feature = "validate"),
 		..Default::default()
 	}
 	.map_tokens(tokens.into())
@@ -51,6 +53,13 @@ pub fn rsx_template(tokens: TokenStream) -> TokenStream {
 
 
 
+/// Generate a builder for the fields of a component,
+/// see [`Props`](beet_rsx::prelude::Props) for usage.
+#[proc_macro_derive(Props, attributes(prop))]
+pub fn derive_props(input: TokenStream) -> TokenStream {
+	derive_props::derive_props(input)
+}
+
 #[proc_macro_derive(Deref)]
 pub fn derive_deref(input: TokenStream) -> TokenStream {
 	derive_deref::derive_deref(input)
@@ -0,0 +1,145 @@
+use rstml::node::KeyedAttribute;
+use rstml::node::NodeAttribute;
+use syn::spanned::Spanned;
+use syn::Expr;
+
+/// A `bind:value`, `bind:checked` or `bind:group` directive, binding a
+/// form element to a signal with a `(get, set)` tuple:
+/// ```ignore
+/// <input bind:value={(get, set)}/>
+/// ```
+/// Both [RstmlToRsx] and [RstmlToRsxTemplate] expand a directive into
+/// two attributes, each with their own rusty tracker:
+/// 1. An attribute block for the getter, ie `Sigfault::parse_bind_value`.
+/// 2. An event for the setter, ie `EventRegistry::register_bind_value`.
+///
+/// As the directive registers the [BindKind::event_key] it is an error for
+/// the element to also have that event attribute.
+pub struct BindDirective {
+	pub kind: BindKind,
+	pub get: Expr,
+	pub set: Expr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindKind {
+	/// `bind:value`, the value of an input, select or textarea
+	Value,
+	/// `bind:checked`, the checked state of a checkbox
+	Checked,
+	/// `bind:group`, a group of radio buttons or checkboxes, each with a `value`
+	Group,
+}
+
+impl BindKind {
+	/// The name used by the runtime and event registry functions,
+	/// ie `parse_bind_value` and `register_bind_value`
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Value => "value",
+			Self::Checked => "checked",
+			Self::Group => "group",
+		}
+	}
+	/// The event that writes back to the signal
+	pub fn event_key(&self) -> &'static str {
+		match self {
+			Self::Value => "oninput",
+			Self::Checked | Self::Group => "onchange",
+		}
+	}
+}
+
+impl BindDirective {
+	/// Returns `None` if the attribute is not a `bind:` directive
+	pub fn parse(attr: &NodeAttribute) -> Option<syn::Result<Self>> {
+		let NodeAttribute::Attribute(attr) = attr else {
+			return None;
+		};
+		let key = attr.key.to_string();
+		let kind = key.strip_prefix("bind:")?;
+		Some(Self::parse_inner(kind, attr))
+	}
+
+	fn parse_inner(kind: &str, attr: &KeyedAttribute) -> syn::Result<Self> {
+		let kind = match kind {
+			"value" => BindKind::Value,
+			"checked" => BindKind::Checked,
+			"group" => BindKind::Group,
+			_ => {
+				return Err(syn::Error::new(
+					attr.key.span(),
+					format!(
+						"Unknown bind directive `bind:{kind}`, expected `bind:value`, `bind:checked` or `bind:group`"
+					),
+				));
+			}
+		};
+		let Some((get, set)) = attr.value().and_then(get_set_tuple) else {
+			return Err(syn::Error::new(
+				attr.span(),
+				format!(
+					"Bind directives expect a getter and setter, ie `bind:{}={{(get, set)}}`",
+					kind.as_str()
+				),
+			));
+		};
+		Ok(Self { kind, get, set })
+	}
+}
+
+/// Get the elements of a `(get, set)` tuple, which may be wrapped in a block
+fn get_set_tuple(expr: &Expr) -> Option<(Expr, Expr)> {
+	match expr {
+		Expr::Block(block) => match block.block.stmts.as_slice() {
+			[syn::Stmt::Expr(expr, None)] => get_set_tuple(expr),
+			_ => None,
+		},
+		Expr::Paren(paren) => get_set_tuple(&paren.expr),
+		Expr::Tuple(tuple) if tuple.elems.len() == 2 => {
+			Some((tuple.elems[0].clone(), tuple.elems[1].clone()))
+		}
+		_ => None,
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use quote::quote;
+	use quote::ToTokens;
+	use rstml::node::Node;
+	use sweet::prelude::*;
+
+	fn parse(
+		tokens: proc_macro2::TokenStream,
+	) -> Option<syn::Result<BindDirective>> {
+		let (nodes, _) = tokens_to_rstml(tokens);
+		let Node::Element(el) = &nodes[0] else {
+			panic!("expected element");
+		};
+		BindDirective::parse(&el.open_tag.attributes[0])
+	}
+
+	#[test]
+	fn works() {
+		expect(parse(quote! {<input value="foo"/>}).is_none()).to_be_true();
+
+		let bind = parse(quote! {<input bind:checked={(get, set)}/>})
+			.unwrap()
+			.unwrap();
+		expect(bind.kind).to_be(BindKind::Checked);
+		expect(bind.get.to_token_stream().to_string()).to_be("get");
+		expect(bind.set.to_token_stream().to_string()).to_be("set");
+
+		expect(
+			parse(quote! {<input bind:foo={(get, set)}/>})
+				.unwrap()
+				.is_err(),
+		)
+		.to_be_true();
+		expect(parse(quote! {<input bind:value={get}/>}).unwrap().is_err())
+			.to_be_true();
+	}
+}
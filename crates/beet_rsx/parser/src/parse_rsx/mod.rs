@@ -1,6 +1,8 @@
+mod bind_directive;
 mod rstml_rust_to_hash;
 mod rstml_to_rsx_template;
 mod rusty_tracker_builder;
+pub use self::bind_directive::*;
 use proc_macro2::Literal;
 use quote::ToTokens;
 pub use rusty_tracker_builder::*;
@@ -9,6 +11,8 @@ pub use self::rstml_rust_to_hash::*;
 #[allow(unused_imports)]
 pub use self::rstml_to_rsx_template::*;
 pub use self::tokens_to_rstml::*;
+mod validate_html;
+pub use self::validate_html::*;
 pub mod rsx_file_visitor;
 #[allow(unused_imports)]
 pub use self::rsx_file_visitor::*;
@@ -21,6 +25,17 @@ use syn::Expr;
 use syn::File;
 
 
+/// Maps the `client:load`, `client:idle` and `client:visible` island
+/// directives to the name of the matching `ClientDirective` variant.
+pub fn client_directive_variant(key: &str) -> Option<&'static str> {
+	match key {
+		"client:load" => Some("Load"),
+		"client:idle" => Some("Idle"),
+		"client:visible" => Some("Visible"),
+		_ => None,
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct RsxIdents {
 	pub mac: syn::Ident,
@@ -46,6 +61,12 @@ impl RsxRuntime {
 			event: syn::parse_quote!(beet::prelude::EventRegistry),
 		}
 	}
+	pub fn reactive() -> Self {
+		Self {
+			effect: syn::parse_quote!(beet::rsx::reactive::ReactiveRuntime),
+			event: syn::parse_quote!(beet::prelude::EventRegistry),
+		}
+	}
 	pub fn bevy() -> Self {
 		Self {
 			effect: syn::parse_quote!(beet::rsx::bevy::BevyRuntime),
@@ -55,10 +76,12 @@ impl RsxRuntime {
 	/// Updates [`Self::effect`] to the given runtime. Built-in runtimes
 	/// have a shorthand:
 	/// - `sigfault` -> `beet::rsx::sigfault::Sigfault`
+	/// - `reactive` -> `beet::rsx::reactive::ReactiveRuntime`
 	/// - `bevy` -> `beet::rsx::bevy::BevyRuntime`
 	pub fn set(&mut self, runtime: &str) -> syn::Result<()> {
 		*self = match runtime {
 			"sigfault" => Self::sigfault(),
+			"reactive" => Self::reactive(),
 			"bevy" => Self::bevy(),
 			_ => {
 				let path: syn::Path = syn::parse_str(&runtime)?;
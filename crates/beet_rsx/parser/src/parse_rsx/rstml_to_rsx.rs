@@ -41,6 +41,8 @@ pub struct RstmlToRsx {
 	pub self_closing_elements: HashSet<&'static str>,
 	pub rusty_tracker: RustyTrackerBuilder,
 	pub idx_incr: TokensRsxIdxIncr,
+	/// Emit compile errors for invalid html, see [HtmlValidator]
+	pub validate_html: bool,
 }
 
 impl RstmlToRsx {
@@ -53,6 +55,14 @@ impl RstmlToRsx {
 
 	pub fn map_tokens(&mut self, tokens: TokenStream) -> TokenStream {
 		let (nodes, rstml_errors) = tokens_to_rstml(tokens.clone());
+		if self.validate_html {
+			self.errors.extend(
+				HtmlValidator::validate_void_close_tags(&tokens)
+					.into_iter()
+					.chain(HtmlValidator::validate(&nodes))
+					.map(|err| err.to_compile_error()),
+			);
+		}
 		let node = self.map_nodes(nodes);
 
 		let location = macro_location_tokens(tokens);
@@ -159,25 +169,83 @@ impl RstmlToRsx {
 				if tag.starts_with(|c: char| c.is_uppercase()) {
 					self.map_component(idx, tag, open_tag, children)
 				} else {
-					let attributes = open_tag
-						.attributes
-						.into_iter()
-						.map(|attr| self.map_attribute(attr))
-						.collect::<Vec<_>>();
+					let (locals, attributes) =
+						self.map_attributes(open_tag.attributes);
 					let children = self.map_nodes(children);
-					quote!(RsxNode::Element(RsxElement {
+					let element = quote!(RsxNode::Element(RsxElement {
 						idx: #idx,
 						tag: #tag.to_string(),
 						attributes: vec![#(#attributes),*],
 						children: Box::new(#children),
 						self_closing: #self_closing,
-					}))
+					}));
+					if locals.is_empty() {
+						element
+					} else {
+						quote!({
+							#(#locals)*
+							#element
+						})
+					}
 				}
 			}
 			Node::Custom(_) => unimplemented!("Custom nodes not yet supported"),
 		}
 	}
 
+	/// Returns the `let` statements for values shared by the expanded
+	/// attributes, and the attributes themselves.
+	fn map_attributes(
+		&mut self,
+		attrs: Vec<NodeAttribute>,
+	) -> (Vec<TokenStream>, Vec<TokenStream>) {
+		// the value of the element is used by `bind:group`
+		let value = attrs.iter().find_map(|attr| match attr {
+			NodeAttribute::Attribute(attr)
+				if attr.key.to_string() == "value" =>
+			{
+				attr.value().map(|value| value.to_token_stream())
+			}
+			_ => None,
+		});
+		// bind directives register their own event
+		let keys = attrs
+			.iter()
+			.filter_map(|attr| match attr {
+				NodeAttribute::Attribute(attr) => Some(attr.key.to_string()),
+				_ => None,
+			})
+			.collect::<HashSet<_>>();
+		let mut locals = Vec::new();
+		let attributes = attrs
+			.into_iter()
+			.flat_map(|attr| match BindDirective::parse(&attr) {
+				Some(Ok(bind)) if keys.contains(bind.kind.event_key()) => {
+					let message = format!(
+						"`bind:{}` registers an `{}` event so it cannot be used with an `{}` attribute",
+						bind.kind.as_str(),
+						bind.kind.event_key(),
+						bind.kind.event_key()
+					);
+					self.errors.push(
+						syn::Error::new(attr.span(), message)
+							.to_compile_error(),
+					);
+					vec![]
+				}
+				Some(Ok(bind)) => {
+					self.map_bind_directive(bind, value.as_ref(), &mut locals)
+				}
+				Some(Err(err)) => {
+					self.errors.push(err.to_compile_error());
+					vec![]
+				}
+				None => vec![self.map_attribute(attr)],
+			})
+			.collect();
+		(locals, attributes)
+	}
+
 	fn map_attribute(&mut self, attr: NodeAttribute) -> TokenStream {
 		let ident = &self.idents.runtime.effect;
 		match attr {
@@ -241,48 +309,215 @@ impl RstmlToRsx {
 			}
 		}
 	}
+	/// Expand a [BindDirective] into an attribute block created by the
+	/// runtime and an event registered by the event registry.
+	fn map_bind_directive(
+		&mut self,
+		bind: BindDirective,
+		value: Option<&TokenStream>,
+		locals: &mut Vec<TokenStream>,
+	) -> Vec<TokenStream> {
+		let BindDirective { kind, get, set } = bind;
+		let get_tracker = self.rusty_tracker.next_tracker(&get);
+		let set_tracker = self.rusty_tracker.next_tracker(&set);
+		let effect = &self.idents.runtime.effect;
+		let event_registry = &self.idents.runtime.event;
+		let event_key = kind.event_key();
+		let parse_func = syn::Ident::new(
+			&format!("parse_bind_{}", kind.as_str()),
+			get.span(),
+		);
+		let register_func = syn::Ident::new(
+			&format!("register_bind_{}", kind.as_str()),
+			set.span(),
+		);
+		let (parse, register) = match kind {
+			BindKind::Value | BindKind::Checked => (
+				quote! { #effect::#parse_func(#get_tracker, #get) },
+				quote! { #event_registry::#register_func(#event_key, cx, #set) },
+			),
+			BindKind::Group => {
+				let Some(value) = value else {
+					let diagnostic = Diagnostic::spanned(
+						get.span(),
+						Level::Error,
+						"`bind:group` elements must have a `value` attribute",
+					);
+					self.errors.push(diagnostic.emit_as_expr_tokens());
+					return vec![];
+				};
+				// the getter is also used to toggle checkbox groups,
+				// so both are evaluated once and shared by the attributes
+				locals.push(quote! {
+					let bind_group_get = #get;
+					let bind_group_value = (#value).to_string();
+				});
+				(
+					quote! {
+						#effect::#parse_func(
+							#get_tracker,
+							bind_group_get.clone(),
+							bind_group_value.clone()
+						)
+					},
+					quote! {
+						#event_registry::#register_func(
+							#event_key,
+							cx,
+							bind_group_value,
+							bind_group_get,
+							#set
+						)
+					},
+				)
+			}
+		};
+		vec![parse, quote! {
+			RsxAttribute::BlockValue {
+				key: #event_key.to_string(),
+				initial: "event-placeholder".to_string(),
+				effect: Effect::new(Box::new(move |cx| {
+					#register;
+					Ok(())
+				}), #set_tracker)
+			}
+		}]
+	}
+
 	fn map_component<C>(
 		&mut self,
 		idx: TokenStream,
 		tag: String,
-		open_tag: OpenTag,
+		mut open_tag: OpenTag,
 		children: Vec<Node<C>>,
 	) -> TokenStream {
 		let tracker = self.rusty_tracker.next_tracker(&open_tag);
-		let props = open_tag.attributes.into_iter().map(|attr| match attr {
-			NodeAttribute::Block(node_block) => {
-				quote! {#node_block}
-			}
-			NodeAttribute::Attribute(attr) => {
-				if let Some(value) = attr.value() {
-					let key = &attr.key;
-					// apply the value to the field
-					quote! {
-						#key: #value
-					}
-				} else {
-					let key = &attr.key;
-					// for components a key is treated as a bool 'flag'
-					quote! {#key: true}
-				}
-			}
-		});
+		let client_directive =
+			self.parse_client_directive(&mut open_tag.attributes);
+		let props = open_tag
+			.attributes
+			.into_iter()
+			.filter_map(|attr| self.map_component_prop(attr))
+			.collect::<Vec<_>>();
 		let ident = syn::Ident::new(&tag, tag.span());
 		let slot_children = self.map_nodes(children);
+		// props are set via the builder so missing and unknown props
+		// are compile errors, see `beet::prelude::Props`.
+		// the context scope is exited after the slot children are built
+		// so they can use the context provided by the component
 		quote!({
+			let _context = beet::prelude::RsxContext::enter_scope();
 			RsxNode::Component(RsxComponent{
 				idx: #idx,
 				tag: #tag.to_string(),
 				tracker: #tracker,
-				root: Box::new(#ident{
-					#(#props,)*
-				}
-				.render()),
+				root: Box::new(beet::prelude::PropsBuilder::build(
+					#ident::builder()
+					#(#props)*
+				)
+				.render()
+				.into_root()),
+				client_directive: #client_directive,
 				slot_children: Box::new(#slot_children)
 			})
 		})
 	}
 
+	/// Map a component attribute to a builder method call:
+	/// - `key=value` -> `.key(value)`
+	/// - `key` -> `.key(true)`, keys are treated as a bool 'flag'
+	/// - `{key}` -> `.key(key)`, a shorthand for `key={key}`
+	fn map_component_prop(
+		&mut self,
+		attr: NodeAttribute,
+	) -> Option<TokenStream> {
+		let (key, value) = match attr {
+			NodeAttribute::Block(block) => {
+				let key = syn::parse2::<syn::Block>(block.to_token_stream())
+					.ok()
+					.and_then(|block| match block.stmts.as_slice() {
+						[syn::Stmt::Expr(syn::Expr::Path(path), None)] => {
+							path.path.get_ident().cloned()
+						}
+						_ => None,
+					});
+				match key {
+					Some(key) => (key.clone(), key.to_token_stream()),
+					None => {
+						let diagnostic = Diagnostic::spanned(
+							block.span(),
+							Level::Error,
+							"Component attribute blocks must be a single identifier, ie {value}",
+						);
+						self.errors.push(diagnostic.emit_as_expr_tokens());
+						return None;
+					}
+				}
+			}
+			NodeAttribute::Attribute(attr) => {
+				let key =
+					match syn::parse2::<syn::Ident>(attr.key.to_token_stream())
+					{
+						Ok(key) => key,
+						Err(_) => {
+							let diagnostic = Diagnostic::spanned(
+								attr.key.span(),
+								Level::Error,
+								format!(
+								"Invalid prop name `{}`, component props must be valid identifiers",
+								attr.key
+							),
+							);
+							self.errors.push(diagnostic.emit_as_expr_tokens());
+							return None;
+						}
+					};
+				let value = match attr.value() {
+					Some(value) => value.to_token_stream(),
+					None => quote!(true),
+				};
+				(key, value)
+			}
+		};
+		Some(quote! {.#key(#value)})
+	}
+
+	/// Remove any `client:` island directive from the component attributes
+	/// so it is not treated as a prop, see `beet::prelude::ClientDirective`.
+	fn parse_client_directive(
+		&mut self,
+		attrs: &mut Vec<NodeAttribute>,
+	) -> TokenStream {
+		let mut directive = quote!(None);
+		attrs.retain(|attr| {
+			let NodeAttribute::Attribute(attr) = attr else {
+				return true;
+			};
+			let key = attr.key.to_string();
+			if !key.starts_with("client:") {
+				return true;
+			}
+			match client_directive_variant(&key) {
+				Some(variant) => {
+					let variant = syn::Ident::new(variant, attr.key.span());
+					directive = quote!(Some(ClientDirective::#variant));
+				}
+				None => {
+					let diagnostic = Diagnostic::spanned(
+						attr.key.span(),
+						Level::Error,
+						format!(
+							"Unknown client directive `{key}`, expected `client:load`, `client:idle` or `client:visible`"
+						),
+					);
+					self.errors.push(diagnostic.emit_as_expr_tokens());
+				}
+			}
+			false
+		});
+		directive
+	}
+
 	/// Ensure that self-closing elements do not have children.
 	fn check_self_closing_children<C>(&mut self, element: &NodeElement<C>) {
 		if element.children.is_empty()
@@ -145,7 +145,12 @@ impl RstmlToRsxTemplate {
 					let attributes = open_tag
 						.attributes
 						.into_iter()
-						.map(|a| self.map_attribute(a))
+						.flat_map(|attr| match BindDirective::parse(&attr) {
+							Some(Ok(bind)) => self.map_bind_directive(bind),
+							// errors are reported by RstmlToRsx
+							Some(Err(_)) => vec![],
+							None => vec![self.map_attribute(attr)],
+						})
 						.collect::<Vec<_>>();
 					let children = self.map_nodes(children);
 					quote! { Element (
@@ -194,6 +199,18 @@ impl RstmlToRsxTemplate {
 			}
 		}
 	}
+	/// Must match [RstmlToRsx], an attribute block for the getter
+	/// and an event for the setter.
+	fn map_bind_directive(&mut self, bind: BindDirective) -> Vec<TokenStream> {
+		let get_tracker = self.rusty_tracker.next_tracker_ron(&bind.get);
+		let set_tracker = self.rusty_tracker.next_tracker_ron(&bind.set);
+		let event_key = bind.kind.event_key();
+		vec![quote! { Block (#get_tracker)}, quote! { BlockValue (
+			key: #event_key,
+			tracker: #set_tracker
+		)}]
+	}
+
 	fn map_component<C: CustomNode>(
 		&mut self,
 		idx: TokenStream,
@@ -203,13 +220,29 @@ impl RstmlToRsxTemplate {
 	) -> TokenStream {
 		let tracker = self.rusty_tracker.next_tracker_ron(&open_tag);
 		// components disregard all the context and rely on the tracker
-		// we rely on the hydrated node to provide the attributes and children
+		// we rely on the hydrated node to provide the attributes and children,
+		// aside from the island directive which is static
+		let client_directive = open_tag
+			.attributes
+			.iter()
+			.find_map(|attr| match attr {
+				NodeAttribute::Attribute(attr) => {
+					client_directive_variant(&attr.key.to_string())
+				}
+				_ => None,
+			})
+			.map(|variant| {
+				let variant = syn::Ident::new(variant, open_tag.span());
+				quote!(Some(#variant))
+			})
+			.unwrap_or(quote!(None));
 		let slot_children = self.map_nodes(children);
 
 		quote! { Component (
 			idx: #idx,
 			tracker: #tracker,
 			tag: #tag,
+			client_directive: #client_directive,
 			slot_children: #slot_children
 		)}
 	}
@@ -0,0 +1,530 @@
+use crate::prelude::*;
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use rstml::node::Node;
+use rstml::node::NodeAttribute;
+use rstml::node::NodeElement;
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+
+/// Validate rstml nodes against the html element model, returning an
+/// error at the span of each problem:
+/// - void elements like `<img>` with children
+/// - unknown lowercase tags, ignoring components and custom elements
+/// - duplicate attributes
+/// - unknown event attributes, ie `onclck`
+/// - invalid nesting, ie `<li>` outside of a list or `<div>` in a `<p>`
+///
+/// Custom elements are those containing a `-`. Void elements are parsed
+/// as self closing so their children are found with
+/// [Self::validate_void_close_tags].
+/// The contents of `<svg>` and `<math>` are not validated, and
+/// nesting is not checked across component boundaries because the
+/// parent of slot children is not known until render.
+#[derive(Debug)]
+pub struct HtmlValidator {
+	void_elements: HashSet<&'static str>,
+	errors: Vec<syn::Error>,
+}
+
+impl Default for HtmlValidator {
+	fn default() -> Self {
+		Self {
+			void_elements: self_closing_elements(),
+			errors: Vec::new(),
+		}
+	}
+}
+
+impl HtmlValidator {
+	/// Validate the nodes of an `rsx!` macro
+	pub fn validate<C>(nodes: &[Node<C>]) -> Vec<syn::Error> {
+		let mut this = Self::default();
+		this.validate_nodes(nodes, &[]);
+		this.errors
+	}
+
+	/// Void elements are always parsed as self closing, so any children
+	/// become siblings followed by an unmatched close tag, ie `</br>`.
+	/// rstml reports these as a wrong close tag, so find them in the
+	/// tokens for a clearer error.
+	pub fn validate_void_close_tags(tokens: &TokenStream) -> Vec<syn::Error> {
+		let void_elements = self_closing_elements();
+		let tokens = tokens.clone().into_iter().collect::<Vec<_>>();
+		tokens
+			.windows(3)
+			.filter_map(|window| match window {
+				[TokenTree::Punct(open), TokenTree::Punct(slash), TokenTree::Ident(tag)]
+					if open.as_char() == '<'
+						&& slash.as_char() == '/'
+						&& void_elements.contains(tag.to_string().as_str()) =>
+				{
+					Some(syn::Error::new(
+						tag.span(),
+						format!(
+							"`<{tag}>` is a void element and cannot have children or a close tag"
+						),
+					))
+				}
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// `ancestors` are the tags of the parent elements, with the direct
+	/// parent last. It is empty at the root and in slot children.
+	fn validate_nodes<C>(&mut self, nodes: &[Node<C>], ancestors: &[String]) {
+		for node in nodes {
+			match node {
+				Node::Element(el) => self.validate_element(el, ancestors),
+				Node::Fragment(fragment) => {
+					self.validate_nodes(&fragment.children, ancestors)
+				}
+				_ => {}
+			}
+		}
+	}
+
+	fn validate_element<C>(
+		&mut self,
+		el: &NodeElement<C>,
+		ancestors: &[String],
+	) {
+		let tag = el.open_tag.name.to_string();
+		let span = el.open_tag.name.span();
+		self.validate_attributes(el, &tag);
+		if tag.starts_with(|c: char| c.is_uppercase()) {
+			// slot children
+			self.validate_nodes(&el.children, &[]);
+			return;
+		}
+		if tag.contains('-') {
+			return;
+		}
+		if !HTML_ELEMENTS.contains(&tag.as_str()) {
+			self.error(span, format!("Unknown html element `<{tag}>`"));
+			return;
+		}
+		if self.void_elements.contains(tag.as_str()) && !el.children.is_empty()
+		{
+			self.error(
+				span,
+				format!("`<{tag}>` is a void element and cannot have children"),
+			);
+		}
+		if let Some(message) = invalid_nesting(&tag, ancestors) {
+			self.error(span, message);
+		}
+		if tag == "svg" || tag == "math" {
+			return;
+		}
+		let mut ancestors = ancestors.to_vec();
+		ancestors.push(tag);
+		self.validate_nodes(&el.children, &ancestors);
+	}
+
+	fn validate_attributes<C>(&mut self, el: &NodeElement<C>, tag: &str) {
+		let is_element = !tag.starts_with(|c: char| c.is_uppercase());
+		let mut keys = HashSet::new();
+		for attr in el.open_tag.attributes.iter() {
+			let NodeAttribute::Attribute(attr) = attr else {
+				continue;
+			};
+			let key = attr.key.to_string();
+			if !keys.insert(key.clone()) {
+				self.error(
+					attr.key.span(),
+					format!("Duplicate attribute `{key}`"),
+				);
+			}
+			if is_element
+				&& key.starts_with("on")
+				&& !EVENT_ATTRIBUTES.contains(&key.as_str())
+			{
+				self.error(attr.key.span(), format!("Unknown event `{key}`"));
+			}
+		}
+	}
+
+	fn error(&mut self, span: proc_macro2::Span, message: String) {
+		self.errors.push(syn::Error::new(span, message));
+	}
+}
+
+/// Returns an error message if the element cannot be a child
+/// of its parent, or a descendant of one of its ancestors.
+fn invalid_nesting(tag: &str, ancestors: &[String]) -> Option<String> {
+	let parent = ancestors.last().map(|parent| parent.as_str());
+	// elements that may only be direct children of specific elements,
+	// only checked when the parent is known
+	let allowed_parents: &[&str] = match tag {
+		"li" => &["ul", "ol", "menu"],
+		"dt" | "dd" => &["dl", "div"],
+		"tr" => &["table", "thead", "tbody", "tfoot"],
+		"td" | "th" => &["tr"],
+		"thead" | "tbody" | "tfoot" | "caption" | "colgroup" => &["table"],
+		"col" => &["colgroup"],
+		"option" => &["select", "datalist", "optgroup"],
+		"optgroup" => &["select"],
+		"figcaption" => &["figure"],
+		"legend" => &["fieldset"],
+		"summary" => &["details"],
+		"head" | "body" => &["html"],
+		_ => &[],
+	};
+	if let Some(parent) = parent {
+		if !allowed_parents.is_empty() && !allowed_parents.contains(&parent) {
+			return Some(format!(
+				"`<{tag}>` cannot be a child of `<{parent}>`, expected one of {}",
+				allowed_parents
+					.iter()
+					.map(|tag| format!("`<{tag}>`"))
+					.collect::<Vec<_>>()
+					.join(", ")
+			));
+		}
+	}
+	if tag == "html" && !ancestors.is_empty() {
+		return Some("`<html>` must be the root element".into());
+	}
+	if parent == Some("p") && BLOCK_ELEMENTS.contains(&tag) {
+		return Some(format!("`<{tag}>` cannot be a child of `<p>`"));
+	}
+	// interactive elements cannot be nested
+	if matches!(tag, "a" | "button" | "form" | "label")
+		&& ancestors.iter().any(|ancestor| ancestor == tag)
+	{
+		return Some(format!("`<{tag}>` cannot be nested inside `<{tag}>`"));
+	}
+	None
+}
+
+/// Elements that close a `<p>`, so are not allowed inside one
+const BLOCK_ELEMENTS: &[&str] = &[
+	"address",
+	"article",
+	"aside",
+	"blockquote",
+	"details",
+	"dialog",
+	"div",
+	"dl",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"header",
+	"hgroup",
+	"hr",
+	"main",
+	"menu",
+	"nav",
+	"ol",
+	"p",
+	"pre",
+	"search",
+	"section",
+	"table",
+	"ul",
+];
+
+/// All html elements, `<svg>` and `<math>` are included but their
+/// children are not validated. `<slot>` is used for component children.
+const HTML_ELEMENTS: &[&str] = &[
+	"a",
+	"abbr",
+	"address",
+	"area",
+	"article",
+	"aside",
+	"audio",
+	"b",
+	"base",
+	"bdi",
+	"bdo",
+	"blockquote",
+	"body",
+	"br",
+	"button",
+	"canvas",
+	"caption",
+	"cite",
+	"code",
+	"col",
+	"colgroup",
+	"data",
+	"datalist",
+	"dd",
+	"del",
+	"details",
+	"dfn",
+	"dialog",
+	"div",
+	"dl",
+	"dt",
+	"em",
+	"embed",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"head",
+	"header",
+	"hgroup",
+	"hr",
+	"html",
+	"i",
+	"iframe",
+	"img",
+	"input",
+	"ins",
+	"kbd",
+	"label",
+	"legend",
+	"li",
+	"link",
+	"main",
+	"map",
+	"mark",
+	"math",
+	"menu",
+	"meta",
+	"meter",
+	"nav",
+	"noscript",
+	"object",
+	"ol",
+	"optgroup",
+	"option",
+	"output",
+	"p",
+	"param",
+	"picture",
+	"pre",
+	"progress",
+	"q",
+	"rp",
+	"rt",
+	"ruby",
+	"s",
+	"samp",
+	"script",
+	"search",
+	"section",
+	"select",
+	"slot",
+	"small",
+	"source",
+	"span",
+	"strong",
+	"style",
+	"sub",
+	"summary",
+	"sup",
+	"svg",
+	"table",
+	"tbody",
+	"td",
+	"template",
+	"textarea",
+	"tfoot",
+	"th",
+	"thead",
+	"time",
+	"title",
+	"tr",
+	"track",
+	"u",
+	"ul",
+	"var",
+	"video",
+	"wbr",
+];
+
+/// Event handler attributes supported by html elements, the macro emits
+/// `EventRegistry::register_{key}` for each so this list must be kept
+/// in sync with `for_each_event!` in `beet_rsx::dom`.
+const EVENT_ATTRIBUTES: &[&str] = &[
+	"onabort",
+	"onanimationend",
+	"onanimationiteration",
+	"onanimationstart",
+	"onauxclick",
+	"onbeforeinput",
+	"onbeforetoggle",
+	"onblur",
+	"oncancel",
+	"oncanplay",
+	"oncanplaythrough",
+	"onchange",
+	"onclick",
+	"onclose",
+	"oncontextmenu",
+	"oncopy",
+	"oncuechange",
+	"oncut",
+	"ondblclick",
+	"ondrag",
+	"ondragend",
+	"ondragenter",
+	"ondragleave",
+	"ondragover",
+	"ondragstart",
+	"ondrop",
+	"ondurationchange",
+	"onemptied",
+	"onended",
+	"onerror",
+	"onfocus",
+	"onfocusin",
+	"onfocusout",
+	"onformdata",
+	"oninput",
+	"oninvalid",
+	"onkeydown",
+	"onkeypress",
+	"onkeyup",
+	"onload",
+	"onloadeddata",
+	"onloadedmetadata",
+	"onloadstart",
+	"onmousedown",
+	"onmouseenter",
+	"onmouseleave",
+	"onmousemove",
+	"onmouseout",
+	"onmouseover",
+	"onmouseup",
+	"onpaste",
+	"onpause",
+	"onplay",
+	"onplaying",
+	"onpointercancel",
+	"onpointerdown",
+	"onpointerenter",
+	"onpointerleave",
+	"onpointermove",
+	"onpointerout",
+	"onpointerover",
+	"onpointerup",
+	"onprogress",
+	"onratechange",
+	"onreset",
+	"onresize",
+	"onscroll",
+	"onscrollend",
+	"onseeked",
+	"onseeking",
+	"onselect",
+	"onslotchange",
+	"onstalled",
+	"onsubmit",
+	"onsuspend",
+	"ontimeupdate",
+	"ontoggle",
+	"ontouchcancel",
+	"ontouchend",
+	"ontouchmove",
+	"ontouchstart",
+	"ontransitionend",
+	"onvolumechange",
+	"onwaiting",
+	"onwheel",
+];
+
+
+#[cfg(test)]
+mod test {
+	use crate::prelude::*;
+	use quote::quote;
+	use sweet::prelude::*;
+
+	fn validate(tokens: proc_macro2::TokenStream) -> Vec<String> {
+		let (nodes, _) = tokens_to_rstml(tokens);
+		HtmlValidator::validate(&nodes)
+			.into_iter()
+			.map(|err| err.to_string())
+			.collect()
+	}
+
+	#[test]
+	fn valid() {
+		expect(validate(quote! {
+			<html>
+				<head><title>hello</title></head>
+				<body>
+					<ul><li>one</li></ul>
+					<p>hello <b>world</b><img src="foo"/></p>
+					<table><tbody><tr><td>cell</td></tr></tbody></table>
+					<my-element unknown />
+					<svg><path d="M0"/></svg>
+					<MyComponent><li>slotted</li></MyComponent>
+					<button onclick={on_click}>click</button>
+				</body>
+			</html>
+		}))
+		.to_be(Vec::<String>::new());
+	}
+
+	#[test]
+	fn unknown_tags() {
+		expect(validate(quote! { <div><dvi/></div> }))
+			.to_be(vec!["Unknown html element `<dvi>`".to_string()]);
+	}
+
+	#[test]
+	fn void_elements() {
+		let tokens = quote! { <div><br>child</br><img/></div> };
+		expect(
+			HtmlValidator::validate_void_close_tags(&tokens)
+				.into_iter()
+				.map(|err| err.to_string())
+				.collect::<Vec<_>>(),
+		)
+		.to_be(vec![
+			"`<br>` is a void element and cannot have children or a close tag"
+				.to_string(),
+		]);
+	}
+
+	#[test]
+	fn attributes() {
+		expect(validate(quote! { <div class="a" class="b" onclck={foo}/> }))
+			.to_be(vec![
+				"Duplicate attribute `class`".to_string(),
+				"Unknown event `onclck`".to_string(),
+			]);
+	}
+
+	#[test]
+	fn nesting() {
+		expect(validate(quote! {
+			<div>
+				<li>one</li>
+				<p><div/></p>
+				<a><span><a/></span></a>
+				<table><td/></table>
+			</div>
+		}))
+		.to_be(vec![
+			"`<li>` cannot be a child of `<div>`, expected one of `<ul>`, `<ol>`, `<menu>`".to_string(),
+			"`<div>` cannot be a child of `<p>`".to_string(),
+			"`<a>` cannot be nested inside `<a>`".to_string(),
+			"`<td>` cannot be a child of `<table>`, expected one of `<tr>`".to_string(),
+		]);
+	}
+}
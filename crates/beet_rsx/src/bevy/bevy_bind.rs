@@ -0,0 +1,166 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use bevy::reflect::GetTypeRegistration;
+use bevy::reflect::Typed;
+
+/// The value of an entity with a `bind:value` directive,
+/// kept in sync with the signal.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct InputValue {
+	pub value: String,
+}
+
+/// The checked state of an entity with a `bind:checked`
+/// or `bind:group` directive, kept in sync with the signal.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Default, Component)]
+pub struct Checked {
+	pub checked: bool,
+}
+
+impl BevyRuntime {
+	/// Used by [`RstmlToRsx`] when it encounters a `bind:value` directive,
+	/// inserting an [InputValue] that is updated with the signal.
+	pub fn parse_bind_value<T: SignalPayload + BindValue>(
+		tracker: RustyTracker,
+		get: SignalGetter<T>,
+	) -> RsxAttribute {
+		Self::parse_bind(tracker, get, |value| InputValue {
+			value: value.to_bind_value(),
+		})
+	}
+
+	/// Used by [`RstmlToRsx`] when it encounters a `bind:checked` directive,
+	/// inserting a [Checked] that is updated with the signal.
+	pub fn parse_bind_checked(
+		tracker: RustyTracker,
+		get: SignalGetter<bool>,
+	) -> RsxAttribute {
+		Self::parse_bind(tracker, get, |checked| Checked { checked: *checked })
+	}
+
+	/// Used by [`RstmlToRsx`] when it encounters a `bind:group` directive,
+	/// where `value` is the value of the entity.
+	pub fn parse_bind_group<T: SignalPayload + BindGroup>(
+		tracker: RustyTracker,
+		get: SignalGetter<T>,
+		value: String,
+	) -> RsxAttribute {
+		Self::parse_bind(tracker, get, move |group| Checked {
+			checked: group.is_checked(&value),
+		})
+	}
+
+	/// Create an attribute block for the component, which is inserted
+	/// into the entity whenever the signal changes.
+	fn parse_bind<T, C>(
+		tracker: RustyTracker,
+		mut get: SignalGetter<T>,
+		map: impl 'static + Send + Sync + Fn(&T) -> C,
+	) -> RsxAttribute
+	where
+		T: SignalPayload,
+		C: bevy::prelude::Component + Reflect + Typed + GetTypeRegistration,
+	{
+		Self::with_mut(|app| {
+			app.register_type::<C>();
+		});
+		let initial = Self::serialize(&map(&get.get())).unwrap();
+		RsxAttribute::Block {
+			initial: vec![RsxAttribute::KeyValue {
+				key: C::short_type_path().to_string(),
+				value: initial,
+			}],
+			effect: Effect::new(
+				Box::new(move |loc| {
+					Self::with_mut(move |app| {
+						app.world_mut().entity_mut(get.entity).observe(
+							move |ev: Trigger<BevySignal<T>>,
+							      query: Query<(Entity, &TreeIdx)>,
+							      mut commands: Commands| {
+								let entity = TreeIdx::find(query.iter(), loc)
+									.unwrap_or_else(|| {
+										panic!(
+											"{}",
+											expect_rsx_element::to_be_at_location(
+												&loc
+											)
+										)
+									});
+								commands
+									.entity(entity)
+									.insert(map(&ev.event().value));
+							},
+						);
+						app.world_mut().flush();
+					});
+					Ok(())
+				}),
+				tracker,
+			),
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	fn initial(attr: RsxAttribute) -> (String, String) {
+		let RsxAttribute::Block { mut initial, .. } = attr else {
+			panic!()
+		};
+		let Some(RsxAttribute::KeyValue { key, value }) = initial.pop() else {
+			panic!()
+		};
+		(key, value)
+	}
+
+	#[test]
+	fn initial_values() {
+		BevyRuntime::reset();
+		let (get, _set) = BevySignal::signal(1.5_f32);
+		expect(initial(BevyRuntime::parse_bind_value(
+			RustyTracker::new(0, 0),
+			get,
+		)))
+		.to_be(("InputValue".to_string(), "(value:\"1.5\")".to_string()));
+
+		let (get, _set) = BevySignal::signal(vec![1, 2]);
+		expect(initial(BevyRuntime::parse_bind_group(
+			RustyTracker::new(0, 0),
+			get,
+			"3".to_string(),
+		)))
+		.to_be(("Checked".to_string(), "(checked:false)".to_string()));
+	}
+
+	#[test]
+	fn updates() {
+		BevyRuntime::reset();
+		let (get, set) = BevySignal::signal(true);
+		let attr =
+			BevyRuntime::parse_bind_checked(RustyTracker::new(0, 0), get);
+		let rsx = RsxRoot {
+			location: Default::default(),
+			node: RsxNode::Element(RsxElement {
+				idx: 0,
+				tag: "entity".to_string(),
+				attributes: vec![attr],
+				children: Default::default(),
+				self_closing: true,
+			}),
+		};
+		let entity = RsxToBevy::spawn(rsx).unwrap()[0];
+		set(false);
+
+		let mut app = BevyRuntime::take();
+		// flush signals
+		app.update();
+		expect(app.world().entity(entity).get::<Checked>())
+			.to_be(Some(&Checked { checked: false }));
+	}
+}
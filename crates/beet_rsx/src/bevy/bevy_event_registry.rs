@@ -20,6 +20,18 @@ pub struct ClickEvt;
 pub struct HoverStart;
 #[derive(Debug, Clone, Event)]
 pub struct HoverEnd;
+/// Trigger on an entity with `bind:value` when its value is edited,
+/// ie by a text input widget.
+#[derive(Debug, Clone, Event)]
+pub struct InputEvt {
+	pub value: String,
+}
+/// Trigger on an entity with `bind:checked` or `bind:group`
+/// when it is checked or unchecked.
+#[derive(Debug, Clone, Event)]
+pub struct ChangeEvt {
+	pub checked: bool,
+}
 
 /// To be used with the [`BevyEventRegistry`] to trigger events
 fn interaction_system(
@@ -70,6 +82,46 @@ impl BevyEventRegistry {
 	) {
 		Self::register_on(_key, loc, value);
 	}
+
+	/// Used by `bind:value`, parsing the value of each [InputEvt]
+	pub fn register_bind_value<T: BindValue>(
+		key: &str,
+		loc: TreeLocation,
+		set: impl 'static + Send + Sync + Fn(T),
+	) {
+		Self::register_on(key, loc, move |ev: Trigger<InputEvt>| {
+			if let Some(value) = T::from_bind_value(&ev.event().value) {
+				set(value);
+			}
+		});
+	}
+
+	/// Used by `bind:checked`, setting the value of each [ChangeEvt]
+	pub fn register_bind_checked(
+		key: &str,
+		loc: TreeLocation,
+		set: impl 'static + Send + Sync + Fn(bool),
+	) {
+		Self::register_on(key, loc, move |ev: Trigger<ChangeEvt>| {
+			set(ev.event().checked);
+		});
+	}
+
+	/// Used by `bind:group`, where `value` is the value of the entity
+	pub fn register_bind_group<T: SignalPayload + BindGroup>(
+		key: &str,
+		loc: TreeLocation,
+		value: String,
+		mut get: SignalGetter<T>,
+		set: impl 'static + Send + Sync + Fn(T),
+	) {
+		Self::register_on(key, loc, move |ev: Trigger<ChangeEvt>| {
+			if let Some(next) = get.get().on_change(&value, ev.event().checked)
+			{
+				set(next);
+			}
+		});
+	}
 }
 
 
@@ -99,6 +151,48 @@ mod test {
 		});
 	}
 
+	#[test]
+	fn bind() {
+		BevyRuntime::reset();
+		let (get, set) = BevySignal::signal(1);
+		let mut get2 = get.clone();
+		let rsx = rsx! { <entity runtime:bevy bind:value={(get, set)}/> };
+		let entity = RsxToBevy::spawn(rsx).unwrap()[0];
+
+		let mut app = BevyRuntime::take();
+		app.world_mut()
+			.entity_mut(entity)
+			.trigger(InputEvt { value: "2".into() });
+		app.world_mut().flush();
+		// flush signals
+		app.update();
+		expect(get2()).to_be(2);
+		expect(app.world().entity(entity).get::<InputValue>())
+			.to_be(Some(&InputValue { value: "2".into() }));
+	}
+
+	#[test]
+	fn bind_checked() {
+		BevyRuntime::reset();
+		let (get, set) = BevySignal::signal(false);
+		let mut get2 = get.clone();
+		let rsx = rsx! { <entity runtime:bevy bind:checked={(get, set)}/> };
+		let entity = RsxToBevy::spawn(rsx).unwrap()[0];
+
+		let mut app = BevyRuntime::take();
+		expect(app.world().entity(entity).get::<Checked>())
+			.to_be(Some(&Checked { checked: false }));
+		app.world_mut()
+			.entity_mut(entity)
+			.trigger(ChangeEvt { checked: true });
+		app.world_mut().flush();
+		// flush signals
+		app.update();
+		expect(get2()).to_be(true);
+		expect(app.world().entity(entity).get::<Checked>())
+			.to_be(Some(&Checked { checked: true }));
+	}
+
 	#[test]
 	fn macro_works() {
 		BevyRuntime::reset();
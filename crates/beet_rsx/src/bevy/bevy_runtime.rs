@@ -49,17 +49,21 @@ impl BevyRuntime {
 	/// let value = || vec![RsxAttribute::Key{key:"foo".to_string()}];
 	/// let node = rsx!{<el {value}/>};
 	/// ```
-	#[allow(unused)]
 	pub fn parse_attribute_block<M, T: SignalOrRon<M>>(
 		tracker: RustyTracker,
 		mut block: T,
 	) -> RsxAttribute {
+		// attribute blocks are entire components, so the field path
+		// is the component name
+		let key = T::Inner::short_type_path().to_string();
+		let initial = block.into_ron_str();
 		RsxAttribute::Block {
-			initial: todo!(),
+			initial: vec![RsxAttribute::KeyValue {
+				key: key.clone(),
+				value: initial,
+			}],
 			effect: Effect::new(
-				Box::new(|_loc| {
-					todo!();
-				}),
+				block.into_attribute_value_effect(key),
 				tracker,
 			),
 		}
@@ -176,4 +180,25 @@ mod test {
 		let transform = query.iter(world).next().unwrap();
 		expect(transform.translation).to_be(Vec3::new(3., 4., 5.));
 	}
+
+	#[test]
+	fn attr_block() {
+		BevyRuntime::reset();
+		BevyRuntime::with_mut(|app| {
+			app.register_type::<Transform>();
+		});
+
+		let (get, set) = BevySignal::signal(Transform::from_xyz(0., 1., 2.));
+		let rsx = rsx! { <entity runtime:bevy {get} /> };
+		RsxToBevy::spawn(rsx).unwrap();
+		set(Transform::from_xyz(3., 4., 5.));
+
+		let mut app = BevyRuntime::with_mut(|app| std::mem::take(app));
+		// flush signals
+		app.update();
+		let world = app.world_mut();
+		let mut query = world.query::<&Transform>();
+		let transform = query.iter(world).next().unwrap();
+		expect(transform.translation).to_be(Vec3::new(3., 4., 5.));
+	}
 }
@@ -1,9 +1,11 @@
+mod bevy_bind;
 #[cfg(feature = "bevy_default")]
 mod bevy_event_registry;
 mod bevy_runtime;
 mod bevy_signal;
 mod reflect_utils;
 mod rsx_to_bevy;
+pub use bevy_bind::*;
 #[cfg(feature = "bevy_default")]
 pub use bevy_event_registry::*;
 pub use bevy_runtime::*;
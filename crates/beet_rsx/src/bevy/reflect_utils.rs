@@ -92,7 +92,7 @@ impl ReflectUtils {
 			dyn_struct.insert(field_path, value);
 			target.apply(&dyn_struct);
 		} else {
-			todo!("apply direct to component");
+			target.apply(&value);
 		}
 		Ok(())
 	}
@@ -166,7 +166,20 @@ impl ReflectUtils {
 					dyn_struct.insert_boxed(field_path, reflect_value);
 					target.apply(&dyn_struct);
 				} else {
-					todo!("apply direct to component");
+					let registration = registry
+						.get(target.reflect_type_info().type_id())
+						.ok_or_else(|| {
+							anyhow::anyhow!(
+								"Could not find registration for component type"
+							)
+						})?;
+					let reflect_deserializer =
+						TypedReflectDeserializer::new(registration, registry);
+					let mut deserializer =
+						ron::de::Deserializer::from_str(ron_value)?;
+					let reflect_value =
+						reflect_deserializer.deserialize(&mut deserializer)?;
+					target.apply(reflect_value.as_ref());
 				}
 			}
 			_ => {
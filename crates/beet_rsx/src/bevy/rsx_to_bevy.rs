@@ -49,6 +49,11 @@ impl RsxToBevy {
 		// println!("rsx_to_bevy found node: {:?}", node.as_ref().discriminant());
 		let nodes = match node.as_ref() {
 			RsxNode::Doctype { .. } => unimplemented!(),
+			RsxNode::RawHtml { value, .. } => {
+				anyhow::bail!(
+					"raw html cannot be spawned as an entity: {value}"
+				)
+			}
 			RsxNode::Comment { .. } => {
 				unimplemented!()
 			}
@@ -97,10 +102,20 @@ impl RsxToBevy {
 				tag: _,
 				tracker: _,
 				root,
+				// islands are a html concept
+				client_directive: _,
 				slot_children,
 			}) => {
 				slot_children.assert_empty();
-				self.spawn_root(world, root)?
+				let mut entities = self.spawn_root(world, root)?;
+				// the empty slot children must still be visited to keep the
+				// tree idx consistent with the [TreeLocationVisitor]
+				entities.extend(self.spawn_node(
+					world,
+					slot_children,
+					macro_location_hash,
+				)?);
+				entities
 			}
 		};
 		Ok(nodes)
@@ -14,23 +14,119 @@ impl BeetDom {
 		console_error_panic_hook::set_once();
 
 		// effects are called on render
-		let doc = RsxToResumableHtml::default().map_root(&app());
+		let doc = RsxToResumableHtml::default().map_root(&mut app());
 		Self::mount_doc(&doc);
 		Self::normalize();
 		// give the dom a moment to mount
 		set_timeout_ms(100, move || {
-			Self::hydrate(app());
+			Self::hydrate(&app);
 		});
 	}
 
+	/// Hydrate a page rendered by [RsxToResumableHtml]. The app should
+	/// be lazy, ie a function, so that any [ResumableState] is loaded
+	/// before its signals are created.
 	pub fn hydrate<M>(app: impl IntoRsxRoot<M>) {
 		console_error_panic_hook::set_once();
 		DomTarget::set(BrowserDomTarget::default());
+		#[cfg(feature = "serde")]
+		Self::load_state();
+		let mut root = app.into_root();
+		// match the fallbacks rendered by the server
+		ErrorBoundary::resolve(&mut root).report();
+		#[cfg(debug_assertions)]
+		Self::verify(&root);
 		// effects called here too
-		app.into_root().register_effects();
+		root.register_effects();
+		#[cfg(feature = "serde")]
+		ResumableState::clear();
 		EventRegistry::initialize().unwrap();
 	}
 
+	/// Log any [HydrationMismatch] between the live document and the
+	/// root, only called in debug builds.
+	#[cfg(debug_assertions)]
+	fn verify(root: &RsxRoot) {
+		let html = web_sys::window()
+			.unwrap()
+			.document()
+			.unwrap()
+			.document_element()
+			.unwrap()
+			.outer_html();
+		let doc = match HtmlDocument::parse(&html) {
+			Ok(doc) => doc,
+			Err(err) => {
+				web_sys::console::warn_1(&err.to_string().into());
+				return;
+			}
+		};
+		for mismatch in HydrationVerifier::verify(root, &doc) {
+			web_sys::console::warn_1(
+				&format!("Hydration mismatch: {}", mismatch).into(),
+			);
+		}
+	}
+
+	/// Load the [ResumableState] script rendered by the server
+	#[cfg(feature = "serde")]
+	fn load_state() {
+		let constants = HtmlConstants::default();
+		let Some(el) = web_sys::window()
+			.unwrap()
+			.document()
+			.unwrap()
+			.query_selector(&format!("[{}]", constants.state_key))
+			.unwrap()
+		else {
+			return;
+		};
+		// invalid state is ignored, resuming from the initial values
+		if let Err(err) =
+			ResumableState::load(&el.text_content().unwrap_or_default())
+		{
+			web_sys::console::warn_1(
+				&format!("Failed to load resumable state: {}", err).into(),
+			);
+		}
+	}
+
+	/// Hydrate the [ClientIsland]s of a page rendered by
+	/// [RsxToResumableHtml], instead of the entire app. This sets
+	/// [HtmlConstants::island_hydrate] which is called by the island loader
+	/// as each island is triggered by its [ClientDirective].
+	pub fn mount_islands(app: impl 'static + Fn() -> RsxRoot) {
+		use wasm_bindgen::prelude::Closure;
+		use wasm_bindgen::JsCast;
+
+		console_error_panic_hook::set_once();
+		DomTarget::set(BrowserDomTarget::default());
+		let constants = DomTarget::with(|h| h.html_constants().clone());
+		let hydrate = Closure::wrap(Box::new(move |island_idx: usize| {
+			#[cfg(feature = "serde")]
+			Self::load_state();
+			let mut root = app();
+			ErrorBoundary::resolve(&mut root).report();
+			#[cfg(feature = "serde")]
+			ResumableState::clear();
+			let islands = root.client_islands();
+			let island = islands
+				.get(island_idx)
+				.expect("island index out of bounds, was the page rebuilt?");
+			root.register_island_effects(island);
+			EventRegistry::initialize_island().unwrap();
+		}) as Box<dyn Fn(usize)>);
+		super::dom_event_registry::sweet_loader_extern::GLOBAL.with(|global| {
+			js_sys::Reflect::set(
+				global,
+				&constants.island_hydrate.into(),
+				hydrate.as_ref().unchecked_ref(),
+			)
+			.unwrap();
+		});
+		hydrate.forget();
+	}
+
 	/// by default the dom mounter will not collapse text nodes
 	/// this recursively collapses text nodes into their parent element
 	fn normalize() {
@@ -72,9 +168,20 @@ impl BeetDom {
 				let text = dom_doc.create_text_node(text);
 				parent.append_child(&text).expect("pizza");
 			}
+			HtmlNode::RawHtml(raw) => {
+				parent.insert_adjacent_html("beforeend", raw).unwrap();
+			}
 			HtmlNode::Element(html_el) => {
 				// sweet_utils::log!("element: {}", html_el.tag);
-				let dom_el = dom_doc.create_element(&html_el.tag).unwrap();
+				let dom_el = if html_el.namespace.is_foreign() {
+					dom_doc.create_element_ns(
+						Some(html_el.namespace.uri()),
+						&html_el.tag,
+					)
+				} else {
+					dom_doc.create_element(&html_el.tag)
+				}
+				.unwrap();
 				for attr in html_el.attributes.iter() {
 					dom_el
 						.set_attribute(
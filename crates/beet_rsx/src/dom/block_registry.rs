@@ -0,0 +1,130 @@
+use crate::prelude::*;
+use std::cell::RefCell;
+use std::ops::Range;
+
+thread_local! {
+	static BLOCK_REGISTRY: RefCell<BlockRegistry> = RefCell::new(BlockRegistry::default());
+}
+
+/// The current position of the html nodes rendered by a block.
+/// The [TreeLocation] of a block is only valid for the initial render,
+/// once a sibling block changes length the position is tracked here.
+#[derive(Debug, Clone)]
+struct BlockRange {
+	parent_idx: TreeIdx,
+	/// the current uncollapsed child index of the first node
+	start: u32,
+	/// the current number of html nodes
+	len: u32,
+	/// tree indices rendered by this block, blocks with an index
+	/// in these ranges are replaced when the block rerenders
+	owned: Vec<Range<u32>>,
+}
+
+impl BlockRange {
+	fn owns(&self, tree_idx: TreeIdx) -> bool {
+		self.owned.iter().any(|range| range.contains(&*tree_idx))
+	}
+}
+
+/// Tracks the live blocks of the current [DomTarget], and allocates
+/// the tree indices of inserted nodes.
+#[derive(Debug, Default)]
+pub(super) struct BlockRegistry {
+	blocks: HashMap<TreeIdx, BlockRange>,
+	/// the next tree index for inserted nodes, greater than any tree index
+	/// that has been registered
+	next_tree_idx: u32,
+}
+
+impl BlockRegistry {
+	pub fn with<O>(func: impl FnOnce(&mut Self) -> O) -> O {
+		BLOCK_REGISTRY.with(|registry| func(&mut registry.borrow_mut()))
+	}
+
+	/// Remove all blocks, called when the [DomTarget] is replaced.
+	/// The tree index allocator is not reset so that effects of the previous
+	/// target can never collide with inserted nodes.
+	pub fn clear(&mut self) { self.blocks.clear(); }
+
+	pub fn reserve(&mut self, num_nodes: u32) {
+		self.next_tree_idx = self.next_tree_idx.max(num_nodes);
+	}
+
+	/// Allocate a range of tree indices for an inserted node
+	pub fn allocate(&mut self, num_nodes: u32) -> Range<u32> {
+		let start = self.next_tree_idx;
+		self.next_tree_idx += num_nodes;
+		start..self.next_tree_idx
+	}
+
+	/// Whether the block at this tree index is live, blocks are removed
+	/// when a parent block rerenders.
+	pub fn contains(&self, tree_idx: TreeIdx) -> bool {
+		self.blocks.contains_key(&tree_idx)
+	}
+
+	/// Track the block at this location, where `node` is its first render
+	pub fn insert(&mut self, loc: TreeLocation, node: &RsxNode) {
+		let start = *loc.tree_idx + 1;
+		self.blocks.insert(loc.tree_idx, BlockRange {
+			parent_idx: loc.parent_idx,
+			start: loc.child_idx,
+			len: html_len(node),
+			owned: vec![start..start + num_nodes(node)],
+		});
+	}
+
+	/// Get the current child index and number of html nodes of the block,
+	/// removing any blocks it owns as they will be replaced by the rerender.
+	/// Returns `None` if the block was itself replaced by a parent block.
+	pub fn begin_update(&mut self, tree_idx: TreeIdx) -> Option<(u32, u32)> {
+		let block = self.blocks.get(&tree_idx)?.clone();
+		let mut owners = vec![block];
+		while let Some(owner) = owners.pop() {
+			let owned = self
+				.blocks
+				.keys()
+				.filter(|idx| **idx != tree_idx && owner.owns(**idx))
+				.copied()
+				.collect::<Vec<_>>();
+			for idx in owned {
+				owners.extend(self.blocks.remove(&idx));
+			}
+		}
+		self.blocks
+			.get(&tree_idx)
+			.map(|block| (block.start, block.len))
+	}
+
+	/// Update the length of the block after its patches were applied,
+	/// shifting the blocks after it in the same parent.
+	pub fn end_update(
+		&mut self,
+		tree_idx: TreeIdx,
+		len: u32,
+		owned: Vec<Range<u32>>,
+	) {
+		let Some(block) = self.blocks.get_mut(&tree_idx) else {
+			return;
+		};
+		let prev_len = std::mem::replace(&mut block.len, len);
+		block.owned = owned;
+		let parent_idx = block.parent_idx;
+		// siblings are visited in order so a greater tree index is
+		// a later sibling, blocks nested in this one were already removed
+		for (idx, sibling) in self.blocks.iter_mut() {
+			if *idx > tree_idx && sibling.parent_idx == parent_idx {
+				sibling.start = sibling.start + len - prev_len;
+			}
+		}
+	}
+}
+
+/// The number of rsx nodes in the tree, ie the number
+/// of tree indices it uses.
+pub(super) fn num_nodes(node: &RsxNode) -> u32 {
+	let mut num_nodes = 0;
+	TreeLocationVisitor::visit(node, |_, _| num_nodes += 1);
+	num_nodes
+}
@@ -1,8 +1,10 @@
 use crate::prelude::*;
 use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
 use web_sys::window;
 use web_sys::Document;
 use web_sys::Element;
+use web_sys::Node;
 use web_sys::Text;
 
 /// A hydrator for working with the dom
@@ -53,6 +55,122 @@ impl BrowserDomTarget {
 		Ok(())
 	}
 
+	/// apply a single patch, where the path is relative to the parent element
+	fn apply_patch(
+		&self,
+		parent: &Element,
+		patch: RsxPatch,
+	) -> ParseResult<()> {
+		let not_found = || {
+			ParseError::Hydration(format!(
+				"Could not find node at patch path: {:?}",
+				patch.path()
+			))
+		};
+		let (idx, parent_path) =
+			patch.path().split_last().ok_or_else(not_found)?;
+		let mut parent: Node = parent.clone().into();
+		for child_idx in parent_path {
+			parent = parent
+				.child_nodes()
+				.item(*child_idx)
+				.ok_or_else(not_found)?;
+		}
+		let child = parent.child_nodes().item(*idx);
+		match patch.clone() {
+			RsxPatch::Insert { mut node, .. } => {
+				// the node was rendered without its parent
+				let namespace = parent
+					.dyn_ref::<Element>()
+					.map(|el| {
+						Namespace::from_uri(
+							&el.namespace_uri().unwrap_or_default(),
+						)
+						.children(&el.local_name())
+					})
+					.unwrap_or_default();
+				node.apply_namespace(namespace);
+				let node = self.create_node(&node)?;
+				parent.insert_before(&node, child.as_ref()).unwrap();
+			}
+			RsxPatch::Remove { .. } => {
+				parent.remove_child(&child.ok_or_else(not_found)?).unwrap();
+			}
+			RsxPatch::Move { to, .. } => {
+				let child = child.ok_or_else(not_found)?;
+				parent.remove_child(&child).unwrap();
+				let reference = parent.child_nodes().item(to);
+				parent.insert_before(&child, reference.as_ref()).unwrap();
+			}
+			RsxPatch::SetText { value, .. } => {
+				child.ok_or_else(not_found)?.set_text_content(Some(&value));
+			}
+			RsxPatch::SetAttribute { key, value, .. } => {
+				child
+					.ok_or_else(not_found)?
+					.dyn_into::<Element>()
+					.map_err(|_| not_found())?
+					.set_attribute(&key, value.as_deref().unwrap_or_default())
+					.unwrap();
+			}
+			RsxPatch::RemoveAttribute { key, .. } => {
+				child
+					.ok_or_else(not_found)?
+					.dyn_into::<Element>()
+					.map_err(|_| not_found())?
+					.remove_attribute(&key)
+					.unwrap();
+			}
+		}
+		Ok(())
+	}
+
+	/// create a dom node from an html node
+	fn create_node(&self, node: &HtmlNode) -> ParseResult<Node> {
+		match node {
+			HtmlNode::Doctype => {
+				Err(ParseError::Hydration("Cannot insert a doctype".into()))
+			}
+			HtmlNode::Comment(comment) => {
+				Ok(self.document.create_comment(comment).into())
+			}
+			HtmlNode::Text(text) => {
+				Ok(self.document.create_text_node(text).into())
+			}
+			HtmlNode::RawHtml(raw) => {
+				let container = self.document.create_element("div").unwrap();
+				container.set_inner_html(raw);
+				let fragment = self.document.create_document_fragment();
+				while let Some(child) = container.first_child() {
+					fragment.append_child(&child).unwrap();
+				}
+				Ok(fragment.into())
+			}
+			HtmlNode::Element(html_el) => {
+				let el = if html_el.namespace.is_foreign() {
+					self.document.create_element_ns(
+						Some(html_el.namespace.uri()),
+						&html_el.tag,
+					)
+				} else {
+					self.document.create_element(&html_el.tag)
+				}
+				.unwrap();
+				for attr in html_el.attributes.iter() {
+					el.set_attribute(
+						&attr.key,
+						attr.value.as_deref().unwrap_or_default(),
+					)
+					.unwrap();
+				}
+				for child in html_el.children.iter() {
+					el.append_child(&self.create_node(child)?).unwrap();
+				}
+				Ok(el.into())
+			}
+		}
+	}
+
 	/// try to get cached element or find it in the dom.
 	/// When it is found it will uncollapse text nodes,
 	/// ie expand into the locations referenced by the [TreeLocation]
@@ -161,6 +279,12 @@ impl DomTargetImpl for BrowserDomTarget {
 					todo!("replace with text node");
 				}
 			}
+			RsxNode::RawHtml { value, .. } => {
+				let node = self.create_node(&HtmlNode::RawHtml(value))?;
+				parent.replace_child(&node, &child).map_err(|err| {
+					ParseError::Hydration(format!("{:?}", err))
+				})?;
+			}
 			RsxNode::Comment { .. } => todo!(),
 			RsxNode::Doctype { .. } => todo!(),
 		}
@@ -168,4 +292,78 @@ impl DomTargetImpl for BrowserDomTarget {
 
 		Ok(())
 	}
+
+	/// Cached elements are dropped once they are no longer connected
+	fn contains_element(&mut self, tree_idx: TreeIdx) -> bool {
+		if let Some(cached) = self.elements.get_mut(*tree_idx as usize) {
+			match cached {
+				Some(el) if el.is_connected() => return true,
+				Some(_) => {
+					*cached = None;
+					return false;
+				}
+				None => {}
+			}
+		}
+		let query = format!("[{}='{}']", self.constants.tree_idx_key, tree_idx);
+		self.document
+			.query_selector(&query)
+			.ok()
+			.flatten()
+			.is_some()
+	}
+
+	fn apply_patches(
+		&mut self,
+		patches: Vec<RsxPatch>,
+		loc: TreeLocation,
+	) -> ParseResult<()> {
+		let parent = self.get_or_find_element(loc.parent_idx)?;
+		for patch in patches {
+			self.apply_patch(&parent, patch.with_offset(loc.child_idx))?;
+		}
+		Ok(())
+	}
+
+	fn update_rsx_attribute(
+		&mut self,
+		key: &str,
+		value: Option<&str>,
+		loc: TreeLocation,
+	) -> ParseResult<()> {
+		let el = self.get_or_find_element(loc.tree_idx)?;
+		el.set_attribute(key, value.unwrap_or_default())
+			.map_err(|err| ParseError::Hydration(format!("{:?}", err)))?;
+		set_form_property(&el, key, Some(value.unwrap_or_default()))
+	}
+
+	fn remove_rsx_attribute(
+		&mut self,
+		key: &str,
+		loc: TreeLocation,
+	) -> ParseResult<()> {
+		let el = self.get_or_find_element(loc.tree_idx)?;
+		el.remove_attribute(key)
+			.map_err(|err| ParseError::Hydration(format!("{:?}", err)))?;
+		set_form_property(&el, key, None)
+	}
+}
+
+/// Once a user has edited a form element its `value` and `checked`
+/// attributes no longer reflect its state, so the properties are
+/// also updated, ie for `bind:value`. A `value` of `None` means the
+/// attribute was removed.
+fn set_form_property(
+	el: &Element,
+	key: &str,
+	value: Option<&str>,
+) -> ParseResult<()> {
+	let value = match key {
+		"value" => JsValue::from_str(value.unwrap_or_default()),
+		"checked" => JsValue::from_bool(value.is_some()),
+		_ => return Ok(()),
+	};
+	js_sys::Reflect::set(el, &key.into(), &value)
+		.map(|_| ())
+		.map_err(|err| ParseError::Hydration(format!("{:?}", err)))
 }
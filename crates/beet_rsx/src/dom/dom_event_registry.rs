@@ -51,6 +51,64 @@ impl EventRegistry {
 		Self::register(key, loc, value);
 	}
 
+	/// Used by `bind:value`, parsing the value of the event target
+	pub fn register_bind_value<T: BindValue>(
+		key: &str,
+		loc: TreeLocation,
+		set: impl 'static + Fn(T),
+	) {
+		Self::register(key, loc, move |e: Event| {
+			if let Some(value) = target_property(&e, "value")
+				.as_string()
+				.and_then(|value| T::from_bind_value(&value))
+			{
+				set(value);
+			}
+		});
+	}
+
+	/// Used by `bind:checked`
+	pub fn register_bind_checked(
+		key: &str,
+		loc: TreeLocation,
+		set: impl 'static + Fn(bool),
+	) {
+		Self::register(key, loc, move |e: Event| {
+			set(target_property(&e, "checked").is_truthy());
+		});
+	}
+
+	/// Used by `bind:group`, where `value` is the value of the element
+	pub fn register_bind_group<T: BindGroup>(
+		key: &str,
+		loc: TreeLocation,
+		value: String,
+		get: impl 'static + Fn() -> T,
+		set: impl 'static + Fn(T),
+	) {
+		Self::register(key, loc, move |e: Event| {
+			let checked = target_property(&e, "checked").is_truthy();
+			if let Some(next) = get().on_change(&value, checked) {
+				set(next);
+			}
+		});
+	}
+
+	/// Hook up the events registered by a single island. Unlike
+	/// [Self::initialize] the global event handler is kept
+	/// because other islands may not be hydrated yet.
+	pub fn initialize_island() -> ParseResult<()> {
+		let constants = DomTarget::with(|h| h.html_constants().clone());
+		hook_up_event_listeners(&constants)
+	}
+
+	/// Hook up the events of nodes inserted by a block after
+	/// the page was hydrated, see [DomTarget::update_block].
+	pub fn initialize_inserted() -> ParseResult<()> {
+		let constants = DomTarget::with(|h| h.html_constants().clone());
+		hook_up_event_listeners(&constants)
+	}
+
 	pub fn initialize() -> ParseResult<()> {
 		let constants = DomTarget::with(|h| h.html_constants().clone());
 		hook_up_event_listeners(&constants)?;
@@ -60,6 +118,32 @@ impl EventRegistry {
 	}
 }
 
+macro_rules! register_events {
+	($($func:ident),* $(,)?) => {
+		impl EventRegistry {
+			$(
+				#[allow(missing_docs)]
+				pub fn $func(
+					key: &str,
+					loc: TreeLocation,
+					value: impl 'static + Fn(Event),
+				) {
+					Self::register(key, loc, value);
+				}
+			)*
+		}
+	};
+}
+for_each_event!(register_events);
+
+
+/// Get a property of the event target, ie `value` or `checked`,
+/// which unlike attributes reflect the current state of the element.
+fn target_property(event: &Event, key: &str) -> JsValue {
+	Reflect::get(event, &"target".into())
+		.and_then(|target| Reflect::get(&target, &key.into()))
+		.unwrap_or(JsValue::UNDEFINED)
+}
 
 /// This may do nothing for one of several reasons:
 /// - this hydration is happening before the page was mounted
@@ -0,0 +1,96 @@
+/// Calls the macro with the `register_on*` function name of every event
+/// attribute accepted by the html validator of the rsx macro,
+/// apart from `onclick` which is implemented by hand as an example.
+/// The macro emits `EventRegistry::register_{key}` so each registry
+/// must implement all of these, and this list must be kept in sync
+/// with `EVENT_ATTRIBUTES` in `beet_rsx_parser`.
+macro_rules! for_each_event {
+	($mac:ident) => {
+		$mac!(
+			register_onabort,
+			register_onanimationend,
+			register_onanimationiteration,
+			register_onanimationstart,
+			register_onauxclick,
+			register_onbeforeinput,
+			register_onbeforetoggle,
+			register_onblur,
+			register_oncancel,
+			register_oncanplay,
+			register_oncanplaythrough,
+			register_onchange,
+			register_onclose,
+			register_oncontextmenu,
+			register_oncopy,
+			register_oncuechange,
+			register_oncut,
+			register_ondblclick,
+			register_ondrag,
+			register_ondragend,
+			register_ondragenter,
+			register_ondragleave,
+			register_ondragover,
+			register_ondragstart,
+			register_ondrop,
+			register_ondurationchange,
+			register_onemptied,
+			register_onended,
+			register_onerror,
+			register_onfocus,
+			register_onfocusin,
+			register_onfocusout,
+			register_onformdata,
+			register_oninput,
+			register_oninvalid,
+			register_onkeydown,
+			register_onkeypress,
+			register_onkeyup,
+			register_onload,
+			register_onloadeddata,
+			register_onloadedmetadata,
+			register_onloadstart,
+			register_onmousedown,
+			register_onmouseenter,
+			register_onmouseleave,
+			register_onmousemove,
+			register_onmouseout,
+			register_onmouseover,
+			register_onmouseup,
+			register_onpaste,
+			register_onpause,
+			register_onplay,
+			register_onplaying,
+			register_onpointercancel,
+			register_onpointerdown,
+			register_onpointerenter,
+			register_onpointerleave,
+			register_onpointermove,
+			register_onpointerout,
+			register_onpointerover,
+			register_onpointerup,
+			register_onprogress,
+			register_onratechange,
+			register_onreset,
+			register_onresize,
+			register_onscroll,
+			register_onscrollend,
+			register_onseeked,
+			register_onseeking,
+			register_onselect,
+			register_onslotchange,
+			register_onstalled,
+			register_onsubmit,
+			register_onsuspend,
+			register_ontimeupdate,
+			register_ontoggle,
+			register_ontouchcancel,
+			register_ontouchend,
+			register_ontouchmove,
+			register_ontouchstart,
+			register_ontransitionend,
+			register_onvolumechange,
+			register_onwaiting,
+			register_onwheel,
+		);
+	};
+}
@@ -0,0 +1,410 @@
+use crate::prelude::*;
+use std::fmt;
+use std::ops::Range;
+
+/// Compare the html expected by an [RsxRoot] against a live document,
+/// usually rendered by the server. Hydration relies on both agreeing,
+/// otherwise effects will fail to find their elements or split text
+/// nodes in the wrong place.
+///
+/// Every element with a tree index is checked, and mismatches are
+/// reported with the [RsxMacroLocation] of the `rsx!` macro that
+/// created the element.
+/// Event attributes are ignored because targets replace them with
+/// listeners.
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let value = 1;
+/// let doc = RsxToResumableHtml::default().map_root(&mut rsx! { <div>{value}</div> });
+/// let value = 2;
+/// let mismatches = HydrationVerifier::verify(&rsx! { <div>{value}</div> }, &doc);
+/// assert_eq!(mismatches.len(), 1);
+/// ```
+pub struct HydrationVerifier {
+	constants: HtmlConstants,
+	/// The tree index range of each nested `rsx!` macro, in depth first
+	/// order so that later ranges are more specific.
+	macro_ranges: Vec<(Range<u32>, RsxMacroLocation)>,
+	mismatches: Vec<HydrationMismatch>,
+}
+
+/// A difference between the expected and received html
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydrationMismatch {
+	/// The tree index of the element
+	pub tree_idx: TreeIdx,
+	/// The location of the `rsx!` macro that created the element
+	pub location: RsxMacroLocation,
+	pub kind: HydrationMismatchKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HydrationMismatchKind {
+	/// No element in the document has the tree index
+	MissingElement { tag: String },
+	/// The element has a different tag
+	Tag { expected: String, received: String },
+	/// An attribute is missing or has a different value
+	Attribute {
+		key: String,
+		expected: Option<String>,
+		received: Option<String>,
+	},
+	/// The element has an attribute that was not expected
+	UnexpectedAttribute { key: String },
+	/// A collapsed text node differs, so cannot be split
+	/// at the positions of its rust blocks.
+	/// `text_idx` is the index of the text node in the element.
+	Text {
+		text_idx: usize,
+		split_positions: Vec<usize>,
+		expected: String,
+		received: Option<String>,
+	},
+}
+
+impl fmt::Display for HydrationMismatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}:{}:{} tree index {}: ",
+			self.location.file(),
+			self.location.line(),
+			self.location.col(),
+			self.tree_idx
+		)?;
+		match &self.kind {
+			HydrationMismatchKind::MissingElement { tag } => {
+				write!(f, "missing element <{}>", tag)
+			}
+			HydrationMismatchKind::Tag { expected, received } => {
+				write!(f, "expected <{}>, received <{}>", expected, received)
+			}
+			HydrationMismatchKind::Attribute {
+				key,
+				expected,
+				received,
+			} => write!(
+				f,
+				"attribute `{}` expected {:?}, received {:?}",
+				key, expected, received
+			),
+			HydrationMismatchKind::UnexpectedAttribute { key } => {
+				write!(f, "unexpected attribute `{}`", key)
+			}
+			HydrationMismatchKind::Text {
+				text_idx,
+				split_positions,
+				expected,
+				received,
+			} => write!(
+				f,
+				"text node {} expected {:?} split at {:?}, received {:?}",
+				text_idx, expected, split_positions, received
+			),
+		}
+	}
+}
+
+impl HydrationVerifier {
+	/// Returns all mismatches between the html expected by the root
+	/// and the document.
+	pub fn verify(
+		root: &RsxRoot,
+		doc: &HtmlDocument,
+	) -> Vec<HydrationMismatch> {
+		let mut this = Self {
+			constants: HtmlConstants::default(),
+			macro_ranges: macro_ranges(root),
+			mismatches: Vec::new(),
+		};
+		let expected = RsxToHtml::default().map_root(root);
+		let selector =
+			Selector::parse(&format!("[{}]", this.constants.tree_idx_key))
+				.unwrap();
+		let received = selector
+			.query_all(&doc.head)
+			.into_iter()
+			.chain(selector.query_all(&doc.body))
+			.filter_map(|el| Some((this.tree_idx(el)?, el)))
+			.collect::<HashMap<_, _>>();
+		for expected in selector.query_all(&expected) {
+			this.verify_element(expected, &received);
+		}
+		this.mismatches
+	}
+
+	/// Like [Self::verify] but returns a [ParseError::Hydration]
+	/// listing every mismatch.
+	pub fn assert(root: &RsxRoot, doc: &HtmlDocument) -> ParseResult<()> {
+		let mismatches = Self::verify(root, doc);
+		if mismatches.is_empty() {
+			return Ok(());
+		}
+		Err(ParseError::Hydration(format!(
+			"{} mismatches between the document and rsx tree:\n{}",
+			mismatches.len(),
+			mismatches
+				.iter()
+				.map(|mismatch| mismatch.to_string())
+				.collect::<Vec<_>>()
+				.join("\n")
+		)))
+	}
+
+	fn tree_idx(&self, el: &HtmlElementNode) -> Option<TreeIdx> {
+		el.get_attribute_value(self.constants.tree_idx_key)
+			.and_then(|idx| idx.parse::<u32>().ok())
+			.map(TreeIdx::new)
+	}
+
+	/// Compare the expected element with the received element
+	/// that has the same tree index.
+	fn verify_element(
+		&mut self,
+		expected: &HtmlElementNode,
+		received: &HashMap<TreeIdx, &HtmlElementNode>,
+	) {
+		let Some(tree_idx) = self.tree_idx(expected) else {
+			return;
+		};
+		let Some(received) = received.get(&tree_idx).copied() else {
+			self.push(tree_idx, HydrationMismatchKind::MissingElement {
+				tag: expected.tag.clone(),
+			});
+			return;
+		};
+		if received.tag != expected.tag {
+			self.push(tree_idx, HydrationMismatchKind::Tag {
+				expected: expected.tag.clone(),
+				received: received.tag.clone(),
+			});
+			return;
+		}
+		self.verify_attributes(tree_idx, expected, received);
+		self.verify_text(tree_idx, expected, received);
+	}
+
+	fn verify_attributes(
+		&mut self,
+		tree_idx: TreeIdx,
+		expected: &HtmlElementNode,
+		received: &HtmlElementNode,
+	) {
+		let is_event = |key: &str| key.starts_with("on");
+		for attr in expected.attributes.iter() {
+			if is_event(&attr.key) {
+				continue;
+			}
+			let received_attr =
+				received.attributes.iter().find(|a| a.key == attr.key);
+			match received_attr {
+				Some(received_attr) if received_attr.value == attr.value => {}
+				received_attr => {
+					self.push(tree_idx, HydrationMismatchKind::Attribute {
+						key: attr.key.clone(),
+						expected: attr.value.clone(),
+						received: received_attr
+							.and_then(|attr| attr.value.clone()),
+					});
+				}
+			}
+		}
+		for attr in received.attributes.iter() {
+			if !is_event(&attr.key) && !expected.has_attribute(&attr.key) {
+				self.push(
+					tree_idx,
+					HydrationMismatchKind::UnexpectedAttribute {
+						key: attr.key.clone(),
+					},
+				);
+			}
+		}
+	}
+
+	/// Compare the text nodes of the element after collapsing adjacent
+	/// text, which is how the browser parses the server html.
+	fn verify_text(
+		&mut self,
+		tree_idx: TreeIdx,
+		expected: &HtmlElementNode,
+		received: &HtmlElementNode,
+	) {
+		let received = collapsed_text(received)
+			.into_iter()
+			.map(|parts| parts.concat())
+			.collect::<Vec<_>>();
+		for (text_idx, parts) in
+			collapsed_text(expected).into_iter().enumerate()
+		{
+			let expected = parts.concat();
+			let received = received.get(text_idx);
+			if received == Some(&expected) {
+				continue;
+			}
+			self.push(tree_idx, HydrationMismatchKind::Text {
+				text_idx,
+				split_positions: parts[..parts.len() - 1]
+					.iter()
+					.map(|part| part.len())
+					.collect(),
+				expected,
+				received: received.cloned(),
+			});
+		}
+	}
+
+	fn push(&mut self, tree_idx: TreeIdx, kind: HydrationMismatchKind) {
+		let location = self
+			.macro_ranges
+			.iter()
+			.rev()
+			.find(|(range, _)| range.contains(&*tree_idx))
+			.map(|(_, location)| location.clone())
+			.unwrap_or_default();
+		self.mismatches.push(HydrationMismatch {
+			tree_idx,
+			location,
+			kind,
+		});
+	}
+}
+
+/// Runs of adjacent text nodes, split by any other node
+fn collapsed_text(el: &HtmlElementNode) -> Vec<Vec<&str>> {
+	let mut runs = Vec::<Vec<&str>>::new();
+	let mut in_run = false;
+	for child in el.children.iter() {
+		match child {
+			HtmlNode::Text(text) if in_run => {
+				runs.last_mut().unwrap().push(text)
+			}
+			HtmlNode::Text(text) => {
+				runs.push(vec![text]);
+				in_run = true;
+			}
+			_ => in_run = false,
+		}
+	}
+	runs
+}
+
+/// The tree index range of the root and every nested [RsxRoot]
+/// created by a macro, ie components and blocks that return `rsx!`.
+/// Roots without a location, like text blocks, are part of their
+/// parent macro.
+fn macro_ranges(root: &RsxRoot) -> Vec<(Range<u32>, RsxMacroLocation)> {
+	let mut num_nodes = 0;
+	TreeLocationVisitor::visit(&root.node, |_, _| num_nodes += 1);
+	let mut ranges = vec![(0..num_nodes, root.location.clone())];
+	TreeLocationVisitor::visit(&root.node, |loc, node| {
+		let nested = match node {
+			RsxNode::Component(RsxComponent { root, .. }) => root,
+			RsxNode::Block(RsxBlock { initial, .. }) => initial,
+			_ => return,
+		};
+		if nested.location == RsxMacroLocation::default() {
+			return;
+		}
+		let mut num_nodes = 0;
+		TreeLocationVisitor::visit(node, |_, _| num_nodes += 1);
+		ranges.push((
+			*loc.tree_idx..*loc.tree_idx + num_nodes,
+			nested.location.clone(),
+		));
+	});
+	ranges
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[derive(Props)]
+	struct Counter {
+		initial: u32,
+	}
+	impl Component for Counter {
+		fn render(self) -> RsxRoot {
+			let count = self.initial;
+			rsx! { <button class="counter">count: {count}</button> }
+		}
+	}
+
+	fn page(initial: u32, class: &'static str) -> RsxRoot {
+		let value = "hello";
+		rsx! {
+			<main class={class}>
+				<p>{value}</p>
+				<Counter initial=initial />
+			</main>
+		}
+	}
+
+	#[test]
+	fn matching() {
+		let doc = RsxToResumableHtml::default().map_root(&mut page(1, "a"));
+		expect(HydrationVerifier::verify(&page(1, "a"), &doc).len()).to_be(0);
+		// also works on expanded text nodes
+		let html = doc.render();
+		let target = RsDomTarget::from_html(&page(1, "a"), &html).unwrap();
+		expect(HydrationVerifier::verify(&page(1, "a"), &target.html).len())
+			.to_be(0);
+	}
+
+	#[test]
+	fn text() {
+		let doc = RsxToResumableHtml::default().map_root(&mut page(10, "a"));
+		let mismatches = HydrationVerifier::verify(&page(1, "a"), &doc);
+		expect(mismatches.len()).to_be(1);
+		expect(&mismatches[0].kind).to_be(&HydrationMismatchKind::Text {
+			text_idx: 0,
+			split_positions: vec![7],
+			expected: "count: 1".into(),
+			received: Some("count: 10".into()),
+		});
+		// the location is the macro in the component
+		expect(mismatches[0].location.line())
+			.to_be(Counter { initial: 1 }.render().location.line());
+		expect(mismatches[0].to_string())
+			.to_contain("text node 0 expected \"count: 1\" split at [7]");
+	}
+
+	#[test]
+	fn attributes() {
+		let doc = RsxToResumableHtml::default().map_root(&mut page(1, "b"));
+		let mismatches = HydrationVerifier::verify(&page(1, "a"), &doc);
+		expect(mismatches.len()).to_be(1);
+		expect(&mismatches[0].kind).to_be(&HydrationMismatchKind::Attribute {
+			key: "class".into(),
+			expected: Some("a".into()),
+			received: Some("b".into()),
+		});
+		expect(mismatches[0].location.line())
+			.to_be(page(1, "a").location.line());
+	}
+
+	#[test]
+	fn missing_element() {
+		let value = "hello";
+		let doc = RsxToResumableHtml::default().map_root(
+			&mut rsx! { <div class={value}><span>{value}</span></div> },
+		);
+		let mismatches = HydrationVerifier::verify(&page(1, "a"), &doc);
+		expect(&mismatches[0].kind).to_be(&HydrationMismatchKind::Tag {
+			expected: "main".into(),
+			received: "div".into(),
+		});
+		expect(mismatches.iter().any(|m| {
+			matches!(m.kind, HydrationMismatchKind::MissingElement { .. })
+		}))
+		.to_be_true();
+		let err = HydrationVerifier::assert(&page(1, "a"), &doc)
+			.unwrap_err()
+			.to_string();
+		expect(err).to_contain("missing element <");
+	}
+}
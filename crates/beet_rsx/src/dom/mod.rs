@@ -1,17 +1,23 @@
 use crate::prelude::*;
+#[macro_use]
+mod event_attributes;
 #[cfg(target_arch = "wasm32")]
 mod beet_dom;
+mod block_registry;
+use block_registry::*;
 #[cfg(target_arch = "wasm32")]
 mod dom_event_registry;
 #[cfg(target_arch = "wasm32")]
 pub use beet_dom::*;
 #[cfg(target_arch = "wasm32")]
 pub use dom_event_registry::EventRegistry;
+mod hydration_verifier;
+pub use hydration_verifier::*;
 #[cfg(not(target_arch = "wasm32"))]
 mod native_event_registry;
 mod rs_dom_target;
 #[cfg(not(target_arch = "wasm32"))]
-pub use native_event_registry::EventRegistry;
+pub use native_event_registry::*;
 pub use rs_dom_target::*;
 use std::cell::RefCell;
 
@@ -22,7 +28,7 @@ mod browser_dom_target;
 pub use browser_dom_target::*;
 
 thread_local! {
-	static DOM_TARGET: RefCell<Box<dyn DomTargetImpl>> = RefCell::new(Box::new(RsDomTarget::new(&().into_root())));
+	static DOM_TARGET: RefCell<Box<dyn DomTargetImpl>> = RefCell::new(Box::new(RsDomTarget::new(&mut ().into_root())));
 }
 
 /// Mechanism for swapping out:
@@ -31,7 +37,7 @@ thread_local! {
 pub struct DomTarget;
 
 impl DomTarget {
-	pub fn with<R>(mut func: impl FnMut(&mut dyn DomTargetImpl) -> R) -> R {
+	pub fn with<R>(func: impl FnOnce(&mut dyn DomTargetImpl) -> R) -> R {
 		DOM_TARGET.with(|current| {
 			let mut current = current.borrow_mut();
 			func(current.as_mut())
@@ -43,6 +49,170 @@ impl DomTarget {
 		DOM_TARGET.with(|current| {
 			*current.borrow_mut() = Box::new(item);
 		});
+		BlockRegistry::with(|registry| registry.clear());
+	}
+
+	/// Ensure tree indices allocated for inserted nodes are greater
+	/// than those of a tree with this many nodes, called by
+	/// [RsxNode::register_effects].
+	pub fn reserve_tree_idx(num_nodes: u32) {
+		BlockRegistry::with(|registry| registry.reserve(num_nodes));
+	}
+
+	/// Track the html nodes rendered by the block at this location,
+	/// called by [RsxNode::register_effects] for each block.
+	pub fn register_block(loc: TreeLocation, node: &RsxNode) {
+		BlockRegistry::with(|registry| registry.insert(loc, node));
+	}
+
+	/// Apply the difference between two renders of the block at the
+	/// location. Inserted nodes are rendered with new tree indices and
+	/// their effects are registered, and the position of later sibling
+	/// blocks is updated if the number of nodes changed.
+	///
+	/// Blocks nested in the previous render are replaced by this one,
+	/// so this does nothing if the block was replaced by a parent.
+	pub fn update_block(
+		loc: TreeLocation,
+		prev: &RsxNode,
+		next: &mut RsxNode,
+	) -> ParseResult<()> {
+		Self::patch_block(loc, Some(prev), next)
+	}
+
+	/// Like [Self::update_block] but the previous render is not known,
+	/// so all html nodes of the block are replaced.
+	pub fn replace_block(
+		loc: TreeLocation,
+		next: &mut RsxNode,
+	) -> ParseResult<()> {
+		Self::patch_block(loc, None, next)
+	}
+
+	fn patch_block(
+		loc: TreeLocation,
+		prev: Option<&RsxNode>,
+		next: &mut RsxNode,
+	) -> ParseResult<()> {
+		let Some((child_idx, len)) =
+			BlockRegistry::with(|registry| registry.begin_update(loc.tree_idx))
+		else {
+			return Ok(());
+		};
+		let mut patches = match prev {
+			Some(prev) => RsxDiff::diff(prev, &*next),
+			None => (0..len)
+				.map(|_| RsxPatch::Remove { path: vec![0] })
+				.chain(RsxDiff::diff(&RsxNode::default(), &*next))
+				.collect(),
+		};
+		let mut inserted = Vec::new();
+		for patch in patches.iter_mut() {
+			let RsxPatch::Insert { path, node } = patch else {
+				continue;
+			};
+			let rsx = node_at_path_mut(next, path).ok_or_else(|| {
+				ParseError::Hydration(format!(
+					"Could not find inserted node at path: {:?}",
+					path
+				))
+			})?;
+			let range = BlockRegistry::with(|registry| {
+				registry.allocate(num_nodes(rsx))
+			});
+			if let Some(html) = RsxToHtml::default()
+				.with_tree_idx(range.start.into())
+				.map_node(&*rsx)
+				.pop()
+			{
+				*node = html;
+			}
+			inserted.push((path.clone(), range));
+		}
+		Self::with(|target| {
+			target.apply_patches(patches, TreeLocation { child_idx, ..loc })
+		})?;
+		// later patches may have moved the node, but the path is its
+		// position in the next render
+		for (path, range) in inserted.iter() {
+			if let Some(rsx) = node_at_path_mut(next, path) {
+				rsx.register_effects_from(range.start.into());
+			}
+		}
+		#[cfg(target_arch = "wasm32")]
+		if !inserted.is_empty() {
+			EventRegistry::initialize_inserted()?;
+		}
+		BlockRegistry::with(|registry| {
+			registry.end_update(
+				loc.tree_idx,
+				html_len(next),
+				inserted.into_iter().map(|(_, range)| range).collect(),
+			)
+		});
+		Ok(())
+	}
+
+	/// Whether the node at the location was removed by the rerender of
+	/// a parent block, ie an item of a [For] or the previous branch of a
+	/// [Show]. Runtimes dispose the effects of removed nodes.
+	/// Blocks are tracked by their parent block and other nodes with
+	/// effects are elements tagged with their tree index.
+	pub fn is_removed(loc: TreeLocation) -> bool {
+		!BlockRegistry::with(|registry| registry.contains(loc.tree_idx))
+			&& !Self::with(|target| target.contains_element(loc.tree_idx))
+	}
+
+	/// Apply the difference between two versions of an attribute block
+	/// to the element at the location, removing keys no longer present.
+	pub fn update_attributes(
+		prev: &[(String, Option<String>)],
+		next: &[(String, Option<String>)],
+		loc: TreeLocation,
+	) -> ParseResult<()> {
+		Self::with(|target| {
+			for (key, _) in prev
+				.iter()
+				.filter(|(key, _)| !next.iter().any(|(next, _)| next == key))
+			{
+				target.remove_rsx_attribute(key, loc)?;
+			}
+			for (key, value) in next.iter().filter(|pair| !prev.contains(pair))
+			{
+				target.update_rsx_attribute(key, value.as_deref(), loc)?;
+			}
+			Ok(())
+		})
+	}
+
+	/// Simulate an event on the first element matching the selector,
+	/// where `event` is the name without the `on` prefix, ie `click`.
+	/// The handler is called after the target is released so that
+	/// any effects can update it.
+	/// ```
+	/// # use beet_rsx::as_beet::*;
+	/// # use beet_rsx::sigfault::signal;
+	/// let (get, set) = signal(0);
+	/// let get2 = get.clone();
+	/// let mut root = rsx! {
+	/// 	<button id="inc" onclick={move |_| set(get2() + 1)}>{get}</button>
+	/// };
+	/// DomTarget::set(RsDomTarget::new(&mut root));
+	/// root.register_effects();
+	/// DomTarget::dispatch("button#inc", "click", ()).unwrap();
+	/// let text = DomTarget::with(|target| target.render());
+	/// assert!(text.contains(">1</button>"));
+	/// ```
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn dispatch(
+		selector: &str,
+		event: &str,
+		payload: impl Into<MockEvent>,
+	) -> ParseResult<()> {
+		let key = format!("on{event}");
+		let handler = Self::with(|target| target.find_event(selector, &key))?;
+		handler(payload.into());
+		Ok(())
 	}
 }
 
@@ -56,11 +226,56 @@ pub trait DomTargetImpl {
 		loc: TreeLocation,
 	) -> ParseResult<()>;
 
-	// TODO update attriute block, update block value
+	/// Apply the [RsxPatch] list produced by [RsxDiff] for the block
+	/// at the provided location, where the `child_idx` is the current
+	/// position of the block, see [DomTarget::update_block].
+	fn apply_patches(
+		&mut self,
+		patches: Vec<RsxPatch>,
+		loc: TreeLocation,
+	) -> ParseResult<()>;
+
+	/// Set an attribute on the element at the provided location,
+	/// where a `value` of `None` is a key-only attribute.
+	fn update_rsx_attribute(
+		&mut self,
+		key: &str,
+		value: Option<&str>,
+		loc: TreeLocation,
+	) -> ParseResult<()>;
+
+	/// Remove an attribute from the element at the provided location,
+	/// used when an attribute block no longer contains the key.
+	fn remove_rsx_attribute(
+		&mut self,
+		key: &str,
+		loc: TreeLocation,
+	) -> ParseResult<()>;
 
 
+	/// Whether the element tagged with this tree index is
+	/// in the document, see [DomTarget::is_removed].
+	fn contains_element(&mut self, tree_idx: TreeIdx) -> bool;
+
 	/// just used for testing atm
 	fn render(&self) -> String;
 
-	// fn register_event(&self, event: Box<dyn Fn() -> ()>);
+	/// Store the handler for an event on the element at the location,
+	/// keyed by its [TreeIdx].
+	#[cfg(not(target_arch = "wasm32"))]
+	fn register_event(
+		&mut self,
+		key: &str,
+		loc: TreeLocation,
+		func: MockEventHandler,
+	);
+
+	/// Find the handler for an event on the first element
+	/// matching the selector, see [DomTarget::dispatch].
+	#[cfg(not(target_arch = "wasm32"))]
+	fn find_event(
+		&self,
+		selector: &str,
+		key: &str,
+	) -> ParseResult<MockEventHandler>;
 }
@@ -1,23 +1,132 @@
 use crate::prelude::*;
-pub struct EventRegistry;
+use std::rc::Rc;
 
+/// Registers event handlers with the current [DomTarget],
+/// which for native targets is usually the [RsDomTarget]. Events can
+/// then be simulated with [DomTarget::dispatch].
+pub struct EventRegistry;
 
+/// A stored event handler, see [DomTargetImpl::register_event]
+pub type MockEventHandler = Rc<dyn Fn(MockEvent)>;
 
+/// A minimal stand-in for a browser event, used for simulating events
+/// on native targets.
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct MockEvent {
 	pub target: MockTarget,
 }
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct MockTarget {
 	pub value: String,
+	pub checked: bool,
+}
+
+impl MockEvent {
+	/// Create an event where the target has this value
+	pub fn new(value: impl Into<String>) -> Self {
+		Self {
+			target: MockTarget {
+				value: value.into(),
+				checked: false,
+			},
+		}
+	}
+	/// Create an event where the target is checked or unchecked,
+	/// ie a checkbox with this value
+	pub fn checked(value: impl Into<String>, checked: bool) -> Self {
+		Self {
+			target: MockTarget {
+				value: value.into(),
+				checked,
+			},
+		}
+	}
 }
 
+impl From<()> for MockEvent {
+	fn from(_: ()) -> Self { Self::default() }
+}
+impl From<&str> for MockEvent {
+	fn from(value: &str) -> Self { Self::new(value) }
+}
+impl From<String> for MockEvent {
+	fn from(value: String) -> Self { Self::new(value) }
+}
+impl From<bool> for MockEvent {
+	fn from(checked: bool) -> Self { Self::checked("on", checked) }
+}
 
 impl EventRegistry {
-	#[allow(unused_variables)]
+	fn register(
+		key: &str,
+		loc: TreeLocation,
+		func: impl 'static + Fn(MockEvent),
+	) {
+		DomTarget::with(|target| {
+			target.register_event(key, loc, Rc::new(func))
+		});
+	}
+
 	pub fn register_onclick(
 		key: &str,
 		loc: TreeLocation,
-		value: impl Fn(MockEvent) -> (),
+		value: impl 'static + Fn(MockEvent),
+	) {
+		Self::register(key, loc, value);
+	}
+
+	/// Used by `bind:value`, parsing the value of the target
+	pub fn register_bind_value<T: BindValue>(
+		key: &str,
+		loc: TreeLocation,
+		set: impl 'static + Fn(T),
+	) {
+		Self::register(key, loc, move |e| {
+			if let Some(value) = T::from_bind_value(&e.target.value) {
+				set(value);
+			}
+		});
+	}
+
+	/// Used by `bind:checked`
+	pub fn register_bind_checked(
+		key: &str,
+		loc: TreeLocation,
+		set: impl 'static + Fn(bool),
+	) {
+		Self::register(key, loc, move |e| set(e.target.checked));
+	}
+
+	/// Used by `bind:group`, where `value` is the value of the element
+	pub fn register_bind_group<T: BindGroup>(
+		key: &str,
+		loc: TreeLocation,
+		value: String,
+		get: impl 'static + Fn() -> T,
+		set: impl 'static + Fn(T),
 	) {
-		todo!()
+		Self::register(key, loc, move |e| {
+			if let Some(next) = get().on_change(&value, e.target.checked) {
+				set(next);
+			}
+		});
 	}
 }
+
+macro_rules! register_events {
+	($($func:ident),* $(,)?) => {
+		impl EventRegistry {
+			$(
+				#[allow(missing_docs)]
+				pub fn $func(
+					key: &str,
+					loc: TreeLocation,
+					value: impl 'static + Fn(MockEvent),
+				) {
+					Self::register(key, loc, value);
+				}
+			)*
+		}
+	};
+}
+for_each_event!(register_events);
@@ -6,18 +6,49 @@ pub struct RsDomTarget {
 	pub html: HtmlDocument,
 	constants: HtmlConstants,
 	loc_map: TreeLocationMap,
+	/// event handlers keyed by the tree index of their element
+	/// and the event key, ie `onclick`
+	#[cfg(not(target_arch = "wasm32"))]
+	events: HashMap<(TreeIdx, String), MockEventHandler>,
 }
 
 impl RsDomTarget {
-	pub fn new(root: &RsxRoot) -> Self {
+	pub fn new(root: &mut RsxRoot) -> Self {
 		let html = RsxToResumableHtml::default().map_root(root);
 		let loc_map = TreeLocationMap::from_node(root);
 		Self {
 			html,
 			constants: Default::default(),
 			loc_map,
+			#[cfg(not(target_arch = "wasm32"))]
+			events: Default::default(),
 		}
 	}
+
+	/// Start from server rendered html instead of rendering the root,
+	/// the html is expected to be the output of [RsxToResumableHtml]
+	/// for this root. Collapsed text nodes are split again using the
+	/// [TreeLocationMap].
+	pub fn from_html(root: &RsxRoot, html: &str) -> ParseResult<Self> {
+		let mut this = Self {
+			html: HtmlDocument::parse(html)?,
+			constants: Default::default(),
+			loc_map: TreeLocationMap::from_node(root),
+			#[cfg(not(target_arch = "wasm32"))]
+			events: Default::default(),
+		};
+		let collapsed = this
+			.loc_map
+			.collapsed_elements
+			.iter()
+			.map(|(idx, encoder)| (*idx, encoder.split_positions.clone()))
+			.collect::<Vec<_>>();
+		for (tree_idx, split_positions) in collapsed {
+			let el = this.find_element(tree_idx)?;
+			expand_text_nodes(el, &split_positions)?;
+		}
+		Ok(this)
+	}
 }
 
 impl DomTargetImpl for RsDomTarget {
@@ -25,41 +56,270 @@ impl DomTargetImpl for RsDomTarget {
 
 	fn render(&self) -> String { self.html.render() }
 
+	fn contains_element(&mut self, tree_idx: TreeIdx) -> bool {
+		self.find_element(tree_idx).is_ok()
+	}
+
 	fn update_rsx_node(
 		&mut self,
 		rsx: RsxNode,
 		loc: TreeLocation,
 	) -> ParseResult<()> {
-		let parent_idx = self
-			.loc_map
-			.rusty_locations
-			.get(&loc.tree_idx)
+		let constants = self.constants.clone();
+		let parent_el = self.find_parent(loc)?;
+		apply_rsx(parent_el, rsx, loc, &constants)
+	}
+
+	fn apply_patches(
+		&mut self,
+		patches: Vec<RsxPatch>,
+		loc: TreeLocation,
+	) -> ParseResult<()> {
+		let parent_el = self.find_parent(loc)?;
+		for patch in patches {
+			apply_patch(parent_el, patch.with_offset(loc.child_idx))?;
+		}
+		// handlers of removed elements can no longer be dispatched
+		#[cfg(not(target_arch = "wasm32"))]
+		{
+			let events = std::mem::take(&mut self.events);
+			self.events = events
+				.into_iter()
+				.filter(|((tree_idx, _), _)| self.contains_element(*tree_idx))
+				.collect();
+		}
+		Ok(())
+	}
+
+	fn update_rsx_attribute(
+		&mut self,
+		key: &str,
+		value: Option<&str>,
+		loc: TreeLocation,
+	) -> ParseResult<()> {
+		let el = self.find_element(loc.tree_idx)?;
+		let value = value.map(|value| value.to_string());
+		match el.attributes.iter_mut().find(|attr| attr.key == key) {
+			Some(attr) => attr.value = value,
+			None => el.attributes.push(HtmlAttribute {
+				key: key.to_string(),
+				value,
+			}),
+		}
+		Ok(())
+	}
+
+	fn remove_rsx_attribute(
+		&mut self,
+		key: &str,
+		loc: TreeLocation,
+	) -> ParseResult<()> {
+		let el = self.find_element(loc.tree_idx)?;
+		el.attributes.retain(|attr| attr.key != key);
+		Ok(())
+	}
+
+	/// Store the handler and remove the event attribute, mirroring the
+	/// browser which replaces it with an event listener.
+	#[cfg(not(target_arch = "wasm32"))]
+	fn register_event(
+		&mut self,
+		key: &str,
+		loc: TreeLocation,
+		func: MockEventHandler,
+	) {
+		if let Ok(el) = self.find_element(loc.tree_idx) {
+			el.remove_attribute(key);
+		}
+		self.events.insert((loc.tree_idx, key.to_string()), func);
+	}
+
+	#[cfg(not(target_arch = "wasm32"))]
+	fn find_event(
+		&self,
+		selector: &str,
+		key: &str,
+	) -> ParseResult<MockEventHandler> {
+		let el = self.html.query_selector(selector)?.ok_or_else(|| {
+			ParseError::Hydration(format!(
+				"Could not find element matching selector: {}",
+				selector
+			))
+		})?;
+		let tree_idx = el
+			.get_attribute_value(self.constants.tree_idx_key)
+			.and_then(|idx| idx.parse::<u32>().ok())
+			.ok_or_else(|| {
+				ParseError::Hydration(format!(
+					"Element matching selector has no tree index: {}",
+					selector
+				))
+			})?;
+		self.events
+			.get(&(tree_idx.into(), key.to_string()))
+			.cloned()
 			.ok_or_else(|| {
 				ParseError::Hydration(format!(
-					"Could not find block parent for tree index: {}",
-					loc.tree_idx
+					"No {} handler for element matching selector: {}",
+					key, selector
 				))
-			})?
-			.parent_idx
-			.to_string();
+			})
+	}
+}
 
+impl RsDomTarget {
+	/// find the element with this tree index, elements with attribute
+	/// effects or rust children are always tagged with their tree index
+	fn find_element(
+		&mut self,
+		tree_idx: TreeIdx,
+	) -> ParseResult<&mut HtmlElementNode> {
+		let tree_idx = tree_idx.to_string();
 		for html in self.html.iter_mut() {
-			// let parent_hash =
-			if let Some(parent_el) = html.query_selector_attr(
+			if let Some(el) = html.query_selector_attr(
 				self.constants.tree_idx_key,
-				Some(&parent_idx),
+				Some(&tree_idx),
 			) {
-				return apply_rsx(parent_el, rsx, loc, &self.constants);
+				return Ok(el);
 			}
 		}
+		Err(ParseError::Hydration(format!(
+			"Could not find element with id: {}",
+			tree_idx
+		)))
+	}
 
-		return Err(ParseError::Hydration(format!(
-			"Could not find node with id: {}",
-			parent_idx
-		)));
+	/// find the parent element of the block at this location
+	fn find_parent(
+		&mut self,
+		loc: TreeLocation,
+	) -> ParseResult<&mut HtmlElementNode> {
+		self.find_element(loc.parent_idx)
 	}
 }
 
+/// split collapsed text nodes, mirroring the browser
+/// which uses `Text::split_text`
+fn expand_text_nodes(
+	el: &mut HtmlElementNode,
+	split_positions: &[Vec<usize>],
+) -> ParseResult<()> {
+	for (child_idx, positions) in split_positions.iter().enumerate() {
+		let Some(HtmlNode::Text(text)) = el.children.get(child_idx) else {
+			return Err(ParseError::Hydration(format!(
+				"Could not find text node at index: {}",
+				child_idx
+			)));
+		};
+		let mut parts = Vec::new();
+		let mut remaining = text.as_str();
+		for position in positions {
+			let (part, rest) =
+				remaining.split_at_checked(*position).ok_or_else(|| {
+					ParseError::Hydration(format!(
+						"Could not split text node at position: {}",
+						position
+					))
+				})?;
+			parts.push(HtmlNode::Text(part.to_string()));
+			remaining = rest;
+		}
+		parts.push(HtmlNode::Text(remaining.to_string()));
+		el.children.splice(child_idx..child_idx + 1, parts);
+	}
+	Ok(())
+}
+
+/// apply a single patch, where the path is relative to the parent element
+fn apply_patch(
+	parent_el: &mut HtmlElementNode,
+	patch: RsxPatch,
+) -> ParseResult<()> {
+	let (parent_path, idx) = match patch.path().split_last() {
+		Some((idx, parent_path)) => (parent_path.to_vec(), *idx as usize),
+		None => return Err(ParseError::Hydration("Empty patch path".into())),
+	};
+	let (namespace, siblings) = children_at(parent_el, &parent_path)?;
+	let out_of_bounds = || {
+		ParseError::Hydration(format!(
+			"Patch path {:?} is out of bounds",
+			patch.path()
+		))
+	};
+	match patch.clone() {
+		RsxPatch::Insert { mut node, .. } => {
+			if idx > siblings.len() {
+				return Err(out_of_bounds());
+			}
+			// the node was rendered without its parent
+			node.apply_namespace(namespace);
+			siblings.insert(idx, node);
+		}
+		RsxPatch::Remove { .. } => {
+			if idx >= siblings.len() {
+				return Err(out_of_bounds());
+			}
+			siblings.remove(idx);
+		}
+		RsxPatch::Move { to, .. } => {
+			if idx >= siblings.len() || to as usize >= siblings.len() {
+				return Err(out_of_bounds());
+			}
+			let node = siblings.remove(idx);
+			siblings.insert(to as usize, node);
+		}
+		RsxPatch::SetText { value, .. } => {
+			match siblings.get_mut(idx).ok_or_else(out_of_bounds)? {
+				HtmlNode::Text(text) => *text = value,
+				other => *other = HtmlNode::Text(value),
+			}
+		}
+		RsxPatch::SetAttribute { key, value, .. } => {
+			let el = element_at(siblings, idx).ok_or_else(out_of_bounds)?;
+			match el.attributes.iter_mut().find(|attr| attr.key == key) {
+				Some(attr) => attr.value = value,
+				None => el.attributes.push(HtmlAttribute { key, value }),
+			}
+		}
+		RsxPatch::RemoveAttribute { key, .. } => {
+			let el = element_at(siblings, idx).ok_or_else(out_of_bounds)?;
+			el.attributes.retain(|attr| attr.key != key);
+		}
+	}
+	Ok(())
+}
+
+fn element_at(
+	siblings: &mut [HtmlNode],
+	idx: usize,
+) -> Option<&mut HtmlElementNode> {
+	match siblings.get_mut(idx) {
+		Some(HtmlNode::Element(el)) => Some(el),
+		_ => None,
+	}
+}
+
+/// get the children of the element at the path, and the namespace
+/// they are in
+fn children_at<'a>(
+	parent_el: &'a mut HtmlElementNode,
+	path: &[u32],
+) -> ParseResult<(Namespace, &'a mut Vec<HtmlNode>)> {
+	let mut namespace = parent_el.namespace.children(&parent_el.tag);
+	let mut children = &mut parent_el.children;
+	for idx in path {
+		let el = element_at(children, *idx as usize).ok_or_else(|| {
+			ParseError::Hydration(format!(
+				"Could not find element at path: {:?}",
+				path
+			))
+		})?;
+		namespace = el.namespace.children(&el.tag);
+		children = &mut el.children;
+	}
+	Ok((namespace, children))
+}
+
 
 /// we've found a html node with a matching id
 #[allow(unused)]
@@ -84,8 +344,170 @@ fn apply_rsx(
 				})?;
 			*child = HtmlNode::Text(value);
 		}
+		RsxNode::RawHtml { value, .. } => {
+			let child = parent_el
+				.children
+				.get_mut(loc.child_idx as usize)
+				.ok_or_else(|| {
+					ParseError::Hydration(format!(
+						"child node at index: {} is out of bounds",
+						loc.child_idx,
+					))
+				})?;
+			*child = HtmlNode::RawHtml(value);
+		}
 		RsxNode::Comment { .. } => todo!(),
 		RsxNode::Doctype { .. } => todo!(),
 	}
 	Ok(())
 }
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use crate::sigfault::signal;
+	use sweet::prelude::*;
+
+	#[test]
+	fn from_html() {
+		let (get, set) = signal(7);
+		let mut rsx = rsx! { <div>value is {get}<b>!</b></div> };
+		let html = RsxToResumableHtml::default().map_root(&mut rsx).render();
+		let target = RsDomTarget::from_html(&rsx, &html).unwrap();
+		// the collapsed text node was split
+		expect(target.html.body[0].clone()).to_be(HtmlNode::Element(
+			HtmlElementNode {
+				tag: "div".into(),
+				namespace: Default::default(),
+				self_closing: false,
+				attributes: vec![HtmlAttribute {
+					key: "data-beet-rsx-idx".into(),
+					value: Some("0".into()),
+				}],
+				children: vec![
+					HtmlNode::Text("value is ".into()),
+					HtmlNode::Text("7".into()),
+					HtmlNode::Element(HtmlElementNode {
+						tag: "b".into(),
+						namespace: Default::default(),
+						self_closing: false,
+						attributes: vec![],
+						children: vec![HtmlNode::Text("!".into())],
+					}),
+				],
+			},
+		));
+		DomTarget::set(target);
+		rsx.register_effects();
+		set(8);
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<div data-beet-rsx-idx=\"0\">value is 8<b>!</b></div>",
+		);
+	}
+
+	#[test]
+	fn dispatch() {
+		let (get, set) = signal(0);
+		let (text, set_text) = signal(String::new());
+		let (get1, set1) = (get.clone(), set.clone());
+		let (get2, set2) = (get.clone(), set.clone());
+		let mut rsx = rsx! {
+			<div>
+				<button id="inc" onclick={move |_| set1(get1() + 1)}>increment</button>
+				<button id="dec" onclick={move |_| set2(get2() - 1)}>decrement</button>
+				<p class="count">{get}</p>
+				<input
+					oninput={move |e| set_text(e.target.value)}
+					onkeydown={move |_| set(0)}
+				/>
+				<span>{text}</span>
+			</div>
+		};
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		let text = |selector: &str| {
+			DomTarget::with(|target| {
+				let html = HtmlDocument::parse(&target.render()).unwrap();
+				html.query_selector(selector)
+					.unwrap()
+					.unwrap()
+					.text_content()
+			})
+		};
+		expect(text("p.count")).to_be("0");
+		DomTarget::dispatch("button#inc", "click", ()).unwrap();
+		DomTarget::dispatch("button#inc", "click", ()).unwrap();
+		expect(text("p.count")).to_be("2");
+		DomTarget::dispatch("#dec", "click", ()).unwrap();
+		expect(text("p.count")).to_be("1");
+		DomTarget::dispatch("input", "input", "hello").unwrap();
+		expect(text("span")).to_be("hello");
+		DomTarget::dispatch("input", "keydown", ()).unwrap();
+		expect(text("p.count")).to_be("0");
+		// the event attributes are removed like in the browser
+		let html = DomTarget::with(|target| target.render());
+		expect(&html).not().to_contain("onclick");
+		expect(&html).not().to_contain("oninput");
+		expect(DomTarget::dispatch("p", "click", ()).is_err()).to_be_true();
+		expect(DomTarget::dispatch("nav", "click", ()).is_err()).to_be_true();
+	}
+
+	#[test]
+	fn hoisted_head() {
+		let (get, set) = signal(7);
+		let mut rsx = rsx! {
+			<div>
+				<Head><title>Page</title></Head>
+				<p>value is</p>
+				{get}
+			</div>
+		};
+		SlotsVisitor::apply(&mut rsx).unwrap();
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		let doc = || {
+			HtmlDocument::parse(&DomTarget::with(|target| target.render()))
+				.unwrap()
+		};
+		expect(doc().head.render()).to_be("<title>Page</title>");
+		set(8);
+		expect(doc().render_body()).to_start_with(
+			"<div data-beet-rsx-idx=\"0\"><!-- head --><p>value is</p>8</div>",
+		);
+	}
+
+	#[test]
+	fn foreign_insert() {
+		let circles = |radii: Vec<u32>| {
+			let circles = move || {
+				radii
+					.iter()
+					.map(|r| {
+						let r = *r;
+						rsx! { <circle key=r r=r/> }
+					})
+					.collect::<Vec<_>>()
+			};
+			rsx! { <svg>{circles}</svg> }
+		};
+		let mut parent = HtmlElementNode {
+			tag: "div".into(),
+			namespace: Default::default(),
+			self_closing: false,
+			attributes: vec![],
+			children: RsxToHtml::default().map_root(&circles(vec![1])),
+		};
+		// patches are rendered without their parent
+		for patch in RsxDiff::diff(circles(vec![1]), circles(vec![1, 2, 3])) {
+			super::apply_patch(&mut parent, patch).unwrap();
+		}
+		let circles = Selector::parse("circle")
+			.unwrap()
+			.query_all(&parent.children);
+		expect(circles.len()).to_be(3);
+		for circle in circles {
+			expect(circle.namespace).to_be(Namespace::Svg);
+		}
+	}
+}
@@ -0,0 +1,135 @@
+use crate::prelude::*;
+
+/// Elements whose text content is not parsed as html,
+/// and so must not be entity escaped.
+pub const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Escape a html text node so it cannot be parsed as markup.
+pub fn escape_html_text(value: &str) -> String {
+	escape(value, |c| match c {
+		'&' => Some("&amp;"),
+		'<' => Some("&lt;"),
+		'>' => Some("&gt;"),
+		_ => None,
+	})
+}
+
+/// Escape a double quoted html attribute value.
+pub fn escape_html_attribute(value: &str) -> String {
+	escape(value, |c| match c {
+		'&' => Some("&amp;"),
+		'"' => Some("&quot;"),
+		'<' => Some("&lt;"),
+		'>' => Some("&gt;"),
+		_ => None,
+	})
+}
+
+/// Comments are not entity decoded so the brackets are escaped
+/// to prevent closing the comment early, ie `-->`.
+pub fn escape_html_comment(value: &str) -> String {
+	escape(value, |c| match c {
+		'<' => Some("&lt;"),
+		'>' => Some("&gt;"),
+		_ => None,
+	})
+}
+
+/// The content of raw text elements like `<script>` is not entity decoded,
+/// so instead we break up any closing tag or comment opener,
+/// ie `</script>` becomes `<\/script>`.
+pub fn escape_raw_text(tag: &str, value: &str) -> String {
+	let closing = format!("</{}", tag.to_ascii_lowercase());
+	let lower = value.to_ascii_lowercase();
+	let mut out = String::with_capacity(value.len());
+	let mut last = 0;
+	for (i, _) in lower.match_indices('<') {
+		let rest = &lower[i..];
+		if rest.starts_with(&closing) || rest.starts_with("<!--") {
+			out.push_str(&value[last..i + 1]);
+			out.push('\\');
+			last = i + 1;
+		}
+	}
+	out.push_str(&value[last..]);
+	out
+}
+
+/// Returns true if the tag is a [RAW_TEXT_ELEMENTS]
+pub fn is_raw_text_element(tag: &str) -> bool {
+	RAW_TEXT_ELEMENTS
+		.iter()
+		.any(|raw| raw.eq_ignore_ascii_case(tag))
+}
+
+fn escape(value: &str, map: impl Fn(char) -> Option<&'static str>) -> String {
+	let mut out = String::with_capacity(value.len());
+	for c in value.chars() {
+		match map(c) {
+			Some(escaped) => out.push_str(escaped),
+			None => out.push(c),
+		}
+	}
+	out
+}
+
+/// Opt out of escaping, the string will be rendered as is.
+/// Only use this for trusted content, user input rendered
+/// this way is vulnerable to cross site scripting.
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let html = RawHtml::new("<b>hello</b>");
+/// assert_eq!(
+/// 	RsxToHtml::render_body(&rsx! { <div>{html}</div> }),
+/// 	"<div data-beet-rsx-idx=\"0\"><b>hello</b></div>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawHtml(pub String);
+
+impl RawHtml {
+	pub fn new(value: impl Into<String>) -> Self { Self(value.into()) }
+}
+
+impl IntoRsxRoot<RawHtml> for RawHtml {
+	fn into_root(self) -> RsxRoot {
+		RsxRoot {
+			location: RsxMacroLocation::default(),
+			node: RsxNode::RawHtml {
+				idx: RsxIdx::default(),
+				value: self.0,
+			},
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn text() {
+		expect(escape_html_text("<a href=\"x\">&</a>"))
+			.to_be("&lt;a href=\"x\"&gt;&amp;&lt;/a&gt;");
+	}
+	#[test]
+	fn attribute() {
+		expect(escape_html_attribute("\" onload=\"alert(1)"))
+			.to_be("&quot; onload=&quot;alert(1)");
+	}
+	#[test]
+	fn comment() {
+		expect(escape_html_comment("--><script>"))
+			.to_be("--&gt;&lt;script&gt;");
+	}
+	#[test]
+	fn raw_text() {
+		expect(escape_raw_text("script", "a</ScRiPt><!--b"))
+			.to_be("a<\\/ScRiPt><\\!--b");
+		expect(escape_raw_text("style", "a</script>")).to_be("a</script>");
+		expect(is_raw_text_element("STYLE")).to_be_true();
+	}
+}
@@ -33,6 +33,7 @@ impl HtmlDocument {
 "#;
 		self.body.push(HtmlNode::Element(HtmlElementNode {
 			tag: "script".to_string(),
+			namespace: Default::default(),
 			self_closing: false,
 			attributes: vec![HtmlAttribute {
 				key: "type".to_string(),
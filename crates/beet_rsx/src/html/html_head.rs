@@ -0,0 +1,200 @@
+use crate::prelude::*;
+// the Props derive and rsx macro expect `beet` in scope
+use crate::as_beet::beet;
+use crate::as_beet::rsx;
+
+/// Add elements to the document head from anywhere in the tree,
+/// for example a page setting its `<title>` inside a layout.
+/// The children are hoisted into [HtmlDocument::head] by
+/// [HtmlDocument::hoist_head], which is called by [RsxRoot::build_document],
+/// or [HtmlDocument::hoist_resumable_head] which is called by
+/// [RsxToResumableHtml::map_root].
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// #[derive(Props)]
+/// struct Page;
+/// impl Component for Page {
+/// 	fn render(self) -> RsxRoot {
+/// 		rsx! {
+/// 			<div>
+/// 				<Head><title>My Page</title></Head>
+/// 				hello world
+/// 			</div>
+/// 		}
+/// 	}
+/// }
+/// let doc = rsx! { <Page /> }.build_document().unwrap();
+/// assert_eq!(doc.head.render(), "<title>My Page</title>");
+/// ```
+#[derive(Props)]
+pub struct Head;
+
+impl Component for Head {
+	/// Renders the children in a `<head>` element
+	fn render(self) -> RsxRoot {
+		rsx! { <head><slot/></head> }
+	}
+}
+
+impl HtmlDocument {
+	/// Move the children of any `<head>` elements in the body into
+	/// [Self::head], then dedupe the head elements.
+	/// Duplicates are replaced in place by the last occurrence,
+	/// so a page nested in a layout can override its title.
+	/// Elements are duplicates if they have the same:
+	/// - `<title>`, `<base>` or `<meta charset>`
+	/// - `<meta>` `name`, `property` or `http-equiv`
+	/// - `<link rel="canonical">`
+	///
+	/// All other elements like scripts and styles are kept as is.
+	pub fn hoist_head(&mut self) { self.hoist_head_inner(false); }
+
+	/// Like [Self::hoist_head] but each `<head>` element in the body is
+	/// replaced with a `<!-- head -->` comment, so the child indices of its siblings
+	/// still match their [TreeLocation].
+	pub fn hoist_resumable_head(&mut self) { self.hoist_head_inner(true); }
+
+	fn hoist_head_inner(&mut self, placeholder: bool) {
+		take_head_elements(&mut self.body, &mut self.head, placeholder);
+
+		let mut head: Vec<HtmlNode> = Vec::with_capacity(self.head.len());
+		let mut keys = HashMap::<String, usize>::default();
+		for node in std::mem::take(&mut self.head) {
+			let key = head_key(&node);
+			match key.as_ref().and_then(|key| keys.get(key).copied()) {
+				Some(idx) => head[idx] = node,
+				None => {
+					if let Some(key) = key {
+						keys.insert(key, head.len());
+					}
+					head.push(node);
+				}
+			}
+		}
+		self.head = head;
+	}
+}
+
+/// recursively move the children of `<head>` elements, the empty
+/// head elements are removed or replaced with a placeholder comment
+fn take_head_elements(
+	nodes: &mut Vec<HtmlNode>,
+	head: &mut Vec<HtmlNode>,
+	placeholder: bool,
+) {
+	for node in nodes.iter_mut() {
+		let HtmlNode::Element(el) = node else {
+			continue;
+		};
+		if el.tag == "head" {
+			head.extend(std::mem::take(&mut el.children));
+			if placeholder {
+				*node = HtmlNode::Comment("head".into());
+			}
+		} else {
+			take_head_elements(&mut el.children, head, placeholder);
+		}
+	}
+	nodes.retain(|node| node.element_with_tag("head").is_none());
+}
+
+/// The key used to dedupe head elements
+fn head_key(node: &HtmlNode) -> Option<String> {
+	let HtmlNode::Element(el) = node else {
+		return None;
+	};
+	match el.tag.as_str() {
+		"title" | "base" => Some(el.tag.clone()),
+		"meta" => {
+			if el.attributes.iter().any(|attr| attr.key == "charset") {
+				return Some("meta:charset".into());
+			}
+			["name", "property", "http-equiv"].iter().find_map(|key| {
+				el.get_attribute_value(key)
+					.map(|value| format!("meta:{key}:{value}"))
+			})
+		}
+		"link" => match el.get_attribute_value("rel") {
+			Some("canonical") => Some("link:canonical".into()),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[derive(Props)]
+	struct Layout;
+	impl Component for Layout {
+		fn render(self) -> RsxRoot {
+			rsx! {
+				<html>
+					<head>
+						<title>Layout</title>
+						<meta charset="utf-8" />
+						<meta name="description" content="layout" />
+					</head>
+					<body>
+						<slot />
+					</body>
+				</html>
+			}
+		}
+	}
+
+	#[derive(Props)]
+	struct Page;
+	impl Component for Page {
+		fn render(self) -> RsxRoot {
+			rsx! {
+				<main>
+					<Head>
+						<title>Page</title>
+						<meta name="description" content="page" />
+						<meta property="og:title" content="Page" />
+						<link rel="canonical" href="/page" />
+					</Head>
+					<p>hello</p>
+				</main>
+			}
+		}
+	}
+
+	#[test]
+	fn hoists() {
+		let doc = rsx! {
+			<Layout>
+				<Page />
+			</Layout>
+		}
+		.build_document()
+		.unwrap();
+		expect(doc.head.render()).to_be(
+			"<title>Page</title><meta charset=\"utf-8\"/><meta name=\"description\" content=\"page\"/><meta property=\"og:title\" content=\"Page\"/><link rel=\"canonical\" href=\"/page\"/>",
+		);
+		expect(doc.render_body()).to_be("<main><p>hello</p></main>");
+	}
+
+	#[test]
+	fn dedupes() {
+		let mut doc = HtmlDocument {
+			head: HtmlParser::default()
+				.parse("<title>a</title><script>1</script><meta name=\"a\"/><meta property=\"a\"/>")
+				.unwrap(),
+			body: HtmlParser::default()
+				.parse("<div><head><title>b</title><script>1</script><meta name=\"a\" content=\"b\"/></head></div>")
+				.unwrap(),
+		};
+		doc.hoist_head();
+		expect(doc.head.render()).to_be(
+			"<title>b</title><script>1</script><meta name=\"a\" content=\"b\"/><meta property=\"a\"/><script>1</script>",
+		);
+		expect(doc.render_body()).to_be("<div></div>");
+	}
+}
@@ -0,0 +1,162 @@
+use crate::prelude::*;
+
+/// The namespace of an element, elements in `<svg>` and `<math>` are
+/// foreign elements which must be created with their namespace,
+/// ie `document.createElementNS`, and follow different parsing rules.
+///
+/// Namespaces are resolved from the parent element while rendering
+/// instead of being stored in [RsxElement], because a component may
+/// be rendered inside an `<svg>` that its own `rsx!` macro cannot see.
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let html = RsxToHtml::default()
+/// 	.map_root(&rsx! { <svg><circle r="5"/></svg> })
+/// 	.into_document();
+/// let circle = html.query_selector("circle").unwrap().unwrap();
+/// assert_eq!(circle.namespace, Namespace::Svg);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Namespace {
+	#[default]
+	Html,
+	Svg,
+	MathMl,
+}
+
+impl Namespace {
+	pub const HTML_URI: &'static str = "http://www.w3.org/1999/xhtml";
+	pub const SVG_URI: &'static str = "http://www.w3.org/2000/svg";
+	pub const MATHML_URI: &'static str = "http://www.w3.org/1998/Math/MathML";
+
+	/// The namespace uri, ie `http://www.w3.org/2000/svg`
+	pub fn uri(&self) -> &'static str {
+		match self {
+			Self::Html => Self::HTML_URI,
+			Self::Svg => Self::SVG_URI,
+			Self::MathMl => Self::MATHML_URI,
+		}
+	}
+
+	/// Get the namespace from its uri, unknown uris are treated as html
+	pub fn from_uri(uri: &str) -> Self {
+		match uri {
+			Self::SVG_URI => Self::Svg,
+			Self::MATHML_URI => Self::MathMl,
+			_ => Self::Html,
+		}
+	}
+
+	/// The namespace of an element with this tag, where `self` is the
+	/// namespace of its siblings, see [Self::children].
+	pub fn element(self, tag: &str) -> Self {
+		match tag {
+			"svg" => Self::Svg,
+			"math" => Self::MathMl,
+			_ => self,
+		}
+	}
+
+	/// The namespace of the children of an element in this namespace,
+	/// `<foreignObject>` and `<annotation-xml>` contain html.
+	pub fn children(self, tag: &str) -> Self {
+		match (self, tag) {
+			(Self::Svg, "foreignObject") => Self::Html,
+			(Self::MathMl, "annotation-xml") => Self::Html,
+			(ns, _) => ns,
+		}
+	}
+
+	/// Foreign elements are those not in the html namespace
+	pub fn is_foreign(&self) -> bool { *self != Self::Html }
+}
+
+impl HtmlNode {
+	/// Recursively set the namespace of this node and its descendants,
+	/// where `context` is the namespace of the children of its parent.
+	/// Used for nodes rendered without their parent, ie patches.
+	pub fn apply_namespace(&mut self, context: Namespace) {
+		if let HtmlNode::Element(el) = self {
+			el.namespace = context.element(&el.tag);
+			let children = el.namespace.children(&el.tag);
+			for child in el.children.iter_mut() {
+				child.apply_namespace(children);
+			}
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn resolves() {
+		let html = RsxToHtml::default()
+			.map_root(&rsx! {
+				<div>
+					<svg viewBox="0 0 10 10">
+						<circle r="5"/>
+						<foreignObject><p>hello</p></foreignObject>
+					</svg>
+					<math><mi>x</mi></math>
+				</div>
+			})
+			.into_document();
+		let ns = |selector: &str| {
+			html.query_selector(selector).unwrap().unwrap().namespace
+		};
+		expect(ns("div")).to_be(Namespace::Html);
+		expect(ns("svg")).to_be(Namespace::Svg);
+		expect(ns("circle")).to_be(Namespace::Svg);
+		expect(ns("foreignObject")).to_be(Namespace::Svg);
+		expect(ns("p")).to_be(Namespace::Html);
+		expect(ns("math")).to_be(Namespace::MathMl);
+		expect(ns("mi")).to_be(Namespace::MathMl);
+	}
+
+	#[derive(Props)]
+	struct Dot;
+	impl Component for Dot {
+		fn render(self) -> RsxRoot {
+			rsx! { <circle r="1"/> }
+		}
+	}
+
+	#[test]
+	fn components() {
+		let html = RsxToHtml::default()
+			.map_root(&rsx! { <svg><Dot/></svg> })
+			.into_document();
+		expect(html.query_selector("circle").unwrap().unwrap().namespace)
+			.to_be(Namespace::Svg);
+	}
+
+	#[test]
+	fn foreign_raw_text() {
+		let html = "<style>a < b</style><svg><style>a &lt; b</style></svg>";
+		let nodes = HtmlParser::default().parse(html).unwrap();
+		// only html style elements are raw text
+		expect(nodes.render()).to_be(html);
+		let doc = nodes.into_document();
+		let style = doc.query_selector("svg style").unwrap().unwrap();
+		expect(style.namespace).to_be(Namespace::Svg);
+		expect(style.text_content()).to_be("a < b");
+	}
+
+	#[test]
+	fn parse() {
+		let doc = HtmlParser::default()
+			.parse_document("<svg><circle/><foreignObject><p>hi</p></foreignObject></svg><math><mi>x</mi></math>")
+			.unwrap();
+		let ns = |selector: &str| {
+			doc.query_selector(selector).unwrap().unwrap().namespace
+		};
+		expect(ns("circle")).to_be(Namespace::Svg);
+		expect(ns("foreignObject")).to_be(Namespace::Svg);
+		expect(ns("p")).to_be(Namespace::Html);
+		expect(ns("mi")).to_be(Namespace::MathMl);
+	}
+}
@@ -1,4 +1,8 @@
-/// A trait for rendering a value to HTML
+use crate::prelude::*;
+
+/// A trait for rendering a value to HTML.
+/// Text, attribute values and comments are escaped,
+/// use [RawHtml] to opt out.
 pub trait RenderHtml {
 	/// Convert a value, usually [HtmlNode] to a string of HTML
 	fn render(&self) -> String {
@@ -44,15 +48,45 @@ impl RenderHtml for Vec<HtmlNode> {
 }
 
 /// Unlike RsxNode, this struct contains only real html nodes
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum HtmlNode {
 	Doctype,
 	Comment(String),
+	/// Text that will be escaped when rendered
 	Text(String),
+	/// Trusted html that will be rendered as is, see [RawHtml]
+	RawHtml(String),
 	Element(HtmlElementNode),
 }
 
 impl HtmlNode {
+	/// The concatenated text of this node and its descendants,
+	/// comments and doctypes are ignored. Raw html is parsed so that
+	/// only its text is included, like the browser.
+	pub fn text_content(&self) -> String {
+		let mut text = String::new();
+		self.push_text_content(&mut text);
+		text
+	}
+	fn push_text_content(&self, text: &mut String) {
+		match self {
+			HtmlNode::Text(val) => text.push_str(val),
+			HtmlNode::RawHtml(val) => {
+				// raw html that cannot be parsed has no text content
+				for node in HtmlParser::default().parse(val).unwrap_or_default()
+				{
+					node.push_text_content(text);
+				}
+			}
+			HtmlNode::Element(el) => {
+				for child in &el.children {
+					child.push_text_content(text);
+				}
+			}
+			HtmlNode::Doctype | HtmlNode::Comment(_) => {}
+		}
+	}
+
 	/// recursively search for an html node with a matching id
 	pub fn query_selector_attr(
 		&mut self,
@@ -109,9 +143,10 @@ impl RenderHtml for HtmlNode {
 		match self {
 			HtmlNode::Doctype => html.push_str("<!DOCTYPE html>"),
 			HtmlNode::Comment(val) => {
-				html.push_str(&format!("<!-- {} -->", val))
+				html.push_str(&format!("<!-- {} -->", escape_html_comment(val)))
 			}
-			HtmlNode::Text(val) => html.push_str(val),
+			HtmlNode::Text(val) => html.push_str(&escape_html_text(val)),
+			HtmlNode::RawHtml(val) => html.push_str(val),
 			HtmlNode::Element(node) => node.render_inner(html),
 		}
 	}
@@ -121,17 +156,24 @@ impl RenderHtml for HtmlNode {
 			HtmlNode::Doctype => {
 				Self::push_pretty(html, indent, "<!DOCTYPE html>")
 			}
-			HtmlNode::Comment(val) => {
-				Self::push_pretty(html, indent, &format!("<!-- {} -->", val))
+			HtmlNode::Comment(val) => Self::push_pretty(
+				html,
+				indent,
+				&format!("<!-- {} -->", escape_html_comment(val)),
+			),
+			HtmlNode::Text(val) => {
+				Self::push_pretty(html, indent, &escape_html_text(val))
 			}
-			HtmlNode::Text(val) => Self::push_pretty(html, indent, val),
+			HtmlNode::RawHtml(val) => Self::push_pretty(html, indent, val),
 			HtmlNode::Element(node) => node.render_pretty_inner(html, indent),
 		}
 	}
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct HtmlElementNode {
 	pub tag: String,
+	/// Resolved from the parent element, see [Namespace::element]
+	pub namespace: Namespace,
 	pub self_closing: bool,
 	pub attributes: Vec<HtmlAttribute>,
 	pub children: Vec<HtmlNode>,
@@ -150,6 +192,7 @@ impl HtmlElementNode {
 	) -> Self {
 		Self {
 			tag: "script".to_string(),
+			namespace: Namespace::Html,
 			self_closing: false,
 			attributes,
 			children: vec![HtmlNode::Text(script)],
@@ -168,6 +211,13 @@ impl HtmlElementNode {
 			.any(|a| a.key == key && a.value.as_deref() == val)
 	}
 
+	/// returns true if the text content of this element should not be
+	/// entity escaped, ie `<script>` and `<style>`. Foreign elements
+	/// like `<svg><style>` are always escaped.
+	pub fn is_raw_text(&self) -> bool {
+		!self.namespace.is_foreign() && is_raw_text_element(&self.tag)
+	}
+
 	/// returns none if the attribute is not found or it has no value
 	pub fn get_attribute_value(&self, key: &str) -> Option<&str> {
 		for attr in &self.attributes {
@@ -177,6 +227,46 @@ impl HtmlElementNode {
 		}
 		None
 	}
+
+	/// returns true if the attribute is present, with or without a value
+	pub fn has_attribute(&self, key: &str) -> bool {
+		self.attributes.iter().any(|attr| attr.key == key)
+	}
+
+	/// set the value of an existing attribute or add a new one
+	pub fn set_attribute(&mut self, key: &str, value: Option<&str>) {
+		let value = value.map(|val| val.to_string());
+		match self.attributes.iter_mut().find(|attr| attr.key == key) {
+			Some(attr) => attr.value = value,
+			None => self.attributes.push(HtmlAttribute {
+				key: key.to_string(),
+				value,
+			}),
+		}
+	}
+
+	/// remove all attributes with a matching key
+	pub fn remove_attribute(&mut self, key: &str) {
+		self.attributes.retain(|attr| attr.key != key);
+	}
+
+	/// returns true if the whitespace separated `class` attribute
+	/// contains the class
+	pub fn has_class(&self, class: &str) -> bool {
+		self.get_attribute_value("class")
+			.map(|val| val.split_whitespace().any(|c| c == class))
+			.unwrap_or(false)
+	}
+
+	/// The concatenated text of the descendants of this element,
+	/// comments are ignored
+	pub fn text_content(&self) -> String {
+		let mut text = String::new();
+		for child in &self.children {
+			child.push_text_content(&mut text);
+		}
+		text
+	}
 }
 
 impl RenderHtml for HtmlElementNode {
@@ -193,7 +283,12 @@ impl RenderHtml for HtmlElementNode {
 			html.push('>');
 		}
 		for child in &self.children {
-			child.render_inner(html);
+			match child {
+				HtmlNode::Text(val) if self.is_raw_text() => {
+					html.push_str(&escape_raw_text(&self.tag, val))
+				}
+				child => child.render_inner(html),
+			}
 		}
 		html.push_str(&format!("</{}>", self.tag));
 	}
@@ -222,7 +317,14 @@ impl RenderHtml for HtmlElementNode {
 					html.pop();
 				}
 			}
-			child.render_pretty_inner(html, indent);
+			match child {
+				HtmlNode::Text(val) if self.is_raw_text() => Self::push_pretty(
+					html,
+					indent,
+					&escape_raw_text(&self.tag, val),
+				),
+				child => child.render_pretty_inner(html, indent),
+			}
 			if let HtmlNode::Text(_) = child {
 				prev_text_node = true;
 			} else {
@@ -233,7 +335,7 @@ impl RenderHtml for HtmlElementNode {
 		Self::push_pretty(html, indent, &format!("</{}>", self.tag));
 	}
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct HtmlAttribute {
 	pub key: String,
 	pub value: Option<String>,
@@ -246,7 +348,7 @@ impl RenderHtml for HtmlAttribute {
 		html.push_str(&self.key);
 		if let Some(value) = &self.value {
 			html.push_str("=\"");
-			html.push_str(value);
+			html.push_str(&escape_html_attribute(value));
 			html.push_str("\"");
 		}
 	}
@@ -284,6 +386,22 @@ pub struct HtmlConstants {
 	pub event_handler: &'static str,
 	/// the global vec that stores prehydrated events
 	pub event_store: &'static str,
+	/// the attribute for suspense boundary ids, used by both the
+	/// fallback placeholder and the streamed content
+	pub suspense_key: &'static str,
+	/// the global function that swaps a fallback with streamed content
+	pub suspense_resolver: &'static str,
+	/// the attribute for the placeholder of a component that returned
+	/// an error, see [ErrorBoundary]
+	pub error_key: &'static str,
+	/// the attribute for error boundary ids
+	pub error_boundary_key: &'static str,
+	/// the attribute marking the top level elements of a [ClientIsland]
+	pub island_key: &'static str,
+	/// the global function the wasm entry sets for hydrating an island
+	pub island_hydrate: &'static str,
+	/// the attribute for the script containing the [ResumableState]
+	pub state_key: &'static str,
 }
 
 impl Default for HtmlConstants {
@@ -293,6 +411,13 @@ impl Default for HtmlConstants {
 			loc_map_key: "data-beet-loc-map",
 			event_handler: "_beet_event_handler",
 			event_store: "_beet_event_store",
+			suspense_key: "data-beet-suspense",
+			suspense_resolver: "_beet_resolve_suspense",
+			error_key: "data-beet-error",
+			error_boundary_key: "data-beet-error-boundary",
+			island_key: "data-beet-island",
+			island_hydrate: "_beet_hydrate_island",
+			state_key: "data-beet-state",
 		}
 	}
 }
@@ -324,4 +449,13 @@ mod test {
 		// println!("{}", doc.render_pretty());
 		expect(doc.render_pretty()).to_be("<!DOCTYPE html>\n<html>\n\t<head>\n\t\t<title data-beet-rsx-idx=\"3\">\n\t\t\tTest \t\t\tfoo\t\t\tbar\n\t\t</title>\n\t</head>\n\t<body>\n\t\t<div foo=\"bar\" bazz>\n\t\t\t<p>\n\t\t\t\tTest\n\t\t\t</p>\n\t\t</div>\n\t</body>\n</html>");
 	}
+
+	#[test]
+	fn text_content() {
+		let raw = RawHtml::new("<b>trusted</b>");
+		let html = RsxToHtml::default().map_root(&rsx! {
+			<div>hello <!-- "comment" -->{raw}</div>
+		});
+		expect(html[0].text_content()).to_be("hello trusted");
+	}
 }
@@ -0,0 +1,570 @@
+use crate::prelude::*;
+
+/// Elements that cannot have children, and so are always closed.
+pub const VOID_ELEMENTS: [&str; 13] = [
+	"area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta",
+	"source", "track", "wbr",
+];
+
+/// Returns true if the tag is a [VOID_ELEMENTS]
+pub fn is_void_element(tag: &str) -> bool {
+	VOID_ELEMENTS
+		.iter()
+		.any(|void| void.eq_ignore_ascii_case(tag))
+}
+
+/// Parse a string of html into [HtmlNode]s. This is the inverse of
+/// [RenderHtml], so text, attributes and comments are decoded
+/// and rendering the parsed nodes results in the same html.
+///
+/// Like browsers the parser is forgiving of common mistakes,
+/// for instance unclosed `<p>` and `<li>` elements, or stray closing tags.
+/// Malformed markup like an unterminated tag or comment is an error.
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let nodes = HtmlParser::default()
+/// 	.parse("<div class=\"foo\">hello &amp; welcome<br></div>")
+/// 	.unwrap();
+/// assert_eq!(
+/// 	nodes.render(),
+/// 	"<div class=\"foo\">hello &amp; welcome<br/></div>"
+/// );
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct HtmlParser {
+	/// Remove text nodes that only contain whitespace,
+	/// useful for formatted html like static pages
+	pub trim_whitespace: bool,
+}
+
+impl HtmlParser {
+	/// Parse html, treating the structure as a fragment.
+	pub fn parse(&self, html: &str) -> ParseResult<Vec<HtmlNode>> {
+		let mut builder = TreeBuilder::default();
+		let mut cursor = Cursor { html, pos: 0 };
+		while !cursor.is_done() {
+			if cursor.starts_with("<!--") {
+				builder.push(HtmlNode::Comment(cursor.parse_comment()?));
+			} else if cursor.starts_with_ignore_case("<!doctype") {
+				cursor.take_until(">", "doctype")?;
+				builder.push(HtmlNode::Doctype);
+			} else if cursor.starts_with("</") {
+				let tag = cursor.parse_close_tag()?;
+				builder.close(&tag);
+			} else if cursor.starts_with("<!") || cursor.starts_with("<?") {
+				// bogus comments like cdata or processing instructions
+				cursor.pos += 2;
+				let value = cursor.take_until(">", "bogus comment")?;
+				builder.push(HtmlNode::Comment(value.to_string()));
+			} else if cursor.starts_with_tag() {
+				let mut el = cursor.parse_open_tag()?;
+				el.namespace = builder.namespace().element(&el.tag);
+				if el.is_raw_text() && !el.self_closing {
+					let text = cursor.parse_raw_text(&el.tag);
+					let mut el = el;
+					if !text.is_empty() {
+						el.children.push(HtmlNode::Text(text));
+					}
+					builder.push(el.into());
+				} else {
+					builder.open(el);
+				}
+			} else {
+				let text = cursor.parse_text();
+				if !(self.trim_whitespace && text.trim().is_empty()) {
+					builder.push(HtmlNode::Text(text));
+				}
+			}
+		}
+		Ok(builder.finish())
+	}
+
+	/// Parse html into a document, see [HtmlDocument::from_nodes]
+	pub fn parse_document(&self, html: &str) -> ParseResult<HtmlDocument> {
+		Ok(self.parse(html)?.into_document())
+	}
+}
+
+impl HtmlDocument {
+	/// Parse a html document with the default [HtmlParser]
+	pub fn parse(html: &str) -> ParseResult<Self> {
+		HtmlParser::default().parse_document(html)
+	}
+}
+
+/// Tracks the open elements, closing them as the html is parsed
+#[derive(Default)]
+struct TreeBuilder {
+	nodes: Vec<HtmlNode>,
+	open: Vec<HtmlElementNode>,
+}
+
+impl TreeBuilder {
+	/// push a node to the current parent, adjacent text is merged
+	fn push(&mut self, node: HtmlNode) {
+		let siblings = match self.open.last_mut() {
+			Some(parent) => &mut parent.children,
+			None => &mut self.nodes,
+		};
+		match (siblings.last_mut(), node) {
+			(Some(HtmlNode::Text(prev)), HtmlNode::Text(text)) => {
+				prev.push_str(&text)
+			}
+			(_, node) => siblings.push(node),
+		}
+	}
+
+	/// The namespace of the children of the current open element
+	fn namespace(&self) -> Namespace {
+		self.open
+			.last()
+			.map(|parent| parent.namespace.children(&parent.tag))
+			.unwrap_or_default()
+	}
+
+	fn open(&mut self, el: HtmlElementNode) {
+		while self
+			.open
+			.last()
+			.map(|parent| closes_implicitly(&parent.tag, &el.tag))
+			.unwrap_or(false)
+		{
+			self.pop();
+		}
+		if el.self_closing {
+			self.push(el.into());
+		} else {
+			self.open.push(el);
+		}
+	}
+
+	/// close the nearest open element with a matching tag,
+	/// closing tags without an open element are ignored
+	fn close(&mut self, tag: &str) {
+		if let Some(pos) = self
+			.open
+			.iter()
+			.rposition(|el| el.tag.eq_ignore_ascii_case(tag))
+		{
+			while self.open.len() > pos {
+				self.pop();
+			}
+		}
+	}
+
+	fn pop(&mut self) {
+		if let Some(el) = self.open.pop() {
+			self.push(el.into());
+		}
+	}
+
+	fn finish(mut self) -> Vec<HtmlNode> {
+		while !self.open.is_empty() {
+			self.pop();
+		}
+		self.nodes
+	}
+}
+
+/// Elements with optional end tags are closed by some siblings,
+/// ie `<li>foo<li>bar` is two list items.
+fn closes_implicitly(open: &str, next: &str) -> bool {
+	const CLOSES_P: [&str; 19] = [
+		"address",
+		"article",
+		"aside",
+		"blockquote",
+		"div",
+		"dl",
+		"fieldset",
+		"footer",
+		"form",
+		"h1",
+		"h2",
+		"h3",
+		"h4",
+		"h5",
+		"h6",
+		"header",
+		"hr",
+		"ol",
+		"ul",
+	];
+	let open = open.to_ascii_lowercase();
+	let next = next.to_ascii_lowercase();
+	match open.as_str() {
+		"p" => next == "p" || next == "table" || CLOSES_P.contains(&&*next),
+		"li" => next == "li",
+		"dt" | "dd" => next == "dt" || next == "dd",
+		"option" => next == "option" || next == "optgroup",
+		"tr" => next == "tr",
+		"td" | "th" => next == "td" || next == "th" || next == "tr",
+		_ => false,
+	}
+}
+
+struct Cursor<'a> {
+	html: &'a str,
+	pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+	fn is_done(&self) -> bool { self.pos >= self.html.len() }
+	fn rest(&self) -> &'a str { &self.html[self.pos..] }
+	fn starts_with(&self, pat: &str) -> bool { self.rest().starts_with(pat) }
+	fn starts_with_ignore_case(&self, pat: &str) -> bool {
+		self.rest()
+			.get(..pat.len())
+			.map(|start| start.eq_ignore_ascii_case(pat))
+			.unwrap_or(false)
+	}
+	/// a `<` followed by a letter, otherwise the `<` is text
+	fn starts_with_tag(&self) -> bool {
+		let mut chars = self.rest().chars();
+		chars.next() == Some('<')
+			&& chars
+				.next()
+				.map(|c| c.is_ascii_alphabetic())
+				.unwrap_or(false)
+	}
+
+	fn error(&self, msg: impl AsRef<str>) -> ParseError {
+		let line = self.html[..self.pos].lines().count().max(1);
+		ParseError::Other(format!("{} at line {}", msg.as_ref(), line))
+	}
+
+	/// take everything up to the pattern, consuming the pattern
+	fn take_until(&mut self, pat: &str, cx: &str) -> ParseResult<&'a str> {
+		let end = self
+			.rest()
+			.find(pat)
+			.ok_or_else(|| self.error(format!("Unterminated {}", cx)))?;
+		let value = &self.rest()[..end];
+		self.pos += end + pat.len();
+		Ok(value)
+	}
+
+	fn skip_whitespace(&mut self) {
+		let trimmed = self.rest().trim_start();
+		self.pos = self.html.len() - trimmed.len();
+	}
+
+	fn take_while(&mut self, func: impl Fn(char) -> bool) -> &'a str {
+		let rest = self.rest();
+		let end = rest.find(|c| !func(c)).unwrap_or(rest.len());
+		self.pos += end;
+		&rest[..end]
+	}
+
+	/// [RenderHtml] pads comments with a space
+	fn parse_comment(&mut self) -> ParseResult<String> {
+		self.pos += "<!--".len();
+		let value = self.take_until("-->", "comment")?;
+		let value = value.strip_prefix(' ').unwrap_or(value);
+		let value = value.strip_suffix(' ').unwrap_or(value);
+		Ok(value.replace("&lt;", "<").replace("&gt;", ">"))
+	}
+
+	fn parse_text(&mut self) -> String {
+		// consume at least one char, it may be a `<` that is not a tag
+		let first = self.rest().chars().next().map(|c| c.len_utf8());
+		let start = self.pos;
+		self.pos += first.unwrap_or(0);
+		self.take_while(|c| c != '<');
+		decode_html_entities(&self.html[start..self.pos])
+	}
+
+	/// the content of raw text elements, up to the closing tag,
+	/// reversing [escape_raw_text]
+	fn parse_raw_text(&mut self, tag: &str) -> String {
+		let closing = format!("</{}", tag.to_ascii_lowercase());
+		let lower = self.rest().to_ascii_lowercase();
+		let end = lower.find(&closing).unwrap_or(lower.len());
+		let text = &self.rest()[..end];
+		self.pos += end;
+		if !self.is_done() {
+			// a raw text element is always closed by its tag
+			self.pos += closing.len();
+			self.take_while(|c| c != '>');
+			self.pos += 1;
+		}
+		let escaped_closing = format!("<\\/{}", tag.to_ascii_lowercase());
+		let lower = text.to_ascii_lowercase();
+		let mut out = String::with_capacity(text.len());
+		let mut last = 0;
+		for (i, _) in lower.match_indices("<\\") {
+			let rest = &lower[i..];
+			if rest.starts_with(&escaped_closing) || rest.starts_with("<\\!--")
+			{
+				out.push_str(&text[last..i + 1]);
+				last = i + 2;
+			}
+		}
+		out.push_str(&text[last..]);
+		out
+	}
+
+	fn parse_tag_name(&mut self) -> &'a str {
+		self.take_while(|c| !c.is_whitespace() && c != '>' && c != '/')
+	}
+
+	fn parse_close_tag(&mut self) -> ParseResult<String> {
+		self.pos += "</".len();
+		let tag = self.parse_tag_name();
+		self.take_until(">", "closing tag")?;
+		Ok(tag.to_string())
+	}
+
+	fn parse_open_tag(&mut self) -> ParseResult<HtmlElementNode> {
+		self.pos += "<".len();
+		let tag = self.parse_tag_name().to_string();
+		let mut attributes = Vec::new();
+		loop {
+			self.skip_whitespace();
+			if self.is_done() {
+				return Err(self.error(format!("Unterminated tag <{}", tag)));
+			} else if self.starts_with("/>") {
+				self.pos += 2;
+				return Ok(HtmlElementNode {
+					tag,
+					namespace: Namespace::Html,
+					self_closing: true,
+					attributes,
+					children: Vec::new(),
+				});
+			} else if self.starts_with(">") {
+				self.pos += 1;
+				return Ok(HtmlElementNode {
+					self_closing: is_void_element(&tag),
+					tag,
+					namespace: Namespace::Html,
+					attributes,
+					children: Vec::new(),
+				});
+			} else if self.starts_with("/") {
+				// stray slash ie `<div / >`
+				self.pos += 1;
+			} else {
+				attributes.push(self.parse_attribute()?);
+			}
+		}
+	}
+
+	fn parse_attribute(&mut self) -> ParseResult<HtmlAttribute> {
+		let key = self
+			.take_while(|c| {
+				!c.is_whitespace() && c != '=' && c != '>' && c != '/'
+			})
+			.to_string();
+		if key.is_empty() {
+			return Err(self.error("Expected attribute"));
+		}
+		self.skip_whitespace();
+		if !self.starts_with("=") {
+			return Ok(HtmlAttribute { key, value: None });
+		}
+		self.pos += 1;
+		self.skip_whitespace();
+		let value = if self.starts_with("\"") {
+			self.pos += 1;
+			self.take_until("\"", "attribute value")?
+		} else if self.starts_with("'") {
+			self.pos += 1;
+			self.take_until("'", "attribute value")?
+		} else {
+			self.take_while(|c| !c.is_whitespace() && c != '>')
+		};
+		Ok(HtmlAttribute {
+			key,
+			value: Some(decode_html_entities(value)),
+		})
+	}
+}
+
+/// Decode named and numeric character references,
+/// unknown references are left as is.
+pub fn decode_html_entities(value: &str) -> String {
+	let mut out = String::with_capacity(value.len());
+	let mut rest = value;
+	while let Some(start) = rest.find('&') {
+		out.push_str(&rest[..start]);
+		rest = &rest[start..];
+		let decoded = rest.find(';').and_then(|end| {
+			let decoded = match &rest[1..end] {
+				"amp" => Some('&'),
+				"lt" => Some('<'),
+				"gt" => Some('>'),
+				"quot" => Some('"'),
+				"apos" => Some('\''),
+				"nbsp" => Some('\u{a0}'),
+				entity => entity
+					.strip_prefix("#x")
+					.or_else(|| entity.strip_prefix("#X"))
+					.and_then(|hex| u32::from_str_radix(hex, 16).ok())
+					.or_else(|| {
+						entity
+							.strip_prefix('#')
+							.and_then(|dec| dec.parse().ok())
+					})
+					.and_then(char::from_u32),
+			};
+			decoded.map(|c| (c, end))
+		});
+		match decoded {
+			Some((c, end)) => {
+				out.push(c);
+				rest = &rest[end + 1..];
+			}
+			None => {
+				out.push('&');
+				rest = &rest[1..];
+			}
+		}
+	}
+	out.push_str(rest);
+	out
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	fn round_trip(html: &str) -> String {
+		HtmlParser::default().parse(html).unwrap().render()
+	}
+
+	#[test]
+	fn elements() {
+		let nodes = HtmlParser::default()
+			.parse("<div id=foo hidden class='a b'>hello<br></div>")
+			.unwrap();
+		expect(&nodes).to_be(&vec![HtmlNode::Element(HtmlElementNode {
+			tag: "div".into(),
+			namespace: Default::default(),
+			self_closing: false,
+			attributes: vec![
+				HtmlAttribute {
+					key: "id".into(),
+					value: Some("foo".into()),
+				},
+				HtmlAttribute {
+					key: "hidden".into(),
+					value: None,
+				},
+				HtmlAttribute {
+					key: "class".into(),
+					value: Some("a b".into()),
+				},
+			],
+			children: vec![
+				HtmlNode::Text("hello".into()),
+				HtmlNode::Element(HtmlElementNode {
+					tag: "br".into(),
+					namespace: Default::default(),
+					self_closing: true,
+					attributes: vec![],
+					children: vec![],
+				}),
+			],
+		})]);
+	}
+
+	#[test]
+	fn decodes() {
+		expect(decode_html_entities(
+			"a &lt;b&gt; &amp;c &#39;&#x41;&foo; &",
+		))
+		.to_be("a <b> &c 'A&foo; &");
+		expect(
+			HtmlParser::default()
+				.parse("<!-- a &lt;b&gt; -->1 < 2")
+				.unwrap(),
+		)
+		.to_be(vec![
+			HtmlNode::Comment("a <b>".into()),
+			HtmlNode::Text("1 < 2".into()),
+		]);
+	}
+
+	#[test]
+	fn raw_text() {
+		let nodes = HtmlParser::default()
+			.parse("<script>if(a<b){}\"<\\/script>\"</SCRIPT><style></style>")
+			.unwrap();
+		expect(nodes).to_be(vec![
+			HtmlElementNode::inline_script(
+				"if(a<b){}\"</script>\"".into(),
+				vec![],
+			)
+			.into(),
+			HtmlElementNode {
+				tag: "style".into(),
+				namespace: Default::default(),
+				self_closing: false,
+				attributes: vec![],
+				children: vec![],
+			}
+			.into(),
+		]);
+	}
+
+	#[test]
+	fn forgiving() {
+		expect(round_trip(
+			"<ul><li>a<li>b</ul><p>one<p>two<div>three</span>",
+		))
+		.to_be(
+			"<ul><li>a</li><li>b</li></ul><p>one</p><p>two</p><div>three</div>",
+		);
+		expect(HtmlParser::default().parse("<div class=\"foo>").is_err())
+			.to_be_true();
+		expect(HtmlParser::default().parse("<!-- foo").is_err()).to_be_true();
+	}
+
+	#[test]
+	fn trim_whitespace() {
+		let parser = HtmlParser {
+			trim_whitespace: true,
+		};
+		expect(
+			parser
+				.parse("<div>\n\t<p> hi </p>\n</div>")
+				.unwrap()
+				.render(),
+		)
+		.to_be("<div><p> hi </p></div>");
+	}
+
+	#[test]
+	fn document() {
+		let doc = HtmlDocument::parse(
+			"<!DOCTYPE html><html><head><title>hi</title></head><body><div/></body></html>",
+		)
+		.unwrap();
+		expect(doc.head.len()).to_be(1);
+		expect(doc.render()).to_be(
+			"<!DOCTYPE html><html><head><title>hi</title></head><body><div/></body></html>",
+		);
+	}
+
+	#[test]
+	fn round_trips() {
+		let value = "<script>alert(\"pwned\")</script> & 'more'";
+		let script = RawHtml::new("<script>let a = 1 < 2;</script>");
+		let mut root = rsx! {
+			<div class="container" data-value={value} hidden>
+				<h1>Title {value}</h1>
+				<img src="foo.png" />
+				<p>some <b>bold</b> text</p>
+				{script}
+			</div>
+		};
+		let doc = RsxToResumableHtml::default().map_root(&mut root);
+		let html = doc.render();
+		let parsed = HtmlDocument::parse(&html).unwrap();
+		expect(parsed.render()).to_be(html);
+	}
+}
@@ -0,0 +1,920 @@
+use crate::prelude::*;
+
+/// A parsed list of css selectors, used to query [HtmlNode] trees
+/// with [HtmlDocument::query_selector] and friends.
+///
+/// This is a small matcher implemented directly over [HtmlNode]
+/// so that it is available without the `css` feature, ie in route tests.
+/// `parcel_selectors` is only a dependency of the `css` feature, and
+/// matching with it requires implementing its `Element` trait which
+/// expects parent and sibling pointers that [HtmlNode] does not have.
+/// Supported selectors:
+/// - type `div`, universal `*`, id `#foo` and class `.foo`
+/// - attributes `[foo]`, `[foo=bar]`, `~=`, `|=`, `^=`, `$=`, `*=`
+/// 	and the case-insensitive flag `[foo=bar i]`
+/// - combinators: descendant ` `, child `>`, next sibling `+`
+/// 	and subsequent sibling `~`
+/// - lists `a, b`
+/// - pseudo classes `:first-child`, `:last-child`, `:only-child`,
+/// 	`:nth-child()`, `:nth-last-child()`, `:first-of-type`,
+/// 	`:last-of-type`, `:only-of-type`, `:nth-of-type()`,
+/// 	`:nth-last-of-type()`, `:empty`, `:not()`, `:is()` and `:where()`
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let doc = HtmlDocument::parse(
+/// 	"<ul><li>a</li><li class=\"active\">b</li></ul>"
+/// ).unwrap();
+/// let el = doc.query_selector("ul > li.active:last-child").unwrap().unwrap();
+/// assert_eq!(el.text_content(), "b");
+/// assert_eq!(doc.query_selector_all("li:not(.active)").unwrap().len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector(Vec<ComplexSelector>);
+
+#[derive(Debug, Clone, PartialEq)]
+struct ComplexSelector {
+	/// left to right, each combinator is the relation to the
+	/// previous compound, the first combinator is ignored
+	parts: Vec<(Combinator, CompoundSelector)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+	Descendant,
+	Child,
+	NextSibling,
+	SubsequentSibling,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CompoundSelector {
+	/// `None` for the universal selector
+	tag: Option<String>,
+	simple: Vec<SimpleSelector>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SimpleSelector {
+	Id(String),
+	Class(String),
+	Attribute {
+		key: String,
+		op: Option<(AttrOp, String, bool)>,
+	},
+	Pseudo(PseudoClass),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AttrOp {
+	/// `=`
+	Equals,
+	/// `~=`
+	Includes,
+	/// `|=`
+	DashMatch,
+	/// `^=`
+	Prefix,
+	/// `$=`
+	Suffix,
+	/// `*=`
+	Substring,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PseudoClass {
+	NthChild { nth: Nth, of_type: bool, last: bool },
+	OnlyChild { of_type: bool },
+	Empty,
+	Not(Selector),
+	Is(Selector),
+}
+
+/// The `an+b` of nth selectors
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Nth {
+	a: i32,
+	b: i32,
+}
+
+impl Nth {
+	/// whether the one-based position matches `an+b` for some `n >= 0`
+	fn matches(&self, pos: i32) -> bool {
+		match self.a {
+			0 => pos == self.b,
+			a => {
+				let diff = pos - self.b;
+				diff % a == 0 && diff / a >= 0
+			}
+		}
+	}
+}
+
+impl Selector {
+	/// Parse a selector list, ie `div > p.foo, #bar`
+	pub fn parse(selector: &str) -> ParseResult<Self> {
+		let mut parser = SelectorParser {
+			chars: selector.chars().collect(),
+			pos: 0,
+		};
+		let list = parser.parse_list()?;
+		parser.skip_whitespace();
+		if let Some(c) = parser.peek() {
+			return Err(parser.error(format!("Unexpected character `{c}`")));
+		}
+		Ok(list)
+	}
+
+	/// Returns true if the element at this location matches any selector
+	fn matches(&self, el: &ElementCx) -> bool {
+		self.0.iter().any(|complex| complex.matches(el))
+	}
+
+	/// All matching elements in the nodes and their descendants,
+	/// in document order
+	pub fn query_all<'a>(
+		&self,
+		nodes: &'a [HtmlNode],
+	) -> Vec<&'a HtmlElementNode> {
+		self.query_paths(nodes)
+			.into_iter()
+			.map(|path| ElementCx { roots: nodes, path }.element())
+			.collect()
+	}
+
+	/// The first matching element in the nodes and their descendants
+	pub fn query<'a>(
+		&self,
+		nodes: &'a [HtmlNode],
+	) -> Option<&'a HtmlElementNode> {
+		self.query_all(nodes).into_iter().next()
+	}
+
+	/// Call the function for every matching element, useful for
+	/// transforms. Elements are visited in reverse document order
+	/// so that changing the children of an element does not
+	/// affect the elements yet to be visited.
+	pub fn for_each_mut(
+		&self,
+		nodes: &mut [HtmlNode],
+		mut func: impl FnMut(&mut HtmlElementNode),
+	) {
+		for path in self.query_paths(nodes).into_iter().rev() {
+			let (first, rest) =
+				path.split_first().expect("paths are not empty");
+			let mut node = &mut nodes[*first];
+			for idx in rest {
+				let HtmlNode::Element(el) = node else {
+					unreachable!("paths only contain elements")
+				};
+				node = &mut el.children[*idx];
+			}
+			if let HtmlNode::Element(el) = node {
+				func(el);
+			}
+		}
+	}
+
+	/// the paths of all matching elements, depth first
+	fn query_paths(&self, nodes: &[HtmlNode]) -> Vec<Vec<usize>> {
+		fn walk(
+			selector: &Selector,
+			roots: &[HtmlNode],
+			children: &[HtmlNode],
+			path: &mut Vec<usize>,
+			out: &mut Vec<Vec<usize>>,
+		) {
+			for (idx, child) in children.iter().enumerate() {
+				let HtmlNode::Element(el) = child else {
+					continue;
+				};
+				path.push(idx);
+				let cx = ElementCx {
+					roots,
+					path: path.clone(),
+				};
+				if selector.matches(&cx) {
+					out.push(cx.path);
+				}
+				walk(selector, roots, &el.children, path, out);
+				path.pop();
+			}
+		}
+		let mut out = Vec::new();
+		walk(self, nodes, nodes, &mut Vec::new(), &mut out);
+		out
+	}
+}
+
+impl ComplexSelector {
+	fn matches(&self, el: &ElementCx) -> bool {
+		self.matches_from(self.parts.len() - 1, el)
+	}
+
+	/// match right to left, starting at the compound at this index
+	fn matches_from(&self, idx: usize, el: &ElementCx) -> bool {
+		let (combinator, compound) = &self.parts[idx];
+		if !compound.matches(el) {
+			return false;
+		}
+		if idx == 0 {
+			return true;
+		}
+		match combinator {
+			Combinator::Descendant => {
+				let mut parent = el.parent();
+				while let Some(el) = parent {
+					if self.matches_from(idx - 1, &el) {
+						return true;
+					}
+					parent = el.parent();
+				}
+				false
+			}
+			Combinator::Child => el
+				.parent()
+				.map(|parent| self.matches_from(idx - 1, &parent))
+				.unwrap_or(false),
+			Combinator::NextSibling => el
+				.prev_siblings()
+				.next()
+				.map(|sibling| self.matches_from(idx - 1, &sibling))
+				.unwrap_or(false),
+			Combinator::SubsequentSibling => el
+				.prev_siblings()
+				.any(|sibling| self.matches_from(idx - 1, &sibling)),
+		}
+	}
+}
+
+impl CompoundSelector {
+	fn matches(&self, cx: &ElementCx) -> bool {
+		let el = cx.element();
+		if let Some(tag) = &self.tag {
+			if !el.tag.eq_ignore_ascii_case(tag) {
+				return false;
+			}
+		}
+		self.simple.iter().all(|simple| match simple {
+			SimpleSelector::Id(id) => {
+				el.get_attribute_value("id") == Some(id.as_str())
+			}
+			SimpleSelector::Class(class) => el.has_class(class),
+			SimpleSelector::Attribute { key, op } => {
+				let Some(attr) = el
+					.attributes
+					.iter()
+					.find(|attr| attr.key.eq_ignore_ascii_case(key))
+				else {
+					return false;
+				};
+				let Some((op, expected, case_insensitive)) = op else {
+					return true;
+				};
+				let value = attr.value.as_deref().unwrap_or_default();
+				let (value, expected) = if *case_insensitive {
+					(value.to_lowercase(), expected.to_lowercase())
+				} else {
+					(value.to_string(), expected.clone())
+				};
+				match op {
+					AttrOp::Equals => value == expected,
+					AttrOp::Includes => {
+						value.split_whitespace().any(|word| word == expected)
+					}
+					AttrOp::DashMatch => {
+						value == expected
+							|| value.starts_with(&format!("{expected}-"))
+					}
+					AttrOp::Prefix => {
+						!expected.is_empty() && value.starts_with(&expected)
+					}
+					AttrOp::Suffix => {
+						!expected.is_empty() && value.ends_with(&expected)
+					}
+					AttrOp::Substring => {
+						!expected.is_empty() && value.contains(&expected)
+					}
+				}
+			}
+			SimpleSelector::Pseudo(pseudo) => match pseudo {
+				PseudoClass::NthChild { nth, of_type, last } => {
+					nth.matches(cx.position(*of_type, *last))
+				}
+				PseudoClass::OnlyChild { of_type } => {
+					cx.position(*of_type, false) == 1
+						&& cx.position(*of_type, true) == 1
+				}
+				PseudoClass::Empty => el.children.iter().all(|child| {
+					matches!(child, HtmlNode::Comment(_))
+						|| matches!(child, HtmlNode::Text(text) if text.is_empty())
+				}),
+				PseudoClass::Not(selector) => !selector.matches(cx),
+				PseudoClass::Is(selector) => selector.matches(cx),
+			},
+		})
+	}
+}
+
+/// The location of an element, tracked by its path from the root nodes
+/// so that parents and siblings can be found.
+struct ElementCx<'a> {
+	roots: &'a [HtmlNode],
+	path: Vec<usize>,
+}
+
+impl<'a> ElementCx<'a> {
+	fn node_at(&self, path: &[usize]) -> &'a HtmlNode {
+		let mut node = &self.roots[path[0]];
+		for idx in &path[1..] {
+			let HtmlNode::Element(el) = node else {
+				unreachable!("paths only contain elements")
+			};
+			node = &el.children[*idx];
+		}
+		node
+	}
+
+	fn element(&self) -> &'a HtmlElementNode {
+		match self.node_at(&self.path) {
+			HtmlNode::Element(el) => el,
+			_ => unreachable!("paths only contain elements"),
+		}
+	}
+
+	fn parent(&self) -> Option<Self> {
+		if self.path.len() <= 1 {
+			return None;
+		}
+		Some(Self {
+			roots: self.roots,
+			path: self.path[..self.path.len() - 1].to_vec(),
+		})
+	}
+
+	/// the siblings of this element, including itself
+	fn siblings(&self) -> &'a [HtmlNode] {
+		match self.parent() {
+			Some(parent) => &parent.element().children,
+			None => self.roots,
+		}
+	}
+
+	/// previous element siblings, nearest first
+	fn prev_siblings(&self) -> impl Iterator<Item = ElementCx<'a>> + '_ {
+		let idx = *self.path.last().expect("paths are not empty");
+		self.siblings()[..idx]
+			.iter()
+			.enumerate()
+			.rev()
+			.filter(|(_, node)| matches!(node, HtmlNode::Element(_)))
+			.map(move |(idx, _)| {
+				let mut path = self.path.clone();
+				*path.last_mut().unwrap() = idx;
+				ElementCx {
+					roots: self.roots,
+					path,
+				}
+			})
+	}
+
+	/// The one-based position of this element among its element
+	/// siblings, optionally of the same type or counting from the end
+	fn position(&self, of_type: bool, from_end: bool) -> i32 {
+		let idx = *self.path.last().expect("paths are not empty");
+		let tag = &self.element().tag;
+		let siblings = self.siblings();
+		let counted = |node: &HtmlNode| match node {
+			HtmlNode::Element(el) => {
+				!of_type || el.tag.eq_ignore_ascii_case(tag)
+			}
+			_ => false,
+		};
+		let count = if from_end {
+			siblings[idx + 1..]
+				.iter()
+				.filter(|node| counted(node))
+				.count()
+		} else {
+			siblings[..idx].iter().filter(|node| counted(node)).count()
+		};
+		count as i32 + 1
+	}
+}
+
+struct SelectorParser {
+	chars: Vec<char>,
+	pos: usize,
+}
+
+impl SelectorParser {
+	fn error(&self, msg: impl AsRef<str>) -> ParseError {
+		ParseError::Other(format!(
+			"Invalid selector `{}`: {}",
+			self.chars.iter().collect::<String>(),
+			msg.as_ref()
+		))
+	}
+	fn peek(&self) -> Option<char> { self.chars.get(self.pos).copied() }
+	fn next(&mut self) -> Option<char> {
+		let c = self.peek();
+		self.pos += 1;
+		c
+	}
+	fn eat(&mut self, c: char) -> bool {
+		if self.peek() == Some(c) {
+			self.pos += 1;
+			true
+		} else {
+			false
+		}
+	}
+	fn expect(&mut self, c: char) -> ParseResult<()> {
+		if self.eat(c) {
+			Ok(())
+		} else {
+			Err(self.error(format!("Expected `{c}`")))
+		}
+	}
+	/// returns true if any whitespace was skipped
+	fn skip_whitespace(&mut self) -> bool {
+		let start = self.pos;
+		while self.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+			self.pos += 1;
+		}
+		self.pos > start
+	}
+
+	fn parse_list(&mut self) -> ParseResult<Selector> {
+		let mut list = vec![self.parse_complex()?];
+		loop {
+			self.skip_whitespace();
+			if !self.eat(',') {
+				return Ok(Selector(list));
+			}
+			list.push(self.parse_complex()?);
+		}
+	}
+
+	fn parse_complex(&mut self) -> ParseResult<ComplexSelector> {
+		self.skip_whitespace();
+		let mut parts = vec![(Combinator::Descendant, self.parse_compound()?)];
+		loop {
+			let whitespace = self.skip_whitespace();
+			let combinator = match self.peek() {
+				Some('>') => Combinator::Child,
+				Some('+') => Combinator::NextSibling,
+				Some('~') => Combinator::SubsequentSibling,
+				Some(',') | Some(')') | None => break,
+				Some(_) if whitespace => {
+					parts
+						.push((Combinator::Descendant, self.parse_compound()?));
+					continue;
+				}
+				Some(c) => {
+					return Err(
+						self.error(format!("Unexpected character `{c}`"))
+					);
+				}
+			};
+			self.pos += 1;
+			self.skip_whitespace();
+			parts.push((combinator, self.parse_compound()?));
+		}
+		Ok(ComplexSelector { parts })
+	}
+
+	fn parse_compound(&mut self) -> ParseResult<CompoundSelector> {
+		let mut compound = CompoundSelector::default();
+		let start = self.pos;
+		if self.eat('*') {
+		} else if self.peek().map(is_ident_char).unwrap_or(false) {
+			compound.tag = Some(self.parse_ident()?);
+		}
+		loop {
+			match self.peek() {
+				Some('#') => {
+					self.pos += 1;
+					compound
+						.simple
+						.push(SimpleSelector::Id(self.parse_ident()?));
+				}
+				Some('.') => {
+					self.pos += 1;
+					compound
+						.simple
+						.push(SimpleSelector::Class(self.parse_ident()?));
+				}
+				Some('[') => {
+					self.pos += 1;
+					compound.simple.push(self.parse_attribute()?);
+				}
+				Some(':') => {
+					self.pos += 1;
+					if self.peek() == Some(':') {
+						return Err(
+							self.error("Pseudo elements are not supported")
+						);
+					}
+					compound
+						.simple
+						.push(SimpleSelector::Pseudo(self.parse_pseudo()?));
+				}
+				_ => break,
+			}
+		}
+		if self.pos == start {
+			return Err(self.error("Expected a selector"));
+		}
+		Ok(compound)
+	}
+
+	fn parse_ident(&mut self) -> ParseResult<String> {
+		let mut ident = String::new();
+		while let Some(c) = self.peek() {
+			if c == '\\' {
+				self.pos += 1;
+				ident.push(
+					self.next().ok_or_else(|| self.error("Invalid escape"))?,
+				);
+			} else if is_ident_char(c) {
+				self.pos += 1;
+				ident.push(c);
+			} else {
+				break;
+			}
+		}
+		if ident.is_empty() {
+			Err(self.error("Expected an identifier"))
+		} else {
+			Ok(ident)
+		}
+	}
+
+	fn parse_attribute(&mut self) -> ParseResult<SimpleSelector> {
+		self.skip_whitespace();
+		let key = self.parse_ident()?;
+		self.skip_whitespace();
+		if self.eat(']') {
+			return Ok(SimpleSelector::Attribute { key, op: None });
+		}
+		let op = match self.next() {
+			Some('=') => AttrOp::Equals,
+			Some(c) => {
+				let op = match c {
+					'~' => AttrOp::Includes,
+					'|' => AttrOp::DashMatch,
+					'^' => AttrOp::Prefix,
+					'$' => AttrOp::Suffix,
+					'*' => AttrOp::Substring,
+					_ => return Err(self.error("Invalid attribute operator")),
+				};
+				self.expect('=')?;
+				op
+			}
+			None => return Err(self.error("Unterminated attribute selector")),
+		};
+		self.skip_whitespace();
+		let value = match self.peek() {
+			Some(quote @ ('"' | '\'')) => {
+				self.pos += 1;
+				let mut value = String::new();
+				loop {
+					match self.next() {
+						Some(c) if c == quote => break,
+						Some('\\') => value.push(
+							self.next()
+								.ok_or_else(|| self.error("Invalid escape"))?,
+						),
+						Some(c) => value.push(c),
+						None => return Err(self.error("Unterminated string")),
+					}
+				}
+				value
+			}
+			_ => self.parse_ident()?,
+		};
+		self.skip_whitespace();
+		let case_insensitive = self.eat('i') || self.eat('I');
+		if !case_insensitive {
+			// the explicit case-sensitive flag
+			let _ = self.eat('s') || self.eat('S');
+		}
+		self.skip_whitespace();
+		self.expect(']')?;
+		Ok(SimpleSelector::Attribute {
+			key,
+			op: Some((op, value, case_insensitive)),
+		})
+	}
+
+	fn parse_pseudo(&mut self) -> ParseResult<PseudoClass> {
+		let name = self.parse_ident()?.to_ascii_lowercase();
+		let nth = |a, b| Nth { a, b };
+		let pseudo = match name.as_str() {
+			"first-child" => PseudoClass::NthChild {
+				nth: nth(0, 1),
+				of_type: false,
+				last: false,
+			},
+			"last-child" => PseudoClass::NthChild {
+				nth: nth(0, 1),
+				of_type: false,
+				last: true,
+			},
+			"first-of-type" => PseudoClass::NthChild {
+				nth: nth(0, 1),
+				of_type: true,
+				last: false,
+			},
+			"last-of-type" => PseudoClass::NthChild {
+				nth: nth(0, 1),
+				of_type: true,
+				last: true,
+			},
+			"only-child" => PseudoClass::OnlyChild { of_type: false },
+			"only-of-type" => PseudoClass::OnlyChild { of_type: true },
+			"empty" => PseudoClass::Empty,
+			"nth-child" | "nth-last-child" | "nth-of-type"
+			| "nth-last-of-type" => {
+				self.expect('(')?;
+				let nth = self.parse_nth()?;
+				PseudoClass::NthChild {
+					nth,
+					of_type: name.ends_with("of-type"),
+					last: name.starts_with("nth-last"),
+				}
+			}
+			"not" | "is" | "where" => {
+				self.expect('(')?;
+				let list = self.parse_list()?;
+				self.skip_whitespace();
+				self.expect(')')?;
+				if name == "not" {
+					PseudoClass::Not(list)
+				} else {
+					PseudoClass::Is(list)
+				}
+			}
+			_ => {
+				return Err(
+					self.error(format!("Unsupported pseudo class `:{name}`"))
+				);
+			}
+		};
+		Ok(pseudo)
+	}
+
+	/// parse the `an+b` argument up to and including the closing paren
+	fn parse_nth(&mut self) -> ParseResult<Nth> {
+		let mut arg = String::new();
+		loop {
+			match self.next() {
+				Some(')') => break,
+				Some(c) if c.is_whitespace() => {}
+				Some(c) => arg.push(c.to_ascii_lowercase()),
+				None => return Err(self.error("Expected `)`")),
+			}
+		}
+		let invalid = || self.error(format!("Invalid nth argument `{arg}`"));
+		let parse_int = |val: &str| val.parse::<i32>().map_err(|_| invalid());
+		match arg.as_str() {
+			"odd" => return Ok(Nth { a: 2, b: 1 }),
+			"even" => return Ok(Nth { a: 2, b: 0 }),
+			_ => {}
+		}
+		match arg.split_once('n') {
+			Some((a, b)) => {
+				let a = match a {
+					"" | "+" => 1,
+					"-" => -1,
+					a => parse_int(a)?,
+				};
+				let b = match b {
+					"" => 0,
+					b if b.starts_with('+') || b.starts_with('-') => {
+						parse_int(b.trim_start_matches('+'))?
+					}
+					_ => return Err(invalid()),
+				};
+				Ok(Nth { a, b })
+			}
+			None => Ok(Nth {
+				a: 0,
+				b: parse_int(arg.trim_start_matches('+'))?,
+			}),
+		}
+	}
+}
+
+fn is_ident_char(c: char) -> bool {
+	c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii()
+}
+
+impl HtmlDocument {
+	/// The first element in the head or body matching the selector.
+	/// ## Errors
+	/// If the selector is invalid, see [Selector::parse]
+	pub fn query_selector(
+		&self,
+		selector: &str,
+	) -> ParseResult<Option<&HtmlElementNode>> {
+		let selector = Selector::parse(selector)?;
+		Ok(selector
+			.query(&self.head)
+			.or_else(|| selector.query(&self.body)))
+	}
+	/// All elements in the head and body matching the selector,
+	/// in document order.
+	/// ## Errors
+	/// If the selector is invalid, see [Selector::parse]
+	pub fn query_selector_all(
+		&self,
+		selector: &str,
+	) -> ParseResult<Vec<&HtmlElementNode>> {
+		let selector = Selector::parse(selector)?;
+		let mut out = selector.query_all(&self.head);
+		out.extend(selector.query_all(&self.body));
+		Ok(out)
+	}
+	/// The text content of the body
+	pub fn text_content(&self) -> String {
+		self.body.iter().map(|node| node.text_content()).collect()
+	}
+}
+
+impl HtmlNode {
+	/// The first element matching the selector, including this node.
+	/// ## Errors
+	/// If the selector is invalid, see [Selector::parse]
+	pub fn query_selector(
+		&self,
+		selector: &str,
+	) -> ParseResult<Option<&HtmlElementNode>> {
+		Ok(Selector::parse(selector)?.query(std::slice::from_ref(self)))
+	}
+	/// All elements matching the selector, including this node.
+	/// ## Errors
+	/// If the selector is invalid, see [Selector::parse]
+	pub fn query_selector_all(
+		&self,
+		selector: &str,
+	) -> ParseResult<Vec<&HtmlElementNode>> {
+		Ok(Selector::parse(selector)?.query_all(std::slice::from_ref(self)))
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	fn doc() -> HtmlDocument {
+		HtmlDocument::parse(
+			r#"
+<html>
+	<head><title>Test</title><meta name="description" content="Hello World"></head>
+	<body>
+		<nav id="main-nav" class="nav dark">
+			<a href="/">Home</a>
+			<a href="/docs/intro" lang="en-US">Docs</a>
+			<a href="https://example.com" target="_blank">External</a>
+		</nav>
+		<main>
+			<h1>Title</h1>
+			<p>one</p>
+			<p class="note">two <b>bold</b></p>
+			<div><p>three</p></div>
+			<span></span>
+		</main>
+	</body>
+</html>"#,
+		)
+		.unwrap()
+	}
+
+	fn texts(doc: &HtmlDocument, selector: &str) -> Vec<String> {
+		doc.query_selector_all(selector)
+			.unwrap()
+			.into_iter()
+			.map(|el| el.text_content().trim().to_string())
+			.collect()
+	}
+
+	#[test]
+	fn simple() {
+		let doc = doc();
+		expect(doc.query_selector("title").unwrap().unwrap().text_content())
+			.to_be("Test");
+		expect(texts(&doc, "#main-nav a")).to_be(vec![
+			"Home".to_string(),
+			"Docs".into(),
+			"External".into(),
+		]);
+		expect(texts(&doc, ".nav.dark > a:first-child"))
+			.to_be(vec!["Home".to_string()]);
+		expect(doc.query_selector("*").unwrap().unwrap().tag.as_str())
+			.to_be("title");
+		expect(doc.query_selector("nav.light").unwrap()).to_be_none();
+	}
+
+	#[test]
+	fn attributes() {
+		let doc = doc();
+		expect(texts(&doc, "a[target]")).to_be(vec!["External".to_string()]);
+		expect(texts(&doc, "a[href^=\"/docs\"]"))
+			.to_be(vec!["Docs".to_string()]);
+		expect(texts(&doc, "a[href$='.com']"))
+			.to_be(vec!["External".to_string()]);
+		expect(texts(&doc, "a[href*=doc]")).to_be(vec!["Docs".to_string()]);
+		expect(texts(&doc, "[lang|=en]")).to_be(vec!["Docs".to_string()]);
+		expect(texts(&doc, "[class~=dark] [href=\"/\"]"))
+			.to_be(vec!["Home".to_string()]);
+		expect(
+			doc.query_selector_all("meta[content=\"hello world\" i]")
+				.unwrap()
+				.len(),
+		)
+		.to_be(1);
+	}
+
+	#[test]
+	fn combinators() {
+		let doc = doc();
+		expect(texts(&doc, "main > p"))
+			.to_be(vec!["one".to_string(), "two bold".into()]);
+		expect(texts(&doc, "main p")).to_be(vec![
+			"one".to_string(),
+			"two bold".into(),
+			"three".into(),
+		]);
+		expect(texts(&doc, "h1 + p")).to_be(vec!["one".to_string()]);
+		expect(texts(&doc, "h1 ~ p"))
+			.to_be(vec!["one".to_string(), "two bold".into()]);
+		expect(texts(&doc, "h1, b"))
+			.to_be(vec!["Title".to_string(), "bold".into()]);
+	}
+
+	#[test]
+	fn pseudo() {
+		let doc = doc();
+		expect(texts(&doc, "main > :nth-child(2n+1)")).to_be(vec![
+			"Title".to_string(),
+			"two bold".into(),
+			"".into(),
+		]);
+		expect(texts(&doc, "main > p:nth-of-type(2)"))
+			.to_be(vec!["two bold".to_string()]);
+		expect(texts(&doc, "main > :nth-last-child(-n+2)"))
+			.to_be(vec!["three".to_string(), "".into()]);
+		expect(texts(&doc, "main > p:last-of-type"))
+			.to_be(vec!["two bold".to_string()]);
+		expect(texts(&doc, "div > p:only-child"))
+			.to_be(vec!["three".to_string()]);
+		expect(doc.query_selector("span:empty").unwrap()).to_be_some();
+		expect(texts(&doc, "main > p:not(.note)"))
+			.to_be(vec!["one".to_string()]);
+		expect(texts(&doc, ":is(h1, b)"))
+			.to_be(vec!["Title".to_string(), "bold".into()]);
+	}
+
+	#[test]
+	fn errors() {
+		expect(Selector::parse("div >").is_err()).to_be_true();
+		expect(Selector::parse("a[href").is_err()).to_be_true();
+		expect(Selector::parse("p::before").is_err()).to_be_true();
+		expect(Selector::parse(":nth-child(foo)").is_err()).to_be_true();
+		expect(Selector::parse(":hover").is_err()).to_be_true();
+		expect(doc().query_selector("div >").is_err()).to_be_true();
+		expect(doc().query_selector_all("a[href").is_err()).to_be_true();
+	}
+
+	#[test]
+	fn transform() {
+		let mut doc = doc();
+		Selector::parse("main p")
+			.unwrap()
+			.for_each_mut(&mut doc.body, |el| {
+				el.set_attribute("data-seen", None);
+			});
+		expect(doc.query_selector_all("[data-seen]").unwrap().len()).to_be(3);
+	}
+
+	#[test]
+	fn rsx() {
+		let root = rsx! {
+			<div>
+				<h1 class="title">hello</h1>
+			</div>
+		};
+		let doc = root.build_document().unwrap();
+		expect(
+			doc.query_selector("div > .title")
+				.unwrap()
+				.unwrap()
+				.text_content(),
+		)
+		.to_be("hello");
+	}
+}
@@ -1,8 +1,24 @@
+mod escape_html;
+pub use escape_html::*;
 mod html_document;
+mod html_head;
+mod html_namespace;
 mod html_node;
+mod html_parser;
+mod html_selector;
+#[cfg(feature = "serde")]
+mod resumable_state;
 mod rsx_to_resumable_html;
 pub use html_document::*;
+pub use html_head::*;
+pub use html_namespace::*;
 pub use html_node::*;
+pub use html_parser::*;
+pub use html_selector::*;
+#[cfg(feature = "serde")]
+pub use resumable_state::*;
 mod rsx_to_html;
 pub use rsx_to_html::*;
+mod rsx_to_html_stream;
+pub use rsx_to_html_stream::*;
 pub use rsx_to_resumable_html::*;
@@ -0,0 +1,192 @@
+use crate::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+thread_local! {
+	static RESUMABLE_STATE: RefCell<ResumableState> = Default::default();
+}
+
+/// Transfers the values of signals from the server to the client so that
+/// hydration starts from the rendered state instead of the initial
+/// rust values, avoiding a flash of the initial state.
+///
+/// Values are matched by the order they are created, which is the
+/// same on the server and client because both run the same app.
+/// 1. On the server, [RsxToResumableHtml] serializes tracked values.
+/// 2. On the client, `BeetDom::hydrate` loads them before the app is created.
+/// 	While resuming, effects are subscribed by the initial render instead
+/// 	of running again, see [ResumableState::is_resuming].
+///
+/// Values are tracked until the next render, which serializes them
+/// or for renders that are not resumable, discards them.
+///
+/// Usually this is used via a runtime's resumable signal:
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// # use beet_rsx::sigfault::resumable_signal;
+/// let (get, set) = resumable_signal(1);
+/// set(2);
+/// let doc = RsxToResumableHtml::default().map_root(&mut rsx! { <div>{get}</div> });
+/// // in the browser this is done by BeetDom::hydrate
+/// ResumableState::load_document(&doc).unwrap();
+/// let (get, _) = resumable_signal(1);
+/// assert_eq!(get(), 2);
+/// ```
+#[derive(Default)]
+pub struct ResumableState {
+	/// serializers for each tracked value, called when rendering
+	tracked: Vec<Box<dyn Fn() -> ParseResult<String>>>,
+	/// values loaded from the document, consumed in order
+	loaded: VecDeque<String>,
+	/// whether state was loaded and the app is yet to be hydrated
+	resuming: bool,
+}
+
+impl ResumableState {
+	/// Returns the next loaded value, or the initial value if there is none.
+	/// If the value cannot be deserialized, ie the app has changed since
+	/// it was rendered, the initial value is used.
+	pub fn resume<T: DeserializeOwned>(initial: T) -> T {
+		RESUMABLE_STATE
+			.with(|state| state.borrow_mut().loaded.pop_front())
+			.and_then(|value| ron::from_str(&value).ok())
+			.unwrap_or(initial)
+	}
+
+	/// Track a value to be serialized by [RsxToResumableHtml].
+	/// The getter is called when rendering so it should not subscribe
+	/// to any signals.
+	pub fn track<T: Serialize>(get: impl 'static + Fn() -> T) {
+		RESUMABLE_STATE.with(|state| {
+			state.borrow_mut().tracked.push(Box::new(move || {
+				ron::to_string(&get())
+					.map_err(|err| ParseError::serde(err.to_string()))
+			}));
+		});
+	}
+
+	/// Returns true if any values are tracked
+	pub fn is_tracking() -> bool {
+		RESUMABLE_STATE.with(|state| !state.borrow().tracked.is_empty())
+	}
+
+	/// Returns true if state has been loaded and not yet cleared, ie
+	/// while `BeetDom::hydrate` creates the app. The html already
+	/// matches the resumed values so runtimes skip the first run of effects,
+	/// subscribing them while rendering the initial value instead.
+	pub fn is_resuming() -> bool {
+		RESUMABLE_STATE.with(|state| state.borrow().resuming)
+	}
+
+	/// Discard the tracked values without serializing them,
+	/// called by renders that are not resumable.
+	pub fn clear_tracked() {
+		RESUMABLE_STATE.with(|state| state.borrow_mut().tracked.clear());
+	}
+
+	/// Serialize the current value of all tracked values,
+	/// clearing them so the next app starts from scratch.
+	/// A value that fails to serialize is stored as an empty string
+	/// so that it resumes from its initial value without shifting
+	/// the values after it.
+	pub fn serialize() -> ParseResult<String> {
+		let tracked = RESUMABLE_STATE
+			.with(|state| std::mem::take(&mut state.borrow_mut().tracked));
+		let values = tracked
+			.iter()
+			.map(|get| get().unwrap_or_default())
+			.collect::<Vec<_>>();
+		ron::to_string(&values)
+			.map_err(|err| ParseError::serde(err.to_string()))
+	}
+
+	/// Load the output of [Self::serialize], to be consumed by
+	/// [Self::resume] in the order the values were tracked.
+	pub fn load(serialized: &str) -> ParseResult<()> {
+		let values: Vec<String> = ron::from_str(serialized)
+			.map_err(|err| ParseError::serde(err.to_string()))?;
+		RESUMABLE_STATE.with(|state| {
+			let mut state = state.borrow_mut();
+			state.loaded = values.into();
+			state.resuming = true;
+		});
+		Ok(())
+	}
+
+	/// Load the state from a document rendered by [RsxToResumableHtml],
+	/// doing nothing if the document contains no state.
+	pub fn load_document(doc: &HtmlDocument) -> ParseResult<()> {
+		let selector = format!("[{}]", HtmlConstants::default().state_key);
+		match doc.query_selector(&selector)? {
+			Some(el) => Self::load(&el.text_content()),
+			None => Ok(()),
+		}
+	}
+
+	/// Remove all tracked and loaded values, called by the client
+	/// once hydrated as the tracked values will not be serialized.
+	pub fn clear() {
+		RESUMABLE_STATE.with(|state| *state.borrow_mut() = Default::default());
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn roundtrip() {
+		ResumableState::clear();
+		ResumableState::track(|| 3);
+		ResumableState::track(|| "hello".to_string());
+		ResumableState::track(|| vec![Some(1.5)]);
+		expect(ResumableState::is_tracking()).to_be_true();
+		let serialized = ResumableState::serialize().unwrap();
+		expect(ResumableState::is_tracking()).to_be_false();
+
+		ResumableState::load(&serialized).unwrap();
+		expect(ResumableState::resume(0)).to_be(3);
+		expect(ResumableState::resume(String::new())).to_be("hello");
+		expect(ResumableState::resume(Vec::<Option<f32>>::new()))
+			.to_be(vec![Some(1.5)]);
+		// no more values
+		expect(ResumableState::resume(7)).to_be(7);
+	}
+
+	#[test]
+	fn mismatch() {
+		ResumableState::clear();
+		ResumableState::track(|| "not a number");
+		ResumableState::load(&ResumableState::serialize().unwrap()).unwrap();
+		expect(ResumableState::resume(7)).to_be(7);
+	}
+
+	#[test]
+	fn document() {
+		ResumableState::clear();
+		let (get, set) = crate::sigfault::resumable_signal(1);
+		let (_read, write) = crate::reactive::create_resumable_signal(10);
+		set(2);
+		write.set(20);
+		let doc = RsxToResumableHtml::default().map_root(&mut rsx! {
+			<div>{get}</div>
+		});
+		expect(doc.query_selector("[data-beet-state]").unwrap()).to_be_some();
+		// the server state is now cleared
+		expect(ResumableState::is_tracking()).to_be_false();
+
+		// client
+		let html = doc.render();
+		ResumableState::load_document(&HtmlDocument::parse(&html).unwrap())
+			.unwrap();
+		let (get, _) = crate::sigfault::resumable_signal(1);
+		let (read, _) = crate::reactive::create_resumable_signal(10);
+		expect(get()).to_be(2);
+		expect(read.get()).to_be(20);
+		ResumableState::clear();
+	}
+}
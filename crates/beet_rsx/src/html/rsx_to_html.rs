@@ -10,7 +10,14 @@ pub struct RsxToHtml {
 	pub no_beet_attributes: bool,
 	/// text node content will be trimmed
 	pub trim: bool,
+	/// If set, only elements in these islands receive beet attributes,
+	/// see [Self::with_islands]
+	islands: Option<Vec<ClientIsland>>,
+	/// static elements that are the parent of a rust block in an island
+	island_parents: HashSet<TreeIdx>,
 	tree_idx_incr: TreeIdxIncr,
+	/// The namespace of the children of the current element
+	namespace: Namespace,
 }
 
 
@@ -22,6 +29,41 @@ impl RsxToHtml {
 		}
 	}
 
+	/// Only add beet attributes to the elements in these islands, and
+	/// the parent elements of their rust blocks. The top level elements
+	/// of each island are marked with [HtmlConstants::island_key].
+	/// The location map should already be filtered with
+	/// [TreeLocationMap::retain_islands].
+	pub fn with_islands(
+		mut self,
+		islands: Vec<ClientIsland>,
+		loc_map: &TreeLocationMap,
+	) -> Self {
+		self.island_parents = loc_map
+			.rusty_locations
+			.values()
+			.map(|loc| loc.parent_idx)
+			.collect();
+		self.islands = Some(islands);
+		self
+	}
+
+	/// Start the tree indices at this value, used for rendering
+	/// nodes inserted by a block, see [DomTarget::update_block].
+	pub fn with_tree_idx(mut self, start: TreeIdx) -> Self {
+		self.tree_idx_incr = TreeIdxIncr::new(start);
+		self
+	}
+
+	/// Returns true if the node should be hydrated, which is always
+	/// the case unless rendering islands.
+	fn is_hydrated(&self, idx: TreeIdx) -> bool {
+		match &self.islands {
+			Some(islands) => islands.iter().any(|island| island.contains(idx)),
+			None => true,
+		}
+	}
+
 	/// convenience so you dont have to add
 	/// a `.render()` at the end of a long rsx macro
 	pub fn render_body(root: &RsxRoot) -> String {
@@ -47,6 +89,9 @@ impl RsxToHtml {
 				let str = if self.trim { value.trim() } else { value };
 				vec![HtmlNode::Text(str.into())]
 			}
+			RsxNode::RawHtml { value, .. } => {
+				vec![HtmlNode::RawHtml(value.clone())]
+			}
 			RsxNode::Element(e) => {
 				vec![HtmlNode::Element(self.map_element(idx, e))]
 			}
@@ -61,7 +106,26 @@ impl RsxToHtml {
 			}) => {
 				slot_children.assert_empty();
 				// use the location of the root
-				self.map_node(&root.node)
+				let mut nodes = self.map_node(&root.node);
+				// the empty slot children must still be visited to keep the
+				// tree idx consistent with the [TreeLocationVisitor]
+				nodes.extend(self.map_node(slot_children));
+				if let Some(island_idx) =
+					self.islands.as_ref().and_then(|islands| {
+						islands
+							.iter()
+							.position(|island| island.loc.tree_idx == idx)
+					}) {
+					for node in nodes.iter_mut() {
+						if let HtmlNode::Element(el) = node {
+							el.set_attribute(
+								self.html_constants.island_key,
+								Some(&island_idx.to_string()),
+							);
+						}
+					}
+				}
+				nodes
 			}
 		}
 	}
@@ -78,18 +142,28 @@ impl RsxToHtml {
 			.flatten()
 			.collect::<Vec<_>>();
 
-		if !self.no_beet_attributes && el.contains_rust() {
+		if !self.no_beet_attributes
+			&& ((el.contains_rust() && self.is_hydrated(idx))
+				|| self.island_parents.contains(&idx))
+		{
 			html_attributes.push(HtmlAttribute {
 				key: self.html_constants.tree_idx_key.to_string(),
 				value: Some(idx.to_string()),
 			});
 		}
 
+		let namespace = self.namespace.element(&el.tag);
+		let parent_namespace =
+			std::mem::replace(&mut self.namespace, namespace.children(&el.tag));
+		let children = self.map_node(&el.children);
+		self.namespace = parent_namespace;
+
 		HtmlElementNode {
 			tag: el.tag.clone(),
+			namespace,
 			self_closing: el.self_closing,
 			attributes: html_attributes,
-			children: self.map_node(&el.children),
+			children,
 		}
 	}
 
@@ -99,6 +173,13 @@ impl RsxToHtml {
 		attr: &RsxAttribute,
 	) -> Vec<HtmlAttribute> {
 		match attr {
+			// the key of a [For] item is only used by the [RsxDiff]
+			RsxAttribute::KeyValue { key, .. }
+			| RsxAttribute::BlockValue { key, .. }
+				if key == "key" =>
+			{
+				vec![]
+			}
 			RsxAttribute::Key { key } => vec![HtmlAttribute {
 				key: key.clone(),
 				value: None,
@@ -110,7 +191,13 @@ impl RsxToHtml {
 				}]
 			}
 			RsxAttribute::BlockValue { key, initial, .. } => {
-				if !self.no_beet_attributes && key.starts_with("on") {
+				if !self.no_beet_attributes
+					&& key.starts_with("on")
+					&& !self.is_hydrated(idx)
+				{
+					// static html has no event handlers
+					vec![]
+				} else if !self.no_beet_attributes && key.starts_with("on") {
 					vec![HtmlAttribute {
 						key: key.clone(),
 						value: Some(format!(
@@ -217,6 +304,7 @@ mod test {
 
 	#[test]
 	fn component_props() {
+		#[derive(Props)]
 		struct Child {
 			value: usize,
 		}
@@ -233,6 +321,7 @@ mod test {
 	}
 	#[test]
 	fn component_children() {
+		#[derive(Props)]
 		struct Layout;
 		impl Component for Layout {
 			fn render(self) -> RsxRoot {
@@ -258,6 +347,7 @@ mod test {
 	}
 	#[test]
 	fn component_slots() {
+		#[derive(Props)]
 		struct Layout;
 		impl Component for Layout {
 			fn render(self) -> RsxRoot {
@@ -285,6 +375,45 @@ mod test {
 	}
 
 
+	#[test]
+	fn escapes_text() {
+		let value = "<script>alert('xss')</script>";
+		expect(RsxToHtml::render_body(&rsx! { <p>{value}</p> })).to_be(
+			"<p data-beet-rsx-idx=\"0\">&lt;script&gt;alert('xss')&lt;/script&gt;</p>",
+		);
+	}
+	#[test]
+	fn escapes_attributes() {
+		let value = "\"><script>alert('xss')</script>";
+		expect(RsxToHtml::render_body(&rsx! { <img src=value /> })).to_be(
+			"<img src=\"&quot;&gt;&lt;script&gt;alert('xss')&lt;/script&gt;\" data-beet-rsx-idx=\"0\"/>",
+		);
+	}
+	#[test]
+	fn escapes_comments() {
+		expect(
+			vec![HtmlNode::Comment("--><script>alert('xss')</script>".into())]
+				.render(),
+		)
+		.to_be("<!-- --&gt;&lt;script&gt;alert('xss')&lt;/script&gt; -->");
+	}
+	#[test]
+	fn escapes_raw_text() {
+		let value = "let a = 1 < 2;</script><script>alert('xss')";
+		expect(
+			HtmlElementNode::inline_script(value.to_string(), vec![]).render(),
+		)
+		.to_be(
+			"<script>let a = 1 < 2;<\\/script><script>alert('xss')</script>",
+		);
+	}
+	#[test]
+	fn raw_html() {
+		let value = RawHtml::new("<b>trusted</b>");
+		expect(RsxToHtml::render_body(&rsx! { <p>{value}</p> }))
+			.to_be("<p data-beet-rsx-idx=\"0\"><b>trusted</b></p>");
+	}
+
 	#[test]
 	fn trims() {
 		expect(
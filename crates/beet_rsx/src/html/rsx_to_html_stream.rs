@@ -0,0 +1,326 @@
+use crate::prelude::*;
+use anyhow::Result;
+use futures::stream::FuturesUnordered;
+use futures::Stream;
+use futures::StreamExt;
+use std::future::Future;
+use std::pin::Pin;
+
+/// The resolved content of a boundary, with its id and the tree index
+/// of its placeholder.
+type ResolvedFuture = Pin<Box<dyn Future<Output = (u64, TreeIdx, RsxRoot)>>>;
+
+/// Render an [`RsxRoot`] as a stream of html chunks, the first chunk
+/// is the document with [`Suspense`] fallbacks in place and each
+/// following chunk is the content of a boundary in the order they resolve,
+/// along with a script that swaps it with the fallback.
+///
+/// Like [`RsxRoot::build_document`], errors not caught by an [`ErrorBoundary`]
+/// are returned as [`RsxErrors`] by the chunk they occur in. The tree indices
+/// of resolved content start at the index of its placeholder, so they match
+/// the tree rendered by [`RsxRoot::resolve_suspense`].
+/// The shell has already been sent so [`Head`] elements cannot be
+/// hoisted from resolved content, and are returned as an error.
+#[derive(Debug, Default)]
+pub struct RsxToHtmlStream {
+	pub html_constants: HtmlConstants,
+}
+
+enum StreamState {
+	Shell(RsxRoot),
+	Pending(FuturesUnordered<ResolvedFuture>),
+	Done,
+}
+
+impl RsxToHtmlStream {
+	pub fn map_root(self, root: RsxRoot) -> impl Stream<Item = Result<String>> {
+		futures::stream::unfold(
+			(self, StreamState::Shell(root)),
+			|(this, state)| async move {
+				match state {
+					StreamState::Shell(root) => {
+						let mut pending = FuturesUnordered::new();
+						let chunk = this.render_shell(root, &mut pending);
+						Some((chunk, (this, StreamState::Pending(pending))))
+					}
+					StreamState::Pending(mut pending) => {
+						match pending.next().await {
+							Some((id, tree_idx, content)) => {
+								let chunk = this.render_content(
+									id,
+									tree_idx,
+									content,
+									&mut pending,
+								);
+								Some((
+									chunk,
+									(this, StreamState::Pending(pending)),
+								))
+							}
+							None => Some((
+								Ok("</body></html>".to_string()),
+								(this, StreamState::Done),
+							)),
+						}
+					}
+					StreamState::Done => None,
+				}
+			},
+		)
+	}
+
+	/// Convenience for collecting the stream into a single string
+	pub async fn render(self, root: RsxRoot) -> Result<String> {
+		let mut stream = std::pin::pin!(self.map_root(root));
+		let mut html = String::new();
+		while let Some(chunk) = stream.next().await {
+			html.push_str(&chunk?);
+		}
+		Ok(html)
+	}
+
+	/// Take the futures of all suspense boundaries in the node, this must
+	/// be called after [SlotsVisitor::apply] so that the tree indices of
+	/// the placeholders match the rendered html.
+	fn suspend(
+		&self,
+		root: &RsxRoot,
+		start: TreeIdx,
+		pending: &mut FuturesUnordered<ResolvedFuture>,
+	) {
+		TreeLocationVisitor::visit(&root.node, |loc, node| {
+			let Some(id) = Suspense::placeholder_id(node) else {
+				return;
+			};
+			if let Some(content) = Suspense::take(id) {
+				let tree_idx = TreeIdx::new(*start + *loc.tree_idx);
+				pending.push(Box::pin(
+					async move { (id, tree_idx, content.await) },
+				));
+			}
+		});
+	}
+
+	/// Apply styles, slots and error boundaries, then take any
+	/// suspended futures.
+	fn prepare(
+		&self,
+		root: &mut RsxRoot,
+		start: TreeIdx,
+		pending: &mut FuturesUnordered<ResolvedFuture>,
+	) -> Result<()> {
+		#[cfg(feature = "css")]
+		ScopedStyle::default().apply(root)?;
+		SlotsVisitor::apply(root)?;
+		if let Err(errors) = ErrorBoundary::resolve(root).into_result() {
+			Suspense::evict(root);
+			return Err(errors.into());
+		}
+		self.suspend(root, start, pending);
+		#[cfg(feature = "serde")]
+		ResumableState::clear_tracked();
+		Ok(())
+	}
+
+	/// The document without the closing body and html tags,
+	/// which are sent once all content has resolved.
+	fn render_shell(
+		&self,
+		mut root: RsxRoot,
+		pending: &mut FuturesUnordered<ResolvedFuture>,
+	) -> Result<String> {
+		self.prepare(&mut root, TreeIdx::default(), pending)?;
+		let mut doc = RsxToHtml::default().map_root(&root).into_document();
+		doc.hoist_head();
+		if !pending.is_empty() {
+			doc.body.push(HtmlNode::Element(HtmlElementNode::inline_script(
+				format!(
+				"function {}(id){{const s=`[{}=\"${{id}}\"]`;const t=document.querySelector(`template${{s}}`);document.querySelector(`{}${{s}}`).replaceWith(t.content);t.remove()}}",
+				self.html_constants.suspense_resolver,
+				self.html_constants.suspense_key,
+				Suspense::TAG,
+				),
+				Vec::new(),
+			)));
+		}
+		let html = doc.render();
+		Ok(html
+			.strip_suffix("</body></html>")
+			.map(|html| html.to_string())
+			.unwrap_or(html))
+	}
+
+	/// Render resolved content into a template, and call the resolver
+	/// to replace the fallback.
+	fn render_content(
+		&self,
+		id: u64,
+		tree_idx: TreeIdx,
+		mut content: RsxRoot,
+		pending: &mut FuturesUnordered<ResolvedFuture>,
+	) -> Result<String> {
+		self.prepare(&mut content, tree_idx, pending)?;
+		let mut doc = HtmlDocument {
+			head: Vec::new(),
+			body: RsxToHtml::default()
+				.with_tree_idx(tree_idx)
+				.map_root(&content),
+		};
+		doc.hoist_head();
+		if !doc.head.is_empty() {
+			anyhow::bail!(
+				"Head elements cannot be streamed, they must be rendered outside of a Suspense boundary:\n{}",
+				doc.head.render()
+			);
+		}
+		let html = doc.body.render();
+		Ok(format!(
+			"<template {key}=\"{id}\">{html}</template><script>{resolver}({id})</script>",
+			key = self.html_constants.suspense_key,
+			resolver = self.html_constants.suspense_resolver,
+		))
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use futures::FutureExt;
+	use futures::StreamExt;
+	use sweet::prelude::*;
+
+	#[sweet::test]
+	async fn streams() {
+		let (send, recv) = futures::channel::oneshot::channel::<&str>();
+		// blocks must be Clone, and may be rendered more than once
+		let recv = recv.shared();
+		let slow = move || {
+			let recv = recv.clone();
+			Suspense::new("loading slow", async move {
+				let value = recv.await.unwrap();
+				rsx! { <p>{value}</p> }
+			})
+		};
+		let fast = || {
+			Suspense::new("loading fast", async {
+				rsx! { <p>fast</p> }
+			})
+		};
+		let root = rsx! { <div>{slow}{fast}</div> };
+		let mut stream =
+			std::pin::pin!(RsxToHtmlStream::default().map_root(root));
+
+		let shell = stream.next().await.unwrap().unwrap();
+		expect(shell.starts_with("<!DOCTYPE html><html><head></head><body>"))
+			.to_be_true();
+		expect(&shell).to_contain("\">loading slow</beet-suspense>");
+		expect(&shell).to_contain("\">loading fast</beet-suspense>");
+		expect(&shell).to_contain("function _beet_resolve_suspense(id)");
+		expect(shell.ends_with("</body></html>")).to_be_false();
+
+		// out of order, the fast boundary resolves first
+		let chunk = stream.next().await.unwrap().unwrap();
+		expect(&chunk).to_contain(
+			"<p>fast</p></template><script>_beet_resolve_suspense(",
+		);
+
+		send.send("slow").unwrap();
+		let chunk = stream.next().await.unwrap().unwrap();
+		expect(&chunk).to_contain(">slow</p></template>");
+		expect(stream.next().await.unwrap().unwrap()).to_be("</body></html>");
+		expect(stream.next().await.is_none()).to_be_true();
+	}
+
+	#[sweet::test]
+	async fn no_suspense() {
+		let html = RsxToHtmlStream::default()
+			.render(rsx! { <div>hello</div> })
+			.await
+			.unwrap();
+		expect(html).to_be(
+			"<!DOCTYPE html><html><head></head><body><div>hello</div></body></html>",
+		);
+	}
+
+	#[derive(Props)]
+	struct Parse {
+		value: &'static str,
+	}
+	impl TryComponent for Parse {
+		fn render(self) -> anyhow::Result<RsxRoot> {
+			let value: u32 = self.value.parse()?;
+			Ok(rsx! { <span>{value}</span> })
+		}
+	}
+
+	/// Collect the chunks of a stream, including errors
+	async fn collect_chunks(root: RsxRoot) -> Vec<anyhow::Result<String>> {
+		RsxToHtmlStream::default().map_root(root).collect().await
+	}
+
+	#[sweet::test]
+	async fn error_boundaries() {
+		let caught = || {
+			Suspense::new((), async {
+				rsx! {
+					<ErrorBoundary fallback=|_: &RsxError| rsx! { <p>oops</p> }>
+						<Parse value="foo"/>
+					</ErrorBoundary>
+				}
+			})
+		};
+		let chunks = collect_chunks(rsx! { <div>{caught}</div> }).await;
+		let content = chunks[1].as_ref().unwrap();
+		expect(content)
+			.to_contain("<p>oops</p></beet-error-boundary></template>");
+
+		let uncaught = || {
+			Suspense::new((), async {
+				rsx! { <Parse value="foo"/> }
+			})
+		};
+		let chunks = collect_chunks(rsx! { <div>{uncaught}</div> }).await;
+		let err = chunks[1].as_ref().unwrap_err();
+		expect(err.downcast_ref::<RsxErrors>().unwrap().0.len()).to_be(1);
+	}
+
+	#[sweet::test]
+	async fn rejects_head() {
+		let content = || {
+			Suspense::new((), async {
+				rsx! { <Head><title>late</title></Head> }
+			})
+		};
+		let chunks = collect_chunks(rsx! { <div>{content}</div> }).await;
+		expect(chunks[1].as_ref().unwrap_err().to_string())
+			.to_contain("<title>late</title>");
+	}
+
+	#[sweet::test]
+	async fn tree_idx() {
+		let app = || {
+			let value = "hello";
+			let content = move || {
+				Suspense::new("loading", async move {
+					rsx! { <p>{value}</p> }
+				})
+			};
+			rsx! { <div>{value}{content}<span>{value}</span></div> }
+		};
+		let expected = RsxToHtml::render_body(&app().resolve_suspense().await);
+		let chunks = collect_chunks(app()).await;
+		let shell = chunks[0].as_ref().unwrap();
+		let content = chunks[1].as_ref().unwrap();
+		let idx = content
+			.split("data-beet-rsx-idx=\"")
+			.nth(1)
+			.and_then(|attr| attr.split('"').next())
+			.unwrap();
+		expect(&expected)
+			.to_contain(&format!("<p data-beet-rsx-idx=\"{idx}\">hello</p>"));
+		expect(shell)
+			.not()
+			.to_contain(&format!("data-beet-rsx-idx=\"{idx}\""));
+	}
+}
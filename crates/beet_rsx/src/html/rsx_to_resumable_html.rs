@@ -10,21 +10,63 @@ pub struct RsxToResumableHtml {
 	pub html_constants: HtmlConstants,
 }
 impl RsxToResumableHtml {
-	pub fn render_body(root: &RsxRoot) -> String {
+	pub fn render_body(root: &mut RsxRoot) -> String {
 		Self::default().map_root(root).render()
 	}
 
-	pub fn map_root(&mut self, root: &RsxRoot) -> HtmlDocument {
+	/// Render the root with everything required for hydration. If the
+	/// root contains any [ClientIsland] only they will be hydrated, with
+	/// all other content rendered as static html.
+	///
+	/// Errors are resolved with [ErrorBoundary::resolve], the client must
+	/// also resolve them so that the tree matches, see `BeetDom::hydrate`.
+	/// Uncaught errors are reported and rendered as empty placeholders.
+	pub fn map_root(&mut self, root: &mut RsxRoot) -> HtmlDocument {
+		ErrorBoundary::resolve(root).report();
+		let islands = root.client_islands();
+		if !islands.is_empty() {
+			return self.map_islands(root, islands);
+		}
 		let mut html = RsxToHtml::default().map_root(root).into_document();
-		self.insert_tree_location_map(root, &mut html);
+		html.hoist_resumable_head();
+		self.insert_tree_location_map(
+			&TreeLocationMap::from_node(root),
+			&mut html,
+		);
+		self.insert_catch_prehydrated_events(&mut html);
+		#[cfg(feature = "serde")]
+		self.insert_resumable_state(&mut html);
+		html
+	}
+
+	fn map_islands(
+		&self,
+		root: &RsxRoot,
+		islands: Vec<ClientIsland>,
+	) -> HtmlDocument {
+		let mut loc_map = TreeLocationMap::from_node(root);
+		loc_map.retain_islands(&islands);
+		let mut html = RsxToHtml::default()
+			.with_islands(islands.clone(), &loc_map)
+			.map_root(root)
+			.into_document();
+		html.hoist_resumable_head();
+		self.insert_tree_location_map(&loc_map, &mut html);
 		self.insert_catch_prehydrated_events(&mut html);
+		#[cfg(feature = "serde")]
+		self.insert_resumable_state(&mut html);
+		self.insert_island_loader(&islands, &mut html);
 		html
 	}
 
 	/// attempt to insert the rsx context map into the html body,
 	/// otherwise append it to the end of the html
-	fn insert_tree_location_map(&self, node: &RsxNode, doc: &mut HtmlDocument) {
-		let loc_map = TreeLocationMap::from_node(node).to_csv();
+	fn insert_tree_location_map(
+		&self,
+		loc_map: &TreeLocationMap,
+		doc: &mut HtmlDocument,
+	) {
+		let loc_map = loc_map.to_csv();
 		let el = HtmlElementNode::inline_script(loc_map, vec![HtmlAttribute {
 			key: self.html_constants.loc_map_key.to_string(),
 			value: None,
@@ -32,6 +74,30 @@ impl RsxToResumableHtml {
 		doc.body.push(el.into());
 	}
 
+	/// serialize the values of resumable signals created while building
+	/// the root, to be loaded by `BeetDom::hydrate`
+	#[cfg(feature = "serde")]
+	fn insert_resumable_state(&self, doc: &mut HtmlDocument) {
+		if !ResumableState::is_tracking() {
+			return;
+		}
+		// without the state script the client uses the initial values
+		let Ok(state) = ResumableState::serialize() else {
+			return;
+		};
+		let el = HtmlElementNode::inline_script(state, vec![
+			HtmlAttribute {
+				key: "type".to_string(),
+				value: Some("application/ron".to_string()),
+			},
+			HtmlAttribute {
+				key: self.html_constants.state_key.to_string(),
+				value: None,
+			},
+		]);
+		doc.body.push(el.into());
+	}
+
 	fn insert_catch_prehydrated_events(&self, doc: &mut HtmlDocument) {
 		let script = format!(
 			r#"
@@ -48,6 +114,62 @@ globalThis.{event_handler} = (id,event) => globalThis.{prehydrate_events}.push([
 		}]);
 		doc.body.push(el.into());
 	}
+
+	/// The wasm entry for islands, the wasm is loaded once the first
+	/// island is triggered and each island is hydrated by calling
+	/// [HtmlConstants::island_hydrate], which is set by `BeetDom::mount_islands`.
+	fn insert_island_loader(
+		&self,
+		islands: &[ClientIsland],
+		doc: &mut HtmlDocument,
+	) {
+		let islands = islands
+			.iter()
+			.enumerate()
+			.map(|(idx, island)| {
+				format!("[{},\"{}\"]", idx, island.directive.as_str())
+			})
+			.collect::<Vec<_>>()
+			.join(",");
+		let script = format!(
+			r#"
+const islands = [{islands}]
+let wasm
+const hydrate = (id) => {{
+	wasm ??= import('./wasm/bindgen.js')
+		.then(({{ default: init }}) => init('./wasm/bindgen_bg.wasm'))
+		.catch((error) => {{
+			if (!error.message.startsWith("Using exceptions for control flow,"))
+				throw error
+		}})
+	wasm.then(() => globalThis.{island_hydrate}(id))
+}}
+for (const [id, directive] of islands) {{
+	const elements = document.querySelectorAll(`[{island_key}="${{id}}"]`)
+	if (directive === "idle") {{
+		(globalThis.requestIdleCallback ?? setTimeout)(() => hydrate(id))
+	}} else if (directive === "visible" && elements.length > 0) {{
+		const observer = new IntersectionObserver((entries) => {{
+			if (entries.some((entry) => entry.isIntersecting)) {{
+				observer.disconnect()
+				hydrate(id)
+			}}
+		}})
+		elements.forEach((el) => observer.observe(el))
+	}} else {{
+		hydrate(id)
+	}}
+}}
+"#,
+			island_hydrate = self.html_constants.island_hydrate,
+			island_key = self.html_constants.island_key,
+		);
+		let el = HtmlElementNode::inline_script(script, vec![HtmlAttribute {
+			key: "type".to_string(),
+			value: Some("module".to_string()),
+		}]);
+		doc.body.push(el.into());
+	}
 }
 
 
@@ -58,12 +180,12 @@ mod test {
 
 	#[test]
 	fn plain() {
-		expect(RsxToResumableHtml::render_body(&rsx! { <br /> }))
+		expect(RsxToResumableHtml::render_body(&mut rsx! { <br /> }))
 			.to_contain("<br/>");
 	}
 	#[test]
 	fn id() {
-		expect(RsxToResumableHtml::render_body(&rsx! {
+		expect(RsxToResumableHtml::render_body(&mut rsx! {
 			<main>
 				<article>{7}</article>
 			</main>
@@ -77,8 +199,68 @@ mod test {
 		let on_click = |_| {};
 
 		expect(RsxToResumableHtml::render_body(
-			&rsx! { <main onclick=on_click></main> },
+			&mut rsx! { <main onclick=on_click></main> },
 		))
 		.to_contain("<main onclick=\"_beet_event_handler(0, event)\" data-beet-rsx-idx=\"0\"></main>");
 	}
+
+	#[derive(Props)]
+	struct Counter;
+	impl Component for Counter {
+		fn render(self) -> RsxRoot {
+			let on_click = |_| {};
+			let count = 0;
+			rsx! { <button onclick=on_click>{count}</button> }
+		}
+	}
+
+	#[test]
+	fn islands() {
+		let on_click = |_| {};
+		let value = "static";
+		let doc = RsxToResumableHtml::default().map_root(&mut rsx! {
+			<main onclick=on_click>
+				<p>{value}</p>
+				<Counter client:visible />
+				<div><Counter client:load /></div>
+			</main>
+		});
+		// static parts have no beet attributes
+		let main = doc.query_selector("main").unwrap().unwrap();
+		expect(main.has_attribute("onclick")).to_be_false();
+		expect(main.has_attribute("data-beet-rsx-idx")).to_be_false();
+		expect(doc.query_selector("p").unwrap().unwrap().attributes.len())
+			.to_be(0);
+		// islands are hydrated
+		let buttons = doc.query_selector_all("button").unwrap();
+		expect(buttons.len()).to_be(2);
+		for (idx, button) in buttons.into_iter().enumerate() {
+			expect(button.get_attribute_value("data-beet-island"))
+				.to_be(Some(idx.to_string().as_str()));
+			expect(button.has_attribute("data-beet-rsx-idx")).to_be_true();
+			expect(button.get_attribute_value("onclick").unwrap().to_string())
+				.to_start_with("_beet_event_handler(");
+		}
+		// only island locations are included
+		let loc_map = doc
+			.query_selector("[data-beet-loc-map]")
+			.unwrap()
+			.unwrap()
+			.text_content();
+		let loc_map = TreeLocationMap::from_csv(&loc_map).unwrap();
+		expect(loc_map.rusty_locations.len()).to_be(2);
+		expect(doc.render())
+			.to_contain("const islands = [[0,\"visible\"],[1,\"load\"]]");
+	}
+
+	#[test]
+	fn no_islands() {
+		let doc = RsxToResumableHtml::default().map_root(&mut rsx! {
+			<div><Counter /></div>
+		});
+		expect(doc.query_selector("[data-beet-island]").unwrap()).to_be_none();
+		expect(doc.render()).not().to_contain("const islands");
+		expect(doc.query_selector("button[data-beet-rsx-idx]").unwrap())
+			.to_be_some();
+	}
 }
@@ -11,6 +11,7 @@
 pub mod dom;
 pub mod error;
 pub mod html;
+pub mod reactive;
 pub mod rsx;
 pub mod sigfault;
 pub mod string_rsx;
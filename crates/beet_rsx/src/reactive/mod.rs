@@ -0,0 +1,293 @@
+mod reactive_graph;
+mod reactive_signal;
+use crate::prelude::*;
+pub use reactive_graph::NodeId;
+pub use reactive_signal::*;
+
+
+/// A fine-grained reactive runtime, selected in the rsx macro
+/// with `runtime:reactive`. Effects of block nodes and attributes
+/// own any memos and effects created while rendering, which are disposed
+/// when the block rerenders.
+///
+/// Unlike [`Sigfault`](crate::sigfault::Sigfault) this runtime supports:
+/// - derived values with [`create_memo`]
+/// - batched updates with [`batch`]
+/// - ownership and cleanup with [`create_scope`] and [`on_cleanup`]
+/// - untracked reads with [`untrack`]
+/// - glitch-free updates of diamond dependencies
+pub struct ReactiveRuntime;
+
+impl ReactiveRuntime {
+	/// Used by [`RstmlToRsx`] when it encounters a block node:
+	/// ```
+	/// # use beet_rsx::as_beet::*;
+	/// let block = "hello";
+	/// let node = rsx!{<div runtime:reactive>{block}</div>};
+	/// ```
+	pub fn parse_block_node<M>(
+		idx: RsxIdx,
+		tracker: RustyTracker,
+		block: impl 'static + Clone + IntoRsxRoot<M>,
+	) -> RsxNode {
+		let (initial, tracked) = if is_resuming() {
+			let (initial, tracked) =
+				create_tracked_effect(|| block.clone().into_root());
+			(initial, Some(tracked))
+		} else {
+			(block.clone().into_root(), None)
+		};
+		RsxNode::Block(RsxBlock {
+			idx,
+			initial: Box::new(initial),
+			effect: Effect::new(
+				Box::new(move |loc: TreeLocation| {
+					match tracked {
+						Some(tracked) => tracked.effect(dispose_removed(
+							loc,
+							resumed_block_effect(loc, block),
+						)),
+						None => create_effect(dispose_removed(
+							loc,
+							block_effect(loc, block),
+						)),
+					}
+					Ok(())
+				}),
+				tracker,
+			),
+		})
+	}
+
+	/// Used by [`RstmlToRsx`] when it encounters an attribute block:
+	/// ```
+	/// # use beet_rsx::as_beet::*;
+	/// let value = || vec![RsxAttribute::Key{key:"foo".to_string()}];
+	/// let node = rsx!{<el runtime:reactive {value}/>};
+	/// ```
+	pub fn parse_attribute_block(
+		tracker: RustyTracker,
+		mut block: impl 'static + FnMut() -> Vec<RsxAttribute>,
+	) -> RsxAttribute {
+		let (initial, tracked) = track_initial(&mut block);
+		// the initial value has already been rendered
+		let mut prev =
+			tracked.as_ref().map(|_| owned_attribute_pairs(&initial));
+		RsxAttribute::Block {
+			initial,
+			effect: Effect::new(
+				Box::new(move |loc| {
+					register_effect(loc, tracked, move || {
+						let next = owned_attribute_pairs(&block());
+						if let Some(prev) = &prev {
+							DomTarget::update_attributes(prev, &next, loc)
+								.unwrap();
+						}
+						prev = Some(next);
+					});
+					Ok(())
+				}),
+				tracker,
+			),
+		}
+	}
+
+	/// Used by [`RstmlToRsx`] when it encounters an attribute with a block value:
+	/// ```
+	/// # use beet_rsx::as_beet::*;
+	/// let value = 3;
+	/// let node = rsx!{<el runtime:reactive key={value}/>};
+	/// ```
+	pub fn parse_attribute_value<M>(
+		key: &'static str,
+		tracker: RustyTracker,
+		block: impl 'static + Clone + IntoReactiveAttrVal<M>,
+	) -> RsxAttribute {
+		let (initial, tracked) =
+			track_initial(|| block.clone().into_reactive_val());
+		// the initial value has already been rendered
+		let mut prev = tracked.as_ref().map(|_| initial.clone());
+		RsxAttribute::BlockValue {
+			key: key.to_string(),
+			initial,
+			effect: Effect::new(
+				Box::new(move |loc| {
+					register_effect(loc, tracked, move || {
+						let value = block.clone().into_reactive_val();
+						if prev.is_some() && prev.as_ref() != Some(&value) {
+							DomTarget::with(|target| {
+								target
+									.update_rsx_attribute(
+										key,
+										Some(&value),
+										loc,
+									)
+									.unwrap()
+							});
+						}
+						prev = Some(value);
+					});
+					Ok(())
+				}),
+				tracker,
+			),
+		}
+	}
+}
+
+/// Compute the initial value of an attribute effect without tracking,
+/// when resuming the effect is subscribed by this instead of its
+/// first run, see [`is_resuming`].
+fn track_initial<O>(func: impl FnOnce() -> O) -> (O, Option<TrackedEffect>) {
+	if is_resuming() {
+		let (out, tracked) = create_tracked_effect(func);
+		(out, Some(tracked))
+	} else {
+		(untrack(func), None)
+	}
+}
+
+/// Set the function of the tracked effect, or create an effect
+/// if the initial value was not tracked.
+fn register_effect(
+	loc: TreeLocation,
+	tracked: Option<TrackedEffect>,
+	func: impl 'static + FnMut(),
+) {
+	match tracked {
+		Some(tracked) => tracked.effect(dispose_removed(loc, func)),
+		None => create_effect(dispose_removed(loc, func)),
+	}
+}
+
+/// Skip the effect once its node is removed, see [DomTarget::is_removed].
+/// Returning without reading any signals unsubscribes the effect
+/// and disposes anything it owned.
+fn dispose_removed(
+	loc: TreeLocation,
+	mut func: impl 'static + FnMut(),
+) -> impl 'static + FnMut() {
+	move || {
+		if !DomTarget::is_removed(loc) {
+			func();
+		}
+	}
+}
+
+pub trait IntoReactiveAttrVal<M> {
+	fn into_reactive_val(self) -> String;
+}
+
+pub struct ToStringIntoReactiveAttrVal;
+impl<T: ToString> IntoReactiveAttrVal<(T, ToStringIntoReactiveAttrVal)> for T {
+	fn into_reactive_val(self) -> String { self.to_string() }
+}
+pub struct FuncIntoReactiveAttrVal;
+impl<T: FnOnce() -> U, U: IntoReactiveAttrVal<M2>, M2>
+	IntoReactiveAttrVal<(M2, FuncIntoReactiveAttrVal)> for T
+{
+	fn into_reactive_val(self) -> String { self().into_reactive_val() }
+}
+pub struct ReadSignalIntoReactiveAttrVal;
+impl<T: 'static + Clone + IntoReactiveAttrVal<M2>, M2>
+	IntoReactiveAttrVal<(M2, ReadSignalIntoReactiveAttrVal)> for ReadSignal<T>
+{
+	fn into_reactive_val(self) -> String { self.get().into_reactive_val() }
+}
+pub struct MemoIntoReactiveAttrVal;
+impl<T: 'static + Clone + IntoReactiveAttrVal<M2>, M2>
+	IntoReactiveAttrVal<(M2, MemoIntoReactiveAttrVal)> for Memo<T>
+{
+	fn into_reactive_val(self) -> String { self.get().into_reactive_val() }
+}
+
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn block_node() {
+		let (count, set_count) = create_signal(1);
+		let double = create_memo(move || count.get() * 2);
+
+		let mut rsx = rsx! { <div runtime:reactive>{count} and {double}</div> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div data-beet-rsx-idx=\"0\">1 and 2</div>");
+		set_count.set(2);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div data-beet-rsx-idx=\"0\">2 and 4</div>");
+	}
+
+	#[test]
+	fn attributes() {
+		let (count, set_count) = create_signal(1);
+		let attrs = move || {
+			vec![RsxAttribute::KeyValue {
+				key: "class".into(),
+				value: format!("count-{}", count.get()),
+			}]
+		};
+
+		let mut rsx = rsx! { <div runtime:reactive count={count} {attrs}/> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<div count=\"1\" class=\"count-1\" data-beet-rsx-idx=\"0\"/>",
+		);
+		set_count.set(2);
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<div count=\"2\" class=\"count-2\" data-beet-rsx-idx=\"0\"/>",
+		);
+	}
+
+	#[test]
+	fn unmount() {
+		let (count, set_count) = create_signal(1);
+		let mut rsx = rsx! { <div runtime:reactive>{count}</div> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		let scope = create_scope(|| rsx.register_effects());
+		scope.dispose();
+		set_count.set(2);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div data-beet-rsx-idx=\"0\">1</div>");
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn resumed() {
+		use std::cell::Cell;
+		use std::rc::Rc;
+		ResumableState::clear();
+		ResumableState::track(|| 2);
+		ResumableState::load(&ResumableState::serialize().unwrap()).unwrap();
+
+		let renders = Rc::new(Cell::new(0));
+		let renders2 = renders.clone();
+		let (count, set_count) = create_resumable_signal(1);
+		let block = move || {
+			renders2.set(renders2.get() + 1);
+			count.get()
+		};
+		let mut rsx =
+			rsx! { <div runtime:reactive count={count}>{block}</div> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		ResumableState::clear();
+		// effects were subscribed by the initial render
+		expect(renders.get()).to_be(1);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div count=\"2\" data-beet-rsx-idx=\"0\">2</div>");
+		set_count.set(3);
+		expect(renders.get()).to_be(2);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div count=\"3\" data-beet-rsx-idx=\"0\">3</div>");
+		set_count.set(4);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div count=\"4\" data-beet-rsx-idx=\"0\">4</div>");
+	}
+}
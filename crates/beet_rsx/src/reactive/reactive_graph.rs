@@ -0,0 +1,460 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+thread_local! {
+	static GRAPH: RefCell<ReactiveGraph> = RefCell::new(ReactiveGraph::default());
+}
+
+/// Index of a node in the [`ReactiveGraph`]. The slots of disposed nodes
+/// are reused, so the id also stores the generation of the slot and a
+/// handle to a disposed node will never point to a new one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId {
+	index: usize,
+	generation: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+	Signal,
+	Memo,
+	Effect,
+	Scope,
+}
+
+/// The coloring used for glitch-free updates, ordered so that
+/// a node can only be marked 'dirtier'.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum NodeState {
+	/// The value is up to date
+	Clean,
+	/// A source further up the graph has changed,
+	/// direct sources must be checked before recomputing
+	Check,
+	/// A direct source has changed, the node must be recomputed
+	Dirty,
+}
+
+/// The computation of a memo or effect, returning whether
+/// the value changed.
+type Compute = Rc<RefCell<dyn FnMut() -> bool>>;
+
+struct Node {
+	kind: NodeKind,
+	state: NodeState,
+	/// The value of signals and memos, memos store `RefCell<Option<T>>`
+	/// as they are lazily computed
+	value: Option<Rc<dyn Any>>,
+	compute: Option<Compute>,
+	/// Nodes read by this node during its last computation
+	sources: Vec<NodeId>,
+	/// Nodes that read this node during their last computation
+	subscribers: Vec<NodeId>,
+	owner: Option<NodeId>,
+	/// Memos, effects and scopes created during the computation of this node,
+	/// disposed before it recomputes.
+	owned: Vec<NodeId>,
+	cleanups: Vec<Box<dyn FnOnce()>>,
+}
+
+impl Node {
+	fn new(kind: NodeKind) -> Self {
+		Self {
+			kind,
+			state: NodeState::Clean,
+			value: None,
+			compute: None,
+			sources: Vec::new(),
+			subscribers: Vec::new(),
+			owner: None,
+			owned: Vec::new(),
+			cleanups: Vec::new(),
+		}
+	}
+}
+
+/// A slot in the [`ReactiveGraph`], the generation is incremented
+/// each time the node in the slot is disposed.
+#[derive(Default)]
+struct Slot {
+	generation: u32,
+	node: Option<Node>,
+}
+
+/// A push-pull reactive graph, effects are scheduled by pushing
+/// [`NodeState`] down the graph and values are pulled by checking sources
+/// in order, so that an effect runs exactly once per change even if
+/// it depends on a source through multiple paths.
+#[derive(Default)]
+pub(super) struct ReactiveGraph {
+	nodes: Vec<Slot>,
+	/// Indices of empty slots, reused before growing the list
+	free: Vec<usize>,
+	/// The node currently tracking reads
+	observer: Option<NodeId>,
+	/// The node that will own any nodes created
+	owner: Option<NodeId>,
+	batch_depth: usize,
+	flushing: bool,
+	pending_effects: VecDeque<NodeId>,
+}
+
+impl ReactiveGraph {
+	fn with<R>(func: impl FnOnce(&mut Self) -> R) -> R {
+		GRAPH.with(|graph| func(&mut graph.borrow_mut()))
+	}
+
+	fn node(&self, id: NodeId) -> Option<&Node> {
+		self.nodes
+			.get(id.index)
+			.filter(|slot| slot.generation == id.generation)
+			.and_then(|slot| slot.node.as_ref())
+	}
+	fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+		self.nodes
+			.get_mut(id.index)
+			.filter(|slot| slot.generation == id.generation)
+			.and_then(|slot| slot.node.as_mut())
+	}
+
+	fn insert(&mut self, mut node: Node) -> NodeId {
+		let index = self.free.pop().unwrap_or_else(|| {
+			self.nodes.push(Slot::default());
+			self.nodes.len() - 1
+		});
+		let id = NodeId {
+			index,
+			generation: self.nodes[index].generation,
+		};
+		node.owner = self.owner;
+		if let Some(owner) = self.owner.and_then(|owner| self.node_mut(owner)) {
+			owner.owned.push(id);
+		}
+		self.nodes[index].node = Some(node);
+		id
+	}
+
+	/// Take the node out of its slot, freeing the slot for reuse
+	fn remove(&mut self, id: NodeId) -> Option<Node> {
+		let slot = self
+			.nodes
+			.get_mut(id.index)
+			.filter(|slot| slot.generation == id.generation)?;
+		let node = slot.node.take()?;
+		slot.generation += 1;
+		self.free.push(id.index);
+		Some(node)
+	}
+
+	pub fn is_disposed(id: NodeId) -> bool {
+		Self::with(|graph| graph.node(id).is_none())
+	}
+
+	pub fn create_signal(value: Rc<dyn Any>) -> NodeId {
+		Self::with(|graph| {
+			let mut node = Node::new(NodeKind::Signal);
+			node.value = Some(value);
+			graph.insert(node)
+		})
+	}
+
+	/// Memos are lazy, the compute function is not called
+	/// until the value is first read.
+	pub fn create_memo(value: Rc<dyn Any>, compute: Compute) -> NodeId {
+		Self::with(|graph| {
+			let mut node = Node::new(NodeKind::Memo);
+			node.state = NodeState::Dirty;
+			node.value = Some(value);
+			node.compute = Some(compute);
+			graph.insert(node)
+		})
+	}
+
+	/// Effects run immediately
+	pub fn create_effect(compute: Compute) -> NodeId {
+		let id = Self::with(|graph| {
+			let mut node = Node::new(NodeKind::Effect);
+			node.state = NodeState::Dirty;
+			node.compute = Some(compute);
+			graph.insert(node)
+		});
+		Self::update_if_necessary(id);
+		id
+	}
+
+	/// Create an effect without a compute function, subscribed to
+	/// the sources read by the function. The effect does nothing
+	/// until [`Self::set_compute`] is called.
+	pub fn create_tracked_effect<R>(func: impl FnOnce() -> R) -> (NodeId, R) {
+		let id = Self::with(|graph| graph.insert(Node::new(NodeKind::Effect)));
+		let (prev_observer, prev_owner) = Self::with(|graph| {
+			(graph.observer.replace(id), graph.owner.replace(id))
+		});
+		let out = func();
+		Self::with(|graph| {
+			graph.observer = prev_observer;
+			graph.owner = prev_owner;
+		});
+		(id, out)
+	}
+
+	/// Set the compute function of a node without running it
+	pub fn set_compute(id: NodeId, compute: Compute) {
+		Self::with(|graph| {
+			if let Some(node) = graph.node_mut(id) {
+				node.compute = Some(compute);
+			}
+		});
+	}
+
+	/// Run the function with a new scope as the owner,
+	/// returning the id of the scope
+	pub fn create_scope(func: impl FnOnce()) -> NodeId {
+		let id = Self::with(|graph| graph.insert(Node::new(NodeKind::Scope)));
+		let prev = Self::with(|graph| graph.owner.replace(id));
+		func();
+		Self::with(|graph| graph.owner = prev);
+		id
+	}
+
+	/// Register a cleanup function on the current owner, to be called
+	/// when it is disposed or recomputed. Without an owner this is a noop,
+	/// as the owner would live forever.
+	pub fn on_cleanup(func: impl 'static + FnOnce()) {
+		Self::with(|graph| {
+			if let Some(owner) = graph.owner.and_then(|id| graph.node_mut(id)) {
+				owner.cleanups.push(Box::new(func));
+			}
+		});
+	}
+
+	/// Get the value of a signal or memo, subscribing the current observer.
+	/// Returns `None` if the node has been disposed.
+	pub fn read(id: NodeId) -> Option<Rc<dyn Any>> {
+		Self::update_if_necessary(id);
+		Self::with(|graph| {
+			let value = graph.node(id)?.value.clone();
+			if let Some(observer) = graph.observer {
+				graph.subscribe(observer, id);
+			}
+			value
+		})
+	}
+
+	/// Get the value of a signal or memo without subscribing.
+	pub fn read_untracked(id: NodeId) -> Option<Rc<dyn Any>> {
+		Self::untrack(|| Self::read(id))
+	}
+
+	fn subscribe(&mut self, observer: NodeId, source: NodeId) {
+		let Some(node) = self.node_mut(observer) else {
+			return;
+		};
+		if node.sources.contains(&source) {
+			return;
+		}
+		node.sources.push(source);
+		if let Some(source) = self.node_mut(source) {
+			source.subscribers.push(observer);
+		}
+	}
+
+	/// Run the function without tracking reads
+	pub fn untrack<R>(func: impl FnOnce() -> R) -> R {
+		let prev = Self::with(|graph| graph.observer.take());
+		let out = func();
+		Self::with(|graph| graph.observer = prev);
+		out
+	}
+
+	/// Notify subscribers that the value of a signal has changed,
+	/// the value should already have been updated.
+	pub fn notify(id: NodeId) {
+		Self::with(|graph| {
+			let subscribers = graph
+				.node(id)
+				.map(|node| node.subscribers.clone())
+				.unwrap_or_default();
+			for subscriber in subscribers {
+				graph.mark(subscriber, NodeState::Dirty);
+			}
+		});
+		Self::flush();
+	}
+
+	/// Mark a node and push [`NodeState::Check`] to all its descendents
+	fn mark(&mut self, id: NodeId, state: NodeState) {
+		let Some(node) = self.node_mut(id) else {
+			return;
+		};
+		if node.state >= state {
+			return;
+		}
+		let was_clean = node.state == NodeState::Clean;
+		node.state = state;
+		let is_effect = node.kind == NodeKind::Effect;
+		let subscribers = node.subscribers.clone();
+		if was_clean && is_effect {
+			self.pending_effects.push_back(id);
+		}
+		for subscriber in subscribers {
+			self.mark(subscriber, NodeState::Check);
+		}
+	}
+
+	/// Run all updates as a single transaction, effects will run
+	/// once after the function has returned.
+	pub fn batch<R>(func: impl FnOnce() -> R) -> R {
+		Self::with(|graph| graph.batch_depth += 1);
+		let out = func();
+		Self::with(|graph| graph.batch_depth -= 1);
+		Self::flush();
+		out
+	}
+
+	/// Run pending effects, unless batching or already flushing.
+	fn flush() {
+		let should_flush = Self::with(|graph| {
+			if graph.batch_depth > 0 || graph.flushing {
+				false
+			} else {
+				graph.flushing = true;
+				true
+			}
+		});
+		if !should_flush {
+			return;
+		}
+		while let Some(id) =
+			Self::with(|graph| graph.pending_effects.pop_front())
+		{
+			Self::update_if_necessary(id);
+		}
+		Self::with(|graph| graph.flushing = false);
+	}
+
+	/// Pull changes from sources, only recomputing
+	/// the node if a source has actually changed.
+	fn update_if_necessary(id: NodeId) {
+		let Some((state, sources)) = Self::with(|graph| {
+			graph
+				.node(id)
+				.map(|node| (node.state, node.sources.clone()))
+		}) else {
+			return;
+		};
+		if state == NodeState::Check {
+			for source in sources {
+				Self::update_if_necessary(source);
+				// a source memo changed, which marks this node dirty
+				if Self::with(|graph| graph.node(id).map(|node| node.state))
+					== Some(NodeState::Dirty)
+				{
+					break;
+				}
+			}
+		}
+		let state = Self::with(|graph| graph.node(id).map(|node| node.state));
+		if state == Some(NodeState::Dirty) {
+			Self::recompute(id);
+		}
+		Self::with(|graph| {
+			if let Some(node) = graph.node_mut(id) {
+				node.state = NodeState::Clean;
+			}
+		});
+	}
+
+	fn recompute(id: NodeId) {
+		let Some(compute) = Self::with(|graph| {
+			graph.node(id).and_then(|node| node.compute.clone())
+		}) else {
+			return;
+		};
+		Self::cleanup(id);
+		let (prev_observer, prev_owner) = Self::with(|graph| {
+			(graph.observer.replace(id), graph.owner.replace(id))
+		});
+		let changed = compute.borrow_mut()();
+		Self::with(|graph| {
+			graph.observer = prev_observer;
+			graph.owner = prev_owner;
+			if !changed {
+				return;
+			}
+			let subscribers = graph
+				.node(id)
+				.map(|node| node.subscribers.clone())
+				.unwrap_or_default();
+			for subscriber in subscribers {
+				if let Some(node) = graph.node_mut(subscriber) {
+					node.state = NodeState::Dirty;
+				}
+			}
+		});
+	}
+
+	/// Unsubscribe from sources, dispose owned nodes and run cleanups,
+	/// called before a node recomputes or is disposed.
+	fn cleanup(id: NodeId) {
+		let (owned, cleanups) = Self::with(|graph| {
+			let Some(node) = graph.node_mut(id) else {
+				return (Vec::new(), Vec::new());
+			};
+			let sources = std::mem::take(&mut node.sources);
+			let owned = std::mem::take(&mut node.owned);
+			let cleanups = std::mem::take(&mut node.cleanups);
+			for source in sources {
+				if let Some(source) = graph.node_mut(source) {
+					source.subscribers.retain(|sub| *sub != id);
+				}
+			}
+			(owned, cleanups)
+		});
+		for child in owned {
+			Self::dispose(child);
+		}
+		for cleanup in cleanups {
+			Self::untrack(cleanup);
+		}
+	}
+
+	/// Remove a node and everything it owns from the graph
+	pub fn dispose(id: NodeId) {
+		Self::cleanup(id);
+		Self::with(|graph| {
+			let Some(node) = graph.remove(id) else {
+				return;
+			};
+			if let Some(owner) = node.owner.and_then(|id| graph.node_mut(id)) {
+				owner.owned.retain(|child| *child != id);
+			}
+			for subscriber in node.subscribers {
+				if let Some(subscriber) = graph.node_mut(subscriber) {
+					subscriber.sources.retain(|source| *source != id);
+				}
+			}
+		});
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn reuses_slots() {
+		let first = ReactiveGraph::create_signal(Rc::new(0));
+		ReactiveGraph::dispose(first);
+		let second = ReactiveGraph::create_signal(Rc::new(1));
+		expect(second.index).to_be(first.index);
+		// the stale handle does not point to the new node
+		expect(ReactiveGraph::is_disposed(first)).to_be_true();
+		expect(ReactiveGraph::read(first).is_none()).to_be_true();
+		expect(ReactiveGraph::read(second).is_some()).to_be_true();
+	}
+}
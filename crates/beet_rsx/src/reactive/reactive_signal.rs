@@ -0,0 +1,417 @@
+use super::reactive_graph::NodeId;
+use super::reactive_graph::ReactiveGraph;
+use crate::prelude::*;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Create a signal, returning a getter and setter.
+/// Both halves are `Copy` so can be freely moved into closures.
+/// ```
+/// # use beet_rsx::reactive::*;
+/// let (count, set_count) = create_signal(1);
+/// set_count.update(|count| *count += 1);
+/// assert_eq!(count.get(), 2);
+/// ```
+pub fn create_signal<T: 'static>(value: T) -> (ReadSignal<T>, WriteSignal<T>) {
+	let id = ReactiveGraph::create_signal(Rc::new(RefCell::new(value)));
+	(
+		ReadSignal {
+			id,
+			phantom: PhantomData,
+		},
+		WriteSignal {
+			id,
+			phantom: PhantomData,
+		},
+	)
+}
+
+/// A [`create_signal`] that is tracked by the [`ResumableState`],
+/// so on the client it resumes from the value rendered by the server.
+#[cfg(feature = "serde")]
+pub fn create_resumable_signal<
+	T: 'static + Clone + serde::Serialize + serde::de::DeserializeOwned,
+>(
+	value: T,
+) -> (ReadSignal<T>, WriteSignal<T>) {
+	let (read, write) = create_signal(ResumableState::resume(value));
+	ResumableState::track(move || read.get_untracked());
+	(read, write)
+}
+
+/// Create a derived value that is only recomputed when its sources change,
+/// and only notifies subscribers if the new value is different.
+/// Memos are lazy, the function will not run until the value is read.
+/// ```
+/// # use beet_rsx::reactive::*;
+/// let (count, set_count) = create_signal(1);
+/// let double = create_memo(move || count.get() * 2);
+/// set_count.set(2);
+/// assert_eq!(double.get(), 4);
+/// ```
+pub fn create_memo<T: 'static + PartialEq>(
+	mut func: impl 'static + FnMut() -> T,
+) -> Memo<T> {
+	let value = Rc::new(RefCell::new(None::<T>));
+	let value2 = value.clone();
+	let id = ReactiveGraph::create_memo(
+		value,
+		Rc::new(RefCell::new(move || {
+			let next = func();
+			let mut value = value2.borrow_mut();
+			if value.as_ref() == Some(&next) {
+				false
+			} else {
+				*value = Some(next);
+				true
+			}
+		})),
+	);
+	Memo {
+		id,
+		phantom: PhantomData,
+	}
+}
+
+/// Create an effect that runs immediately, and again whenever
+/// any signal or memo read during its last run changes.
+/// Any memos, effects or cleanups created inside the effect are
+/// owned by it, and disposed before it runs again.
+pub fn create_effect(mut func: impl 'static + FnMut()) {
+	ReactiveGraph::create_effect(Rc::new(RefCell::new(move || {
+		func();
+		true
+	})));
+}
+
+/// An effect subscribed to the sources read by [`create_tracked_effect`],
+/// it does nothing until its function is set.
+pub struct TrackedEffect(NodeId);
+
+impl TrackedEffect {
+	/// Set the function, unlike [`create_effect`] it is not called
+	/// immediately as the tracked function already computed the current value.
+	pub fn effect(self, mut func: impl 'static + FnMut()) {
+		ReactiveGraph::set_compute(
+			self.0,
+			Rc::new(RefCell::new(move || {
+				func();
+				true
+			})),
+		);
+	}
+}
+
+/// Call the function, subscribing a [`TrackedEffect`] to any sources it reads.
+/// Memos and effects created by the function are owned by the effect.
+pub fn create_tracked_effect<O>(
+	func: impl FnOnce() -> O,
+) -> (O, TrackedEffect) {
+	let (id, out) = ReactiveGraph::create_tracked_effect(func);
+	(out, TrackedEffect(id))
+}
+
+/// Run the function in a new [`ReactiveScope`], which will own all memos,
+/// effects and cleanups created inside it until [`ReactiveScope::dispose`]
+/// is called.
+pub fn create_scope(func: impl FnOnce()) -> ReactiveScope {
+	ReactiveScope(ReactiveGraph::create_scope(func))
+}
+
+/// Register a function to be called when the current effect reruns
+/// or the current scope is disposed.
+pub fn on_cleanup(func: impl 'static + FnOnce()) {
+	ReactiveGraph::on_cleanup(func);
+}
+
+/// Run the function without subscribing the current effect or memo
+/// to any signals read inside it.
+pub fn untrack<R>(func: impl FnOnce() -> R) -> R {
+	ReactiveGraph::untrack(func)
+}
+
+/// Apply several updates as a single change, effects
+/// will run once after the function returns.
+/// ```
+/// # use beet_rsx::reactive::*;
+/// let (a, set_a) = create_signal(1);
+/// let (b, set_b) = create_signal(2);
+/// batch(|| {
+/// 	set_a.set(3);
+/// 	set_b.set(4);
+/// });
+/// assert_eq!(a.get() + b.get(), 7);
+/// ```
+pub fn batch<R>(func: impl FnOnce() -> R) -> R { ReactiveGraph::batch(func) }
+
+/// The owner of reactive nodes created with [`create_scope`],
+/// for instance the effects of a mounted tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReactiveScope(NodeId);
+
+impl ReactiveScope {
+	/// Dispose all memos and effects owned by this scope,
+	/// running their cleanup functions.
+	pub fn dispose(self) { ReactiveGraph::dispose(self.0); }
+	/// Whether [`Self::dispose`] has been called.
+	pub fn is_disposed(&self) -> bool { ReactiveGraph::is_disposed(self.0) }
+}
+
+/// The getter half of a signal
+pub struct ReadSignal<T> {
+	id: NodeId,
+	phantom: PhantomData<fn() -> T>,
+}
+
+/// The setter half of a signal
+pub struct WriteSignal<T> {
+	id: NodeId,
+	phantom: PhantomData<fn() -> T>,
+}
+
+/// A derived value, see [`create_memo`]
+pub struct Memo<T> {
+	id: NodeId,
+	phantom: PhantomData<fn() -> T>,
+}
+
+// manual impls to avoid the `T: Clone` bound
+macro_rules! impl_handle {
+	($ty:ident) => {
+		impl<T> Clone for $ty<T> {
+			fn clone(&self) -> Self { *self }
+		}
+		impl<T> Copy for $ty<T> {}
+		impl<T> std::fmt::Debug for $ty<T> {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.debug_tuple(stringify!($ty)).field(&self.id).finish()
+			}
+		}
+	};
+}
+impl_handle!(ReadSignal);
+impl_handle!(WriteSignal);
+impl_handle!(Memo);
+
+/// ## Panics
+/// If the node has been disposed
+fn expect_value(value: Option<Rc<dyn std::any::Any>>) -> Rc<dyn std::any::Any> {
+	value.expect("Attempted to access a signal or memo that has been disposed")
+}
+
+impl<T: 'static> ReadSignal<T> {
+	/// Access the value by reference, subscribing the current effect.
+	pub fn with<R>(&self, func: impl FnOnce(&T) -> R) -> R {
+		let value = expect_value(ReactiveGraph::read(self.id));
+		let value = value.downcast_ref::<RefCell<T>>().unwrap().borrow();
+		func(&value)
+	}
+	/// Access the value by reference without subscribing.
+	pub fn with_untracked<R>(&self, func: impl FnOnce(&T) -> R) -> R {
+		untrack(|| self.with(func))
+	}
+	/// Clone the value, subscribing the current effect.
+	pub fn get(&self) -> T
+	where
+		T: Clone,
+	{
+		self.with(T::clone)
+	}
+	/// Clone the value without subscribing.
+	pub fn get_untracked(&self) -> T
+	where
+		T: Clone,
+	{
+		self.with_untracked(T::clone)
+	}
+}
+
+impl<T: 'static> WriteSignal<T> {
+	/// Set the value, notifying subscribers.
+	pub fn set(&self, value: T) { self.update(|current| *current = value); }
+
+	/// Update the value in place, notifying subscribers.
+	pub fn update(&self, func: impl FnOnce(&mut T)) {
+		let value = expect_value(ReactiveGraph::read_untracked(self.id));
+		func(&mut value.downcast_ref::<RefCell<T>>().unwrap().borrow_mut());
+		ReactiveGraph::notify(self.id);
+	}
+}
+
+impl<T: 'static> Memo<T> {
+	/// Access the value by reference, subscribing the current effect.
+	pub fn with<R>(&self, func: impl FnOnce(&T) -> R) -> R {
+		let value = expect_value(ReactiveGraph::read(self.id));
+		let value =
+			value.downcast_ref::<RefCell<Option<T>>>().unwrap().borrow();
+		func(value.as_ref().expect("memos are computed when read"))
+	}
+	/// Access the value by reference without subscribing.
+	pub fn with_untracked<R>(&self, func: impl FnOnce(&T) -> R) -> R {
+		untrack(|| self.with(func))
+	}
+	/// Clone the value, subscribing the current effect.
+	pub fn get(&self) -> T
+	where
+		T: Clone,
+	{
+		self.with(T::clone)
+	}
+	/// Clone the value without subscribing.
+	pub fn get_untracked(&self) -> T
+	where
+		T: Clone,
+	{
+		self.with_untracked(T::clone)
+	}
+}
+
+pub struct ReadSignalIntoRsx;
+impl<T: 'static + Clone + IntoRsxRoot<M>, M> IntoRsxRoot<(M, ReadSignalIntoRsx)>
+	for ReadSignal<T>
+{
+	fn into_root(self) -> RsxRoot { self.get().into_root() }
+}
+pub struct MemoIntoRsx;
+impl<T: 'static + Clone + IntoRsxRoot<M>, M> IntoRsxRoot<(M, MemoIntoRsx)>
+	for Memo<T>
+{
+	fn into_root(self) -> RsxRoot { self.get().into_root() }
+}
+
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use sweet::prelude::*;
+
+	fn counter() -> (Rc<RefCell<u32>>, impl Fn()) {
+		let count = Rc::new(RefCell::new(0));
+		let count2 = count.clone();
+		(count, move || *count2.borrow_mut() += 1)
+	}
+
+	#[test]
+	fn signals() {
+		let (get, set) = create_signal(0);
+		let (runs, incr) = counter();
+		create_effect(move || {
+			get.get();
+			incr();
+		});
+		expect(*runs.borrow()).to_be(1);
+		set.set(1);
+		expect(get.get()).to_be(1);
+		expect(*runs.borrow()).to_be(2);
+		set.update(|val| *val += 1);
+		expect(get.get_untracked()).to_be(2);
+		expect(*runs.borrow()).to_be(3);
+	}
+
+	#[test]
+	fn memos() {
+		let (get, set) = create_signal(1);
+		let (memo_runs, incr_memo) = counter();
+		let is_even = create_memo(move || {
+			incr_memo();
+			get.get() % 2 == 0
+		});
+		// memos are lazy
+		expect(*memo_runs.borrow()).to_be(0);
+		let (runs, incr) = counter();
+		create_effect(move || {
+			is_even.get();
+			incr();
+		});
+		expect(*memo_runs.borrow()).to_be(1);
+		expect(*runs.borrow()).to_be(1);
+		set.set(2);
+		expect(*memo_runs.borrow()).to_be(2);
+		expect(*runs.borrow()).to_be(2);
+		// memo is unchanged, so the effect does not run
+		set.set(4);
+		expect(*memo_runs.borrow()).to_be(3);
+		expect(*runs.borrow()).to_be(2);
+	}
+
+	#[test]
+	fn diamond() {
+		let (get, set) = create_signal(1);
+		let double = create_memo(move || get.get() * 2);
+		let triple = create_memo(move || get.get() * 3);
+		let seen = Rc::new(RefCell::new(Vec::new()));
+		let seen2 = seen.clone();
+		create_effect(move || {
+			seen2.borrow_mut().push((double.get(), triple.get()));
+		});
+		set.set(2);
+		// no glitch where only one of the memos had updated
+		expect(seen.borrow().clone()).to_be(vec![(2, 3), (4, 6)]);
+	}
+
+	#[test]
+	fn batched() {
+		let (a, set_a) = create_signal(1);
+		let (b, set_b) = create_signal(1);
+		let (runs, incr) = counter();
+		create_effect(move || {
+			a.get();
+			b.get();
+			incr();
+		});
+		batch(|| {
+			set_a.set(2);
+			set_b.set(2);
+		});
+		expect(*runs.borrow()).to_be(2);
+	}
+
+	#[test]
+	fn untracked() {
+		let (a, set_a) = create_signal(1);
+		let (b, set_b) = create_signal(1);
+		let (runs, incr) = counter();
+		create_effect(move || {
+			a.get();
+			untrack(|| b.get());
+			incr();
+		});
+		set_b.set(2);
+		expect(*runs.borrow()).to_be(1);
+		set_a.set(2);
+		expect(*runs.borrow()).to_be(2);
+	}
+
+	#[test]
+	fn scopes() {
+		let (get, set) = create_signal(0);
+		let (cleanups, incr_cleanups) = counter();
+		let (inner_runs, incr_inner) = counter();
+		let incr_inner = Rc::new(incr_inner);
+		let incr_cleanups = Rc::new(incr_cleanups);
+		let scope = create_scope(move || {
+			create_effect(move || {
+				get.get();
+				let incr_inner = incr_inner.clone();
+				// nested effects are disposed when the parent reruns
+				create_effect(move || {
+					get.get();
+					incr_inner();
+				});
+				let incr_cleanups = incr_cleanups.clone();
+				on_cleanup(move || incr_cleanups());
+			});
+		});
+		expect(*inner_runs.borrow()).to_be(1);
+		set.set(1);
+		expect(*cleanups.borrow()).to_be(1);
+		// old inner effect was disposed before it could run
+		expect(*inner_runs.borrow()).to_be(2);
+		scope.dispose();
+		expect(scope.is_disposed()).to_be_true();
+		expect(*cleanups.borrow()).to_be(2);
+		set.set(2);
+		expect(*inner_runs.borrow()).to_be(2);
+	}
+}
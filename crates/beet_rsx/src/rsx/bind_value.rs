@@ -0,0 +1,113 @@
+/// Values that can be bound to a form element with `bind:value`,
+/// or used as the value of a `bind:group`.
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// # use beet_rsx::sigfault::signal;
+/// let (get, set) = signal(1.5_f32);
+/// let mut root = rsx! { <input type="number" bind:value={(get, set)}/> };
+/// DomTarget::set(RsDomTarget::new(&mut root));
+/// root.register_effects();
+/// DomTarget::dispatch("input", "input", "2.5").unwrap();
+/// let html = DomTarget::with(|target| target.render());
+/// assert!(html.contains("value=\"2.5\""));
+/// ```
+pub trait BindValue: Sized {
+	/// The value of the attribute, ie `value="2.5"`
+	fn to_bind_value(&self) -> String;
+	/// Parse the value of the element, returning `None` if it is
+	/// not valid, ie a number input containing only `-`, in which
+	/// case the signal is not updated.
+	fn from_bind_value(value: &str) -> Option<Self>;
+}
+
+impl BindValue for String {
+	fn to_bind_value(&self) -> String { self.clone() }
+	fn from_bind_value(value: &str) -> Option<Self> { Some(value.to_string()) }
+}
+
+macro_rules! impl_bind_value_number {
+	($($ty:ty),*) => {
+		$(impl BindValue for $ty {
+			fn to_bind_value(&self) -> String { self.to_string() }
+			fn from_bind_value(value: &str) -> Option<Self> {
+				value.trim().parse().ok()
+			}
+		})*
+	};
+}
+
+impl_bind_value_number!(
+	u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+
+/// Values that can be bound to a group of radio buttons or
+/// checkboxes with `bind:group`, where each element has a `value`:
+/// - A single [BindValue] is the value of the checked radio button.
+/// - A `Vec` of [BindValue] contains the values of all checked checkboxes.
+pub trait BindGroup: Sized {
+	/// Whether the element with this value is checked
+	fn is_checked(&self, value: &str) -> bool;
+	/// The next value when the element with this value is checked
+	/// or unchecked, returning `None` if there is no change.
+	fn on_change(&self, value: &str, checked: bool) -> Option<Self>;
+}
+
+impl<T: BindValue> BindGroup for T {
+	fn is_checked(&self, value: &str) -> bool { self.to_bind_value() == value }
+	fn on_change(&self, value: &str, checked: bool) -> Option<Self> {
+		// unchecking a radio button means another was checked
+		if checked {
+			T::from_bind_value(value)
+		} else {
+			None
+		}
+	}
+}
+
+impl<T: BindValue + Clone> BindGroup for Vec<T> {
+	fn is_checked(&self, value: &str) -> bool {
+		self.iter().any(|item| item.to_bind_value() == value)
+	}
+	fn on_change(&self, value: &str, checked: bool) -> Option<Self> {
+		let mut next = self
+			.iter()
+			.filter(|item| item.to_bind_value() != value)
+			.cloned()
+			.collect::<Vec<_>>();
+		if checked {
+			next.push(T::from_bind_value(value)?);
+		}
+		Some(next)
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn numbers() {
+		expect(u32::from_bind_value(" 7")).to_be(Some(7));
+		expect(i32::from_bind_value("-")).to_be(None);
+		expect(f64::from_bind_value("1.5")).to_be(Some(1.5));
+		expect(String::from_bind_value("-")).to_be(Some("-".to_string()));
+		expect(2.5_f32.to_bind_value()).to_be("2.5");
+	}
+
+	#[test]
+	fn group() {
+		let radio = "a".to_string();
+		expect(radio.is_checked("a")).to_be_true();
+		expect(radio.on_change("b", true)).to_be(Some("b".to_string()));
+		expect(radio.on_change("a", false)).to_be(None);
+
+		let checkboxes = vec![1, 2];
+		expect(checkboxes.is_checked("2")).to_be_true();
+		expect(checkboxes.on_change("3", true)).to_be(Some(vec![1, 2, 3]));
+		expect(checkboxes.on_change("1", false)).to_be(Some(vec![2]));
+	}
+}
@@ -0,0 +1,179 @@
+use crate::prelude::*;
+use std::ops::Range;
+
+/// Directives for rendering a component as an island, where only
+/// the component is hydrated and the rest of the page is static html.
+/// If a page contains any islands [RsxToResumableHtml] will only output
+/// tree locations and event wiring for them.
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// #[derive(Props)]
+/// struct Counter;
+/// impl Component for Counter {
+/// 	fn render(self) -> RsxRoot {
+/// 		let count = 0;
+/// 		rsx! { <button>{count}</button> }
+/// 	}
+/// }
+/// let root = rsx! { <main><Counter client:visible /></main> };
+/// let islands = root.client_islands();
+/// assert_eq!(islands[0].directive, ClientDirective::Visible);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClientDirective {
+	/// `client:load`, hydrate as soon as the page loads
+	Load,
+	/// `client:idle`, hydrate once the browser is idle
+	Idle,
+	/// `client:visible`, hydrate once the island enters the viewport
+	Visible,
+}
+
+impl ClientDirective {
+	/// The name used by the island loader, ie `load`
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Load => "load",
+			Self::Idle => "idle",
+			Self::Visible => "visible",
+		}
+	}
+}
+
+/// A component with a [ClientDirective]. Tree indices are assigned
+/// depth first, so all nodes in the island are a contiguous range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientIsland {
+	pub directive: ClientDirective,
+	/// The location of the component node
+	pub loc: TreeLocation,
+	/// The tree indices of the component node and all of its descendants
+	pub tree_range: Range<u32>,
+}
+
+impl ClientIsland {
+	/// Returns true if the node at this index is part of the island
+	pub fn contains(&self, tree_idx: TreeIdx) -> bool {
+		self.tree_range.contains(&*tree_idx)
+	}
+}
+
+impl RsxNode {
+	/// All components with a [ClientDirective] in depth first order.
+	/// Islands nested in another island are hydrated as part of the
+	/// outer island so are not included.
+	pub fn client_islands(&self) -> Vec<ClientIsland> {
+		let mut islands = Vec::<ClientIsland>::new();
+		TreeLocationVisitor::visit(self, |loc, node| {
+			let RsxNode::Component(RsxComponent {
+				client_directive: Some(directive),
+				..
+			}) = node
+			else {
+				return;
+			};
+			if islands.iter().any(|island| island.contains(loc.tree_idx)) {
+				return;
+			}
+			let mut num_nodes = 0;
+			TreeLocationVisitor::visit(node, |_, _| num_nodes += 1);
+			islands.push(ClientIsland {
+				directive: *directive,
+				loc,
+				tree_range: *loc.tree_idx..*loc.tree_idx + num_nodes,
+			});
+		});
+		islands
+	}
+
+	/// Register the effects of a single island, used by the client
+	/// to hydrate islands independently.
+	pub fn register_island_effects(&mut self, island: &ClientIsland) {
+		self.register_effects_where(|loc| island.contains(loc.tree_idx));
+	}
+}
+
+impl TreeLocationMap {
+	/// Retain only the locations required to hydrate the islands,
+	/// including collapsed elements that are the parent of an island.
+	pub fn retain_islands(&mut self, islands: &[ClientIsland]) {
+		self.rusty_locations.retain(|idx, _| {
+			islands.iter().any(|island| island.contains(*idx))
+		});
+		let parents = self
+			.rusty_locations
+			.values()
+			.map(|loc| loc.parent_idx)
+			.collect::<Vec<_>>();
+		self.collapsed_elements
+			.retain(|idx, _| parents.contains(idx));
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[derive(Props)]
+	struct Counter {
+		initial: u32,
+	}
+	impl Component for Counter {
+		fn render(self) -> RsxRoot {
+			let on_click = |_| {};
+			rsx! {
+				<div>
+					<button onclick=on_click>{self.initial}</button>
+					<Counter2 client:load />
+				</div>
+			}
+		}
+	}
+	#[derive(Props)]
+	struct Counter2;
+	impl Component for Counter2 {
+		fn render(self) -> RsxRoot {
+			let value = 2;
+			rsx! { <span>{value}</span> }
+		}
+	}
+
+	fn page() -> RsxRoot {
+		let value = "static";
+		rsx! {
+			<main>
+				<p>{value}</p>
+				<Counter initial=1 client:visible />
+				<Counter2 client:idle />
+			</main>
+		}
+	}
+
+	#[test]
+	fn islands() {
+		let root = page();
+		let islands = root.client_islands();
+		expect(islands.len()).to_be(2);
+		expect(islands[0].directive).to_be(ClientDirective::Visible);
+		expect(islands[1].directive).to_be(ClientDirective::Idle);
+		// tree idxs are depth first
+		expect(islands[0].tree_range.end).to_be(*islands[1].loc.tree_idx);
+		expect(root.client_islands()).to_be(islands);
+		expect(rsx! { <Counter2 /> }.client_islands().len()).to_be(0);
+	}
+
+	#[test]
+	fn retain_islands() {
+		let root = page();
+		let islands = root.client_islands();
+		let mut map = TreeLocationMap::from_node(&root);
+		expect(map.rusty_locations.len()).to_be(4);
+		map.retain_islands(&islands);
+		expect(map.rusty_locations.len()).to_be(3);
+		expect(map.collapsed_elements.len()).to_be(3);
+	}
+}
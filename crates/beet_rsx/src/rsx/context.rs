@@ -0,0 +1,208 @@
+use crate::prelude::*;
+use std::any::Any;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type ContextMap = HashMap<TypeId, Rc<dyn Any>>;
+
+thread_local! {
+	static CONTEXT: RefCell<RsxContext> = RefCell::new(RsxContext::default());
+}
+
+/// Provide a value to this component and its descendants, including
+/// slot children, replacing any value of the same type provided by this
+/// component. Outside of a component a scope must be entered with
+/// [`RsxContext::enter_scope`], otherwise the value would outlive
+/// every render on this thread.
+///
+/// ## Panics
+/// In debug builds, if there is no current scope. In release builds
+/// the value is not provided.
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// #[derive(Clone)]
+/// struct Theme(&'static str);
+///
+/// #[derive(Props)]
+/// struct ThemeProvider;
+/// impl Component for ThemeProvider {
+/// 	fn render(self) -> RsxRoot {
+/// 		provide_context(Theme("dark"));
+/// 		rsx! { <slot/> }
+/// 	}
+/// }
+///
+/// #[derive(Props)]
+/// struct Button;
+/// impl Component for Button {
+/// 	fn render(self) -> RsxRoot {
+/// 		let theme = use_context::<Theme>().map(|t| t.0).unwrap_or("light");
+/// 		rsx! { <button class={theme}>click me</button> }
+/// 	}
+/// }
+///
+/// let html = rsx! { <ThemeProvider><Button/></ThemeProvider> }
+/// 	.apply_and_render();
+/// assert!(html.contains("class=\"dark\""));
+/// ```
+pub fn provide_context<T: 'static>(value: T) {
+	CONTEXT.with(|context| {
+		let context = context.borrow();
+		let Some(scope) = context.scopes.last() else {
+			debug_assert!(
+				false,
+				"provide_context called outside of a component, use RsxContext::enter_scope"
+			);
+			return;
+		};
+		scope.borrow_mut().insert(TypeId::of::<T>(), Rc::new(value));
+	});
+}
+
+/// Get the nearest value of this type provided by a component or
+/// one of its ancestors, see [`provide_context`].
+pub fn use_context<T: 'static + Clone>() -> Option<T> {
+	CONTEXT.with(|context| {
+		context.borrow().scopes.iter().rev().find_map(|scope| {
+			scope
+				.borrow()
+				.get(&TypeId::of::<T>())
+				.and_then(|value| value.downcast_ref::<T>())
+				.cloned()
+		})
+	})
+}
+
+/// The context scopes of the component currently rendering.
+///
+/// Components are rendered eagerly by the `rsx!` macro, which enters
+/// a new scope for each component that is exited after its slot children
+/// are built. Effects capture the context when they are created so that
+/// [`use_context`] also works when they are registered, and runtimes
+/// use [`RsxContext::with`] when rerendering blocks.
+#[derive(Debug, Default, Clone)]
+pub struct RsxContext {
+	scopes: Vec<Rc<RefCell<ContextMap>>>,
+}
+
+impl RsxContext {
+	/// A snapshot of the current context
+	pub fn current() -> Self {
+		CONTEXT.with(|context| context.borrow().clone())
+	}
+
+	/// Enter a new scope for a component, which is exited when
+	/// the guard is dropped.
+	pub fn enter_scope() -> ContextScope {
+		let prev = Self::current();
+		CONTEXT.with(|context| {
+			context.borrow_mut().scopes.push(Default::default())
+		});
+		ContextScope { prev }
+	}
+
+	/// Run the function with this context, restoring the previous
+	/// context afterwards.
+	pub fn with<O>(&self, func: impl FnOnce() -> O) -> O {
+		let _scope = ContextScope {
+			prev: CONTEXT.with(|context| context.replace(self.clone())),
+		};
+		func()
+	}
+}
+
+/// Restores the previous [`RsxContext`] when dropped, including
+/// when unwinding from a panic.
+pub struct ContextScope {
+	prev: RsxContext,
+}
+
+impl Drop for ContextScope {
+	fn drop(&mut self) {
+		let prev = std::mem::take(&mut self.prev);
+		CONTEXT.with(|context| *context.borrow_mut() = prev);
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use crate::sigfault::signal;
+	use sweet::prelude::*;
+
+	#[derive(Debug, Clone, PartialEq)]
+	struct Locale(&'static str);
+
+	#[derive(Props)]
+	struct Provider {
+		locale: &'static str,
+	}
+	impl Component for Provider {
+		fn render(self) -> RsxRoot {
+			provide_context(Locale(self.locale));
+			rsx! { <div><slot/></div> }
+		}
+	}
+
+	#[derive(Props)]
+	struct Greeting;
+	impl Component for Greeting {
+		fn render(self) -> RsxRoot {
+			let locale = use_context::<Locale>().map(|l| l.0);
+			rsx! { <p>{locale.unwrap_or("none")}</p> }
+		}
+	}
+
+	#[test]
+	fn scopes() {
+		expect(use_context::<Locale>()).to_be_none();
+		let html = rsx! {
+			<Provider locale="en">
+				<Greeting/>
+				<Provider locale="fr">
+					<Greeting/>
+				</Provider>
+				<Greeting/>
+			</Provider>
+			<Greeting/>
+		}
+		.apply_and_render();
+		expect(html).to_be(
+			"<div><p data-beet-rsx-idx=\"5\">en</p><div><p data-beet-rsx-idx=\"13\">fr</p></div><p data-beet-rsx-idx=\"19\">en</p></div><p data-beet-rsx-idx=\"28\">none</p>",
+		);
+		expect(use_context::<Locale>()).to_be_none();
+	}
+
+	#[test]
+	fn with() {
+		let context = {
+			let _scope = RsxContext::enter_scope();
+			provide_context(Locale("en"));
+			RsxContext::current()
+		};
+		expect(use_context::<Locale>()).to_be_none();
+		expect(context.with(use_context::<Locale>)).to_be(Some(Locale("en")));
+		expect(use_context::<Locale>()).to_be_none();
+	}
+
+	#[test]
+	fn effects() {
+		let (get, set) = signal(0);
+		let label = move || {
+			let locale = use_context::<Locale>().map(|l| l.0);
+			format!("{} {}", locale.unwrap_or("none"), get())
+		};
+		let mut rsx = {
+			let _scope = RsxContext::enter_scope();
+			provide_context(Locale("en"));
+			rsx! { <span>{label}</span> }
+		};
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render())).to_contain("en 0");
+		set(1);
+		expect(&DomTarget::with(|h| h.render())).to_contain("en 1");
+	}
+}
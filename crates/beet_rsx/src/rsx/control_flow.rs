@@ -0,0 +1,289 @@
+use crate::prelude::*;
+use std::rc::Rc;
+
+/// Renders a keyed list, each item is given a `key` attribute so that
+/// when the list changes the [RsxDiff] will move, insert and remove items
+/// by their key instead of patching every item after the first change.
+/// The key is not rendered, and the effects of removed items are
+/// disposed, see [DomTarget::is_removed].
+/// Like other blocks it should be created in a closure, which will
+/// rerun when a signal used by `each` changes.
+///
+/// The key is applied to the root element of each item, items that render
+/// text or multiple root nodes are matched by their position.
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let (get, set) = beet::sigfault::signal(vec![1, 2, 3]);
+/// let list = move || {
+/// 	For::new(get.clone(), |item| *item, |item| rsx! { <li>{item}</li> })
+/// };
+/// let node = rsx! { <ul>{list}</ul> };
+/// ```
+pub struct For<T> {
+	each: Rc<dyn Fn() -> Vec<T>>,
+	key: Rc<dyn Fn(&T) -> String>,
+	children: Rc<dyn Fn(T) -> RsxRoot>,
+}
+
+impl<T> Clone for For<T> {
+	fn clone(&self) -> Self {
+		Self {
+			each: self.each.clone(),
+			key: self.key.clone(),
+			children: self.children.clone(),
+		}
+	}
+}
+
+impl<T> For<T> {
+	pub fn new<I: IntoIterator<Item = T>, K: ToString>(
+		each: impl 'static + Fn() -> I,
+		key: impl 'static + Fn(&T) -> K,
+		children: impl 'static + Fn(T) -> RsxRoot,
+	) -> Self {
+		Self {
+			each: Rc::new(move || each().into_iter().collect()),
+			key: Rc::new(move |item| key(item).to_string()),
+			children: Rc::new(children),
+		}
+	}
+}
+
+impl<T> IntoRsxRoot<For<T>> for For<T> {
+	fn into_root(self) -> RsxRoot {
+		let nodes = (self.each)()
+			.into_iter()
+			.map(|item| {
+				let key = (self.key)(&item);
+				let mut root = (self.children)(item);
+				set_key(&mut root.node, key);
+				root.node
+			})
+			.collect();
+		RsxRoot {
+			location: RsxMacroLocation::default(),
+			node: RsxNode::Fragment {
+				idx: RsxIdx::default(),
+				nodes,
+			},
+		}
+	}
+}
+
+/// Set the `key` attribute of the root element, replacing any existing key
+fn set_key(node: &mut RsxNode, key: String) {
+	match node {
+		RsxNode::Element(el) => {
+			el.attributes.retain(
+				|attr| !matches!(attr, RsxAttribute::KeyValue { key, .. } if key == "key"),
+			);
+			el.attributes.push(RsxAttribute::KeyValue {
+				key: "key".to_string(),
+				value: key,
+			});
+		}
+		RsxNode::Fragment { nodes, .. } if nodes.len() == 1 => {
+			set_key(&mut nodes[0], key);
+		}
+		RsxNode::Component(component) => {
+			set_key(&mut component.root.node, key);
+		}
+		_ => {}
+	}
+}
+
+/// Renders the `children` when the condition is true, otherwise the
+/// `fallback` if there is one. Like other blocks it should be created
+/// in a closure, which will rerun when a signal used by `when` changes.
+///
+/// Switching branches removes the previous nodes and inserts the next,
+/// while changes within a branch are patched in place by the [RsxDiff].
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let (get, set) = beet::sigfault::signal(false);
+/// let content = move || {
+/// 	Show::new(get.clone(), || rsx! { <p>welcome back</p> })
+/// 		.with_fallback(|| rsx! { <button>log in</button> })
+/// };
+/// let node = rsx! { <div>{content}</div> };
+/// ```
+#[derive(Clone)]
+pub struct Show {
+	when: Rc<dyn Fn() -> bool>,
+	children: Rc<dyn Fn() -> RsxRoot>,
+	fallback: Option<Rc<dyn Fn() -> RsxRoot>>,
+}
+
+impl Show {
+	pub fn new(
+		when: impl 'static + Fn() -> bool,
+		children: impl 'static + Fn() -> RsxRoot,
+	) -> Self {
+		Self {
+			when: Rc::new(when),
+			children: Rc::new(children),
+			fallback: None,
+		}
+	}
+	/// Render this when the condition is false
+	pub fn with_fallback(
+		mut self,
+		fallback: impl 'static + Fn() -> RsxRoot,
+	) -> Self {
+		self.fallback = Some(Rc::new(fallback));
+		self
+	}
+}
+
+impl IntoRsxRoot<Show> for Show {
+	fn into_root(self) -> RsxRoot {
+		if (self.when)() {
+			(self.children)()
+		} else if let Some(fallback) = &self.fallback {
+			fallback()
+		} else {
+			RsxRoot::default()
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use crate::sigfault::signal;
+	use std::cell::Cell;
+	use std::rc::Rc;
+	use sweet::prelude::*;
+
+	fn list(items: Vec<&'static str>) -> RsxRoot {
+		For::new(
+			move || items.clone(),
+			|item| *item,
+			|item| {
+				rsx! { <li>{item}</li> }
+			},
+		)
+		.into_root()
+	}
+
+	#[test]
+	fn keyed_patches() {
+		expect(RsxDiff::diff(
+			list(vec!["a", "b", "c"]),
+			list(vec!["c", "a"]),
+		))
+		.to_be(vec![RsxPatch::Remove { path: vec![1] }, RsxPatch::Move {
+			path: vec![1],
+			to: 0,
+		}]);
+		let patches =
+			RsxDiff::diff(list(vec!["a", "b"]), list(vec!["a", "c", "b"]));
+		expect(patches.len()).to_be(1);
+		expect(matches!(
+			&patches[0],
+			RsxPatch::Insert { path, .. } if path == &vec![1]
+		))
+		.to_be_true();
+	}
+
+	#[test]
+	fn for_each() {
+		let (get, set) = signal(vec!["a", "b", "c"]);
+		let items = move || {
+			For::new(
+				get.clone(),
+				|item| *item,
+				|item| {
+					rsx! { <li>{item}</li> }
+				},
+			)
+		};
+		let mut rsx = rsx! { <ul>{items}</ul> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<ul data-beet-rsx-idx=\"0\"><li data-beet-rsx-idx=\"3\">a</li><li data-beet-rsx-idx=\"6\">b</li><li data-beet-rsx-idx=\"9\">c</li></ul>",
+		);
+		// items are moved with their attributes, not patched in place
+		set(vec!["c", "a"]);
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<ul data-beet-rsx-idx=\"0\"><li data-beet-rsx-idx=\"9\">c</li><li data-beet-rsx-idx=\"3\">a</li></ul>",
+		);
+		set(vec!["c", "d", "a"]);
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<li data-beet-rsx-idx=\"9\">c</li><li data-beet-rsx-idx=\"12\">d</li><li data-beet-rsx-idx=\"3\">a</li></ul>",
+		);
+	}
+
+	#[test]
+	fn disposes_removed() {
+		let (get, set) = signal(vec!["a", "b"]);
+		let (class, set_class) = signal("x");
+		let runs = Rc::new(Cell::new(0));
+		let runs2 = runs.clone();
+		let items = move || {
+			let class = class.clone();
+			let runs = runs2.clone();
+			For::new(
+				get.clone(),
+				|item| *item,
+				move |item| {
+					let class = class.clone();
+					let runs = runs.clone();
+					let class = move || {
+						if item == "a" {
+							runs.set(runs.get() + 1);
+						}
+						class()
+					};
+					rsx! { <li class={class} onclick={|_| {}}>{item}</li> }
+				},
+			)
+		};
+		let mut rsx = rsx! { <ul>{items}</ul> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		set(vec!["b"]);
+		let prev_runs = runs.get();
+		set_class("y");
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<li class=\"y\" data-beet-rsx-idx=\"6\">b</li></ul>");
+		// the effect of the removed item disposed itself
+		expect(runs.get()).to_be(prev_runs);
+		set_class("z");
+		expect(runs.get()).to_be(prev_runs);
+		expect(DomTarget::dispatch("li", "click", ()).is_ok()).to_be_true();
+	}
+
+	#[test]
+	fn show() {
+		let (get, set) = signal(false);
+		let content = move || {
+			Show::new(get.clone(), || rsx! { <p>welcome</p> })
+				.with_fallback(|| rsx! { <button>log in</button> })
+		};
+		let mut rsx = rsx! { <div>{content}</div> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<div data-beet-rsx-idx=\"0\"><button>log in</button></div>",
+		);
+		set(true);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div data-beet-rsx-idx=\"0\"><p>welcome</p></div>");
+		set(false);
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<div data-beet-rsx-idx=\"0\"><button>log in</button></div>",
+		);
+
+		let (get, set) = signal(true);
+		let content = move || Show::new(get.clone(), || rsx! { <p>hello</p> });
+		let mut rsx = rsx! { <div>{content}</div> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		set(false);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div data-beet-rsx-idx=\"0\"></div>");
+	}
+}
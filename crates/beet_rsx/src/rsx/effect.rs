@@ -12,11 +12,17 @@ pub struct Effect {
 	/// this may or may not be populated depending
 	/// on the settings of the parser
 	pub tracker: RustyTracker,
+	/// the context when the effect was created, see [`use_context`]
+	context: RsxContext,
 }
 
 impl Effect {
 	pub fn new(register: RegisterEffect, tracker: RustyTracker) -> Self {
-		Self { register, tracker }
+		Self {
+			register,
+			tracker,
+			context: RsxContext::current(),
+		}
 	}
 
 	/// call the FnOnce register func and replace it
@@ -27,11 +33,82 @@ impl Effect {
 		Self {
 			register,
 			tracker: self.tracker,
+			context: self.context.clone(),
 		}
 	}
 
+	/// Register the effect with the context it was created in
 	pub fn register(self, loc: TreeLocation) -> Result<()> {
-		(self.register)(loc)
+		self.context.with(|| (self.register)(loc))
+	}
+}
+
+/// Whether effects created now should be subscribed while rendering
+/// their initial value instead of by their first run, which is skipped
+/// as the html already matches, see `ResumableState::is_resuming`.
+pub fn is_resuming() -> bool {
+	#[cfg(feature = "serde")]
+	return ResumableState::is_resuming();
+	#[cfg(not(feature = "serde"))]
+	false
+}
+
+/// The function run by the effect of a block node, shared by runtimes.
+/// The first run subscribes the effect, later runs rerender the block
+/// in the context the effect was registered in, catching errors with
+/// the nearest [ErrorBoundary], and apply the difference
+/// with [DomTarget::update_block]. Errors without a boundary are
+/// reported and the previous render is kept.
+pub fn block_effect<M>(
+	loc: TreeLocation,
+	block: impl 'static + Clone + IntoRsxRoot<M>,
+) -> impl 'static + FnMut() {
+	block_effect_inner(loc, block, true)
+}
+
+/// A [block_effect] that was subscribed while rendering the initial value
+/// of the block, see [is_resuming]. Every run is a change, the first
+/// replaces the nodes with [DomTarget::replace_block] as the initial
+/// render is not kept.
+pub fn resumed_block_effect<M>(
+	loc: TreeLocation,
+	block: impl 'static + Clone + IntoRsxRoot<M>,
+) -> impl 'static + FnMut() {
+	block_effect_inner(loc, block, false)
+}
+
+fn block_effect_inner<M>(
+	loc: TreeLocation,
+	block: impl 'static + Clone + IntoRsxRoot<M>,
+	mut first_run: bool,
+) -> impl 'static + FnMut() {
+	let mut prev: Option<RsxRoot> = None;
+	let context = RsxContext::current();
+	move || {
+		let (mut node, caught) = context.with(|| {
+			let mut node = block.clone().into_root();
+			let caught = ErrorBoundary::catch(&mut node);
+			(node, caught)
+		});
+		Suspense::evict(&node);
+		// resumable values created by a rerender are never serialized
+		#[cfg(feature = "serde")]
+		ResumableState::clear_tracked();
+		if let Err(errors) = caught {
+			errors.report();
+			first_run = false;
+			return;
+		}
+		if first_run {
+			first_run = false;
+		} else {
+			match &prev {
+				Some(prev) => DomTarget::update_block(loc, prev, &mut node),
+				None => DomTarget::replace_block(loc, &mut node),
+			}
+			.unwrap();
+		}
+		prev = Some(node);
 	}
 }
 
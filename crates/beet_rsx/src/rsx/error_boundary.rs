@@ -0,0 +1,483 @@
+use crate::prelude::*;
+use anyhow::Result;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// entries are taken by [ErrorBoundary::resolve],
+// which is called by each render path
+thread_local! {
+	static ERRORS: RefCell<HashMap<u64, anyhow::Error>> = RefCell::new(HashMap::default());
+	static BOUNDARIES: RefCell<HashMap<u64, ErrorBoundary>> = RefCell::new(HashMap::default());
+	static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+fn next_id() -> u64 {
+	NEXT_ID.with(|next| {
+		let id = next.get();
+		next.set(id + 1);
+		id
+	})
+}
+
+/// A component that may fail to render, errors are caught by the
+/// nearest [ErrorBoundary].
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// #[derive(Props)]
+/// struct MyComponent;
+/// impl TryComponent for MyComponent {
+/// 	fn render(self) -> anyhow::Result<RsxRoot> {
+/// 		let value: u32 = "42".parse()?;
+/// 		Ok(rsx! { <div>{value}</div> })
+/// 	}
+/// }
+/// let node = rsx! { <MyComponent/> };
+/// ```
+pub trait TryComponent {
+	fn render(self) -> Result<RsxRoot>;
+}
+
+/// An error returned by a component, with the location of the
+/// `rsx!` macro the component was used in.
+#[derive(Debug)]
+pub struct RsxError {
+	pub location: RsxMacroLocation,
+	pub error: anyhow::Error,
+}
+
+impl RsxError {
+	/// Log the error, this is called for each error caught by
+	/// an [ErrorBoundary].
+	pub fn report(&self) {
+		#[cfg(target_arch = "wasm32")]
+		web_sys::console::error_1(&self.to_string().into());
+		#[cfg(not(target_arch = "wasm32"))]
+		eprintln!("{self}");
+	}
+}
+
+impl std::fmt::Display for RsxError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Error in component at {}:{}:{}: {}",
+			self.location.file,
+			self.location.line,
+			self.location.col,
+			self.error
+		)
+	}
+}
+
+impl std::error::Error for RsxError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		Some(self.error.as_ref())
+	}
+}
+
+/// The errors not caught by an [ErrorBoundary], returned by
+/// [RsxRoot::build_document].
+#[derive(Debug, Default)]
+pub struct RsxErrors(pub Vec<RsxError>);
+
+impl RsxErrors {
+	pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+	/// Log each error with [RsxError::report]
+	pub fn report(&self) {
+		for error in self.0.iter() {
+			error.report();
+		}
+	}
+
+	/// Returns `Ok` if there are no errors
+	pub fn into_result(self) -> Result<(), Self> {
+		if self.is_empty() {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+}
+
+impl std::fmt::Display for RsxErrors {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let errors = self
+			.0
+			.iter()
+			.map(|error| error.to_string())
+			.collect::<Vec<_>>();
+		write!(f, "{}", errors.join("\n"))
+	}
+}
+
+impl std::error::Error for RsxErrors {}
+
+/// Errors are stored and replaced with a placeholder element,
+/// which is resolved by [ErrorBoundary::resolve].
+pub struct ResultIntoRsx;
+impl<T: IntoRsxRoot<M>, M> IntoRsxRoot<(M, ResultIntoRsx)> for Result<T> {
+	fn into_root(self) -> RsxRoot {
+		match self {
+			Ok(value) => value.into_root(),
+			Err(error) => {
+				let id = next_id();
+				ERRORS.with(|errors| errors.borrow_mut().insert(id, error));
+				RsxRoot {
+					location: RsxMacroLocation::default(),
+					node: placeholder(
+						ErrorBoundary::ERROR_TAG,
+						HtmlConstants::default().error_key,
+						id,
+						RsxNode::default(),
+					),
+				}
+			}
+		}
+	}
+}
+
+/// Renders the `fallback` in place of its children if any component
+/// in the subtree returns an error, reporting each error with
+/// [RsxError::report].
+///
+/// Errors are caught when rendering with [RsxRoot::build_document]
+/// or [RsxToResumableHtml::map_root], and when a block rerenders in an
+/// effect, where the fallback replaces the contents of the block.
+/// Errors outside of a boundary are returned by [RsxRoot::build_document],
+/// or reported by an effect which keeps the previous render of the block.
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// #[derive(Props)]
+/// struct Broken;
+/// impl TryComponent for Broken {
+/// 	fn render(self) -> anyhow::Result<RsxRoot> {
+/// 		anyhow::bail!("oh no")
+/// 	}
+/// }
+/// let fallback = |err: &RsxError| rsx! { <p>{err.error.to_string()}</p> };
+/// let html = rsx! {
+/// 	<ErrorBoundary fallback={fallback}>
+/// 		<Broken/>
+/// 	</ErrorBoundary>
+/// }
+/// .apply_and_render();
+/// assert!(html.contains("oh no"));
+/// ```
+#[derive(Clone)]
+pub struct ErrorBoundary {
+	fallback: Rc<dyn Fn(&RsxError) -> RsxRoot>,
+}
+
+/// The builder for [ErrorBoundary], the `fallback` prop is required.
+pub struct ErrorBoundaryBuilder;
+
+impl ErrorBoundaryBuilder {
+	/// Rendered in place of the children if any of them return an error
+	pub fn fallback(
+		self,
+		fallback: impl 'static + Fn(&RsxError) -> RsxRoot,
+	) -> ErrorBoundary {
+		ErrorBoundary {
+			fallback: Rc::new(fallback),
+		}
+	}
+}
+
+impl Props for ErrorBoundary {
+	type Builder = ErrorBoundaryBuilder;
+	fn builder() -> Self::Builder { ErrorBoundaryBuilder }
+}
+
+impl PropsBuilder for ErrorBoundary {
+	type Component = Self;
+	fn build(self) -> Self::Component { self }
+}
+
+impl Component for ErrorBoundary {
+	/// Renders the children in a boundary element, the boundary is
+	/// also provided as context for effects.
+	fn render(self) -> RsxRoot {
+		let id = next_id();
+		provide_context(self.clone());
+		BOUNDARIES.with(|boundaries| boundaries.borrow_mut().insert(id, self));
+		let slot = RsxNode::Element(RsxElement {
+			idx: 1,
+			tag: "slot".to_string(),
+			attributes: Vec::new(),
+			children: Box::new(RsxNode::default()),
+			self_closing: true,
+		});
+		RsxRoot {
+			location: RsxMacroLocation::default(),
+			node: placeholder(
+				Self::TAG,
+				HtmlConstants::default().error_boundary_key,
+				id,
+				slot,
+			),
+		}
+	}
+}
+
+impl ErrorBoundary {
+	/// The tag of the boundary element
+	pub const TAG: &'static str = "beet-error-boundary";
+	/// The tag of the placeholder for a component that returned an error
+	pub const ERROR_TAG: &'static str = "beet-error";
+
+	/// Replace the children of each boundary containing errors with its
+	/// fallback. This must be called after [SlotsVisitor::apply] so that
+	/// the children are inside the boundary.
+	/// Returns any errors that were not caught by a boundary.
+	pub fn resolve(root: &mut RsxRoot) -> RsxErrors {
+		let mut errors = Vec::new();
+		resolve_node(&mut root.node, &root.location, &mut errors);
+		RsxErrors(errors)
+	}
+
+	/// Resolve errors in a rerendered block, rendering the fallback of
+	/// the nearest boundary if any errors were not caught.
+	///
+	/// ## Errors
+	/// If there are uncaught errors and no boundary in the [RsxContext].
+	pub fn catch(root: &mut RsxRoot) -> Result<(), RsxErrors> {
+		let errors = Self::resolve(root);
+		if errors.is_empty() {
+			return Ok(());
+		}
+		let Some(boundary) = use_context::<ErrorBoundary>() else {
+			return Err(errors);
+		};
+		errors.report();
+		*root = (boundary.fallback)(&errors.0[0]);
+		Ok(())
+	}
+}
+
+fn placeholder(tag: &str, key: &str, id: u64, children: RsxNode) -> RsxNode {
+	RsxNode::Element(RsxElement {
+		idx: RsxIdx::default(),
+		tag: tag.to_string(),
+		attributes: vec![RsxAttribute::KeyValue {
+			key: key.to_string(),
+			value: id.to_string(),
+		}],
+		children: Box::new(children),
+		self_closing: false,
+	})
+}
+
+/// Returns the id if the element is a placeholder with this tag
+fn placeholder_id(el: &RsxElement, tag: &str, key: &str) -> Option<u64> {
+	if el.tag != tag {
+		return None;
+	}
+	el.attributes.iter().find_map(|attr| match attr {
+		RsxAttribute::KeyValue { key: k, value } if k == key => {
+			value.parse().ok()
+		}
+		_ => None,
+	})
+}
+
+fn resolve_node(
+	node: &mut RsxNode,
+	location: &RsxMacroLocation,
+	errors: &mut Vec<RsxError>,
+) {
+	let constants = HtmlConstants::default();
+	match node {
+		RsxNode::Element(el) => {
+			if let Some(id) = placeholder_id(
+				el,
+				ErrorBoundary::ERROR_TAG,
+				constants.error_key,
+			) {
+				// errors may have already been taken by a previous resolve
+				if let Some(error) =
+					ERRORS.with(|errors| errors.borrow_mut().remove(&id))
+				{
+					errors.push(RsxError {
+						location: location.clone(),
+						error,
+					});
+				}
+			} else if let Some(id) = placeholder_id(
+				el,
+				ErrorBoundary::TAG,
+				constants.error_boundary_key,
+			) {
+				let mut caught = Vec::new();
+				resolve_node(&mut el.children, location, &mut caught);
+				// the boundary is only needed once, effects get it from context
+				let boundary = BOUNDARIES.with(|b| b.borrow_mut().remove(&id));
+				if caught.is_empty() {
+					return;
+				}
+				match boundary {
+					Some(boundary) => {
+						let caught = RsxErrors(caught);
+						caught.report();
+						*el.children = (boundary.fallback)(&caught.0[0]).node;
+					}
+					None => errors.extend(caught),
+				}
+			} else {
+				resolve_node(&mut el.children, location, errors);
+			}
+		}
+		RsxNode::Fragment { nodes, .. } => {
+			for node in nodes.iter_mut() {
+				resolve_node(node, location, errors);
+			}
+		}
+		RsxNode::Block(block) => {
+			let location = root_location(&block.initial, location);
+			resolve_node(&mut block.initial.node, &location, errors);
+		}
+		RsxNode::Component(component) => {
+			// an error placeholder has no location, so it takes the location
+			// of the rsx macro that the component was used in
+			let inner = root_location(&component.root, location);
+			resolve_node(&mut component.root.node, &inner, errors);
+			resolve_node(&mut component.slot_children, location, errors);
+		}
+		_ => {}
+	}
+}
+
+/// The location of the root, or the parent location if it is a placeholder
+fn root_location(
+	root: &RsxRoot,
+	parent: &RsxMacroLocation,
+) -> RsxMacroLocation {
+	if root.location == RsxMacroLocation::default() {
+		parent.clone()
+	} else {
+		root.location.clone()
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use crate::sigfault::signal;
+	use sweet::prelude::*;
+
+	#[derive(Props)]
+	struct Parse {
+		value: &'static str,
+	}
+	impl TryComponent for Parse {
+		fn render(self) -> anyhow::Result<RsxRoot> {
+			let value: u32 = self.value.parse()?;
+			Ok(rsx! { <span>{value}</span> })
+		}
+	}
+
+	fn fallback(err: &RsxError) -> RsxRoot {
+		let line = err.location.line;
+		rsx! { <p>{line}</p> }
+	}
+
+	#[test]
+	fn catches() {
+		let line = line!() + 2;
+		let html = rsx! {
+			<div>
+				<ErrorBoundary fallback={fallback}>
+					<Parse value="foo"/>
+					<Parse value="1"/>
+				</ErrorBoundary>
+				<Parse value="2"/>
+			</div>
+		}
+		.apply_and_render();
+		expect(&html).to_contain(&format!(">{line}</p></beet-error-boundary>"));
+		expect(&html).not().to_contain(">1</span>");
+		expect(&html).to_contain(">2</span></div>");
+	}
+
+	#[test]
+	fn nested() {
+		let html = rsx! {
+			<ErrorBoundary fallback=|_: &RsxError| rsx! { outer }>
+				<ErrorBoundary fallback=|_: &RsxError| rsx! { inner }>
+					<Parse value="foo"/>
+				</ErrorBoundary>
+			</ErrorBoundary>
+		}
+		.apply_and_render();
+		expect(&html).to_contain("inner");
+		expect(&html).not().to_contain("outer");
+	}
+
+	#[test]
+	fn uncaught() {
+		let err = rsx! { <div><Parse value="foo"/></div> }
+			.build_document()
+			.unwrap_err()
+			.to_string();
+		expect(err).to_start_with(&format!(
+			"Error in component at {}:{}:",
+			file!(),
+			line!() - 7
+		));
+	}
+
+	#[test]
+	fn uncaught_all() {
+		let err = rsx! { <div><Parse value="foo"/><Parse value="bar"/></div> }
+			.build_document()
+			.unwrap_err();
+		let errors = err.downcast_ref::<RsxErrors>().unwrap();
+		expect(errors.0.len()).to_be(2);
+	}
+
+	#[test]
+	fn resumable() {
+		let mut rsx = rsx! {
+			<ErrorBoundary fallback=|_: &RsxError| rsx! { <p>oops</p> }>
+				<Parse value="foo"/>
+			</ErrorBoundary>
+		};
+		SlotsVisitor::apply(&mut rsx).unwrap();
+		let html = RsxToResumableHtml::default().map_root(&mut rsx).render();
+		expect(&html).to_contain("<p>oops</p>");
+	}
+
+	#[test]
+	fn effects() {
+		let (get, set) = signal("1");
+		let content = move || rsx! { <Parse value={get()}/> };
+		let mut rsx = rsx! {
+			<ErrorBoundary fallback=|_: &RsxError| rsx! { <p>oops</p> }>
+				<div>{content}</div>
+			</ErrorBoundary>
+		};
+		SlotsVisitor::apply(&mut rsx).unwrap();
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render())).to_contain(">1</span>");
+		set("foo");
+		expect(&DomTarget::with(|h| h.render())).to_contain("<p>oops</p>");
+		set("2");
+		expect(&DomTarget::with(|h| h.render())).to_contain(">2</span>");
+	}
+
+	#[test]
+	fn effects_uncaught() {
+		let (get, set) = signal("1");
+		let content = move || rsx! { <Parse value={get()}/> };
+		let mut rsx = rsx! { <div>{content}</div> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		set("foo");
+		expect(&DomTarget::with(|h| h.render())).to_contain(">1</span>");
+		set("2");
+		expect(&DomTarget::with(|h| h.render())).to_contain(">2</span>");
+	}
+}
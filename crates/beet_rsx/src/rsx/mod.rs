@@ -1,4 +1,14 @@
+mod bind_value;
+mod client_island;
+mod context;
+mod control_flow;
+mod error_boundary;
 mod rsx_idx;
+pub use bind_value::*;
+pub use client_island::*;
+pub use context::*;
+pub use control_flow::*;
+pub use error_boundary::*;
 mod slots_visitor;
 mod tree_location;
 mod tree_location_map;
@@ -17,7 +27,10 @@ mod rsx_visitor_fn;
 mod rusty_part;
 #[cfg(feature = "css")]
 mod scoped_style;
+#[cfg(feature = "css")]
+mod style_sheets;
 mod tree_idx;
+pub use rsx_diff::*;
 pub use rsx_root_map::*;
 pub use rsx_template_map::*;
 pub use rsx_template_node::*;
@@ -26,13 +39,19 @@ pub use rsx_visitor_fn::*;
 pub use rusty_part::*;
 #[cfg(feature = "css")]
 pub use scoped_style::*;
+#[cfg(feature = "css")]
+pub use style_sheets::*;
 pub use tree_idx::*;
 mod rsx_node;
 mod rsx_visitor;
 pub use rsx_node::*;
 pub use rsx_visitor::*;
 pub use text_block_encoder::*;
+mod props;
+pub use props::*;
 mod rsx_root;
+mod suspense;
+pub use suspense::*;
 mod text_block_encoder;
 pub use effect::*;
 pub use rsx_location::*;
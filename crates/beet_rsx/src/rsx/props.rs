@@ -0,0 +1,129 @@
+/// Props are the fields of a [`Component`](crate::prelude::Component),
+/// set by the attributes of an rsx element. This is usually implemented
+/// with `#[derive(Props)]`, which generates a builder where fields are:
+/// - **required** unless otherwise specified
+/// - **optional** if they are an `Option<T>`, the setter accepts `T`
+/// - **defaulted** with `#[prop(default)]` or `#[prop(default = expr)]`
+///
+/// Any field can accept `impl Into<T>` with `#[prop(into)]`.
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// #[derive(Props)]
+/// struct MyComponent {
+/// 	#[prop(into)]
+/// 	title: String,
+/// 	count: u32,
+/// 	subtitle: Option<String>,
+/// 	#[prop(default = 3)]
+/// 	size: u32,
+/// }
+/// impl Component for MyComponent {
+/// 	fn render(self) -> RsxRoot {
+/// 		rsx! { <h1>{self.title}{self.count}{self.size}</h1> }
+/// 	}
+/// }
+/// let node = rsx! { <MyComponent title="hello" count={3} /> };
+/// ```
+///
+/// Missing props are a compile error:
+/// ```compile_fail
+/// # use beet_rsx::as_beet::*;
+/// # #[derive(Props)]
+/// # struct MyComponent {
+/// # 	title: String,
+/// # }
+/// # impl Component for MyComponent {
+/// # 	fn render(self) -> RsxRoot { rsx! { <h1>{self.title}</h1> } }
+/// # }
+/// let node = rsx! { <MyComponent /> };
+/// ```
+///
+/// As are unknown props:
+/// ```compile_fail
+/// # use beet_rsx::as_beet::*;
+/// # #[derive(Props)]
+/// # struct MyComponent;
+/// # impl Component for MyComponent {
+/// # 	fn render(self) -> RsxRoot { rsx! { <h1>hello</h1> } }
+/// # }
+/// let node = rsx! { <MyComponent title="foo" /> };
+/// ```
+pub trait Props {
+	/// The builder with no props set
+	type Builder;
+	fn builder() -> Self::Builder;
+}
+
+/// Implemented for a [`Props::Builder`] once all required props are set.
+pub trait PropsBuilder {
+	type Component;
+	fn build(self) -> Self::Component;
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[derive(Props)]
+	struct MyComponent {
+		#[prop(into)]
+		title: String,
+		count: u32,
+		subtitle: Option<String>,
+		#[prop(default)]
+		active: bool,
+		#[prop(default = 3, into)]
+		size: u32,
+	}
+	impl Component for MyComponent {
+		fn render(self) -> RsxRoot {
+			let subtitle = self.subtitle.unwrap_or_else(|| "none".into());
+			let active = self.active.to_string();
+			let size = self.size.to_string();
+			rsx! { <div>{self.title}-{self.count}-{subtitle}-{active}-{size}</div> }
+		}
+	}
+
+	#[derive(Props)]
+	struct Generic<T: 'static + Clone + ToString> {
+		value: T,
+	}
+	impl<T: 'static + Clone + ToString> Component for Generic<T> {
+		fn render(self) -> RsxRoot {
+			let value = self.value.to_string();
+			rsx! { <span>{value}</span> }
+		}
+	}
+
+	#[test]
+	fn builder() {
+		let props = MyComponent::builder()
+			.count(1)
+			.title("foo")
+			.subtitle("bar".into())
+			.build();
+		expect(props.title).to_be("foo");
+		expect(props.count).to_be(1);
+		expect(props.subtitle).to_be(Some("bar".to_string()));
+		expect(props.active).to_be(false);
+		expect(props.size).to_be(3);
+	}
+
+	#[test]
+	fn rsx() {
+		expect(RsxToHtml::render_body(
+			&rsx! { <MyComponent title="foo" count={2} /> },
+		))
+		.to_be("<div data-beet-rsx-idx=\"1\">foo-2-none-false-3</div>");
+		let subtitle = "bar".to_string();
+		expect(RsxToHtml::render_body(&rsx! {
+			<MyComponent title="foo" count=2 active size=4u8 {subtitle} />
+		}))
+		.to_be("<div data-beet-rsx-idx=\"1\">foo-2-bar-true-4</div>");
+		expect(RsxToHtml::render_body(&rsx! { <Generic value=3 /> }))
+			.to_be("<span data-beet-rsx-idx=\"1\">3</span>");
+	}
+}
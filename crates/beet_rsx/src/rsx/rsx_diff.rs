@@ -1,8 +1,521 @@
-// use crate::prelude::*;
+use crate::prelude::*;
 
 
-// todo!
-// we'll probably need a vec<usize> path pattern instead of rsx idx
-// check out leptos diff for inspiration
+/// A single change required to turn one rendered [RsxNode] into another.
+///
+/// Each patch has a `path` of *uncollapsed* child indices, starting
+/// from the element containing the diffed nodes. For a block this is the
+/// parent element of the block, see [`RsxPatch::with_offset`].
+///
+/// Patches must be applied in order, the path of each patch
+/// is only valid after the previous patches have been applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RsxPatch {
+	/// Insert a node so that it will be located at the path
+	Insert { path: Vec<u32>, node: HtmlNode },
+	/// Remove the node at the path
+	Remove { path: Vec<u32> },
+	/// Move the node at the path to the `to` index of the same parent
+	Move { path: Vec<u32>, to: u32 },
+	/// Set the value of the text node at the path
+	SetText { path: Vec<u32>, value: String },
+	/// Set an attribute of the element at the path
+	SetAttribute {
+		path: Vec<u32>,
+		key: String,
+		value: Option<String>,
+	},
+	/// Remove an attribute from the element at the path
+	RemoveAttribute { path: Vec<u32>, key: String },
+}
 
-// pub struct RsxDiff;
+impl RsxPatch {
+	/// The path of the node this patch applies to
+	pub fn path(&self) -> &[u32] {
+		match self {
+			RsxPatch::Insert { path, .. } => path,
+			RsxPatch::Remove { path } => path,
+			RsxPatch::Move { path, .. } => path,
+			RsxPatch::SetText { path, .. } => path,
+			RsxPatch::SetAttribute { path, .. } => path,
+			RsxPatch::RemoveAttribute { path, .. } => path,
+		}
+	}
+
+	/// Offset the first index of the path, used when the diffed nodes
+	/// are not the first children of their parent,
+	/// ie a block at [`TreeLocation::child_idx`].
+	pub fn with_offset(mut self, offset: u32) -> Self {
+		let is_root = self.path().len() == 1;
+		match &mut self {
+			RsxPatch::Insert { path, .. }
+			| RsxPatch::Remove { path }
+			| RsxPatch::SetText { path, .. }
+			| RsxPatch::SetAttribute { path, .. }
+			| RsxPatch::RemoveAttribute { path, .. } => {
+				path[0] += offset;
+			}
+			RsxPatch::Move { path, to } => {
+				path[0] += offset;
+				if is_root {
+					*to += offset;
+				}
+			}
+		}
+		self
+	}
+}
+
+/// Diff two [RsxNode] trees, producing the [RsxPatch] list required
+/// to update the html rendered by the first into the html rendered
+/// by the second.
+///
+/// Transparent nodes like fragments, blocks and components are flattened
+/// so that only html nodes are compared. Siblings are matched by
+/// their `key` attribute if present, otherwise by their [RsxIdx],
+/// kind and tag, so that conditional and reordered nodes are moved
+/// instead of rebuilt.
+///
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let patches = RsxDiff::diff(
+/// 	&rsx! { <div class="a">hello</div> },
+/// 	&rsx! { <div class="b">hello</div> },
+/// );
+/// assert_eq!(patches, vec![RsxPatch::SetAttribute {
+/// 	path: vec![0],
+/// 	key: "class".into(),
+/// 	value: Some("b".into()),
+/// }]);
+/// ```
+#[derive(Debug, Default)]
+pub struct RsxDiff {
+	patches: Vec<RsxPatch>,
+}
+
+/// Identifies a sibling across two renders
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DiffKey<'a> {
+	/// An explicit `key` attribute
+	Key(&'a str),
+	/// The nth node with this kind, tag and idx
+	Node {
+		kind: &'a str,
+		tag: &'a str,
+		idx: RsxIdx,
+		nth: usize,
+	},
+}
+
+impl RsxDiff {
+	pub fn diff(
+		old: impl AsRef<RsxNode>,
+		new: impl AsRef<RsxNode>,
+	) -> Vec<RsxPatch> {
+		let mut this = Self::default();
+		this.diff_children(&[], &flatten(old.as_ref()), &flatten(new.as_ref()));
+		this.patches
+	}
+
+	fn diff_children(
+		&mut self,
+		path: &[u32],
+		old: &[&RsxNode],
+		new: &[&RsxNode],
+	) {
+		let old_keys = keys(old);
+		let new_keys = keys(new);
+		let child_path = |idx: usize| {
+			let mut path = path.to_vec();
+			path.push(idx as u32);
+			path
+		};
+
+		// the current order of the children, none for inserted nodes
+		let mut live: Vec<Option<usize>> = (0..old.len()).map(Some).collect();
+
+		// remove back to front so indices remain valid
+		for idx in (0..old.len()).rev() {
+			if !new_keys.contains(&old_keys[idx]) {
+				self.patches.push(RsxPatch::Remove {
+					path: child_path(idx),
+				});
+				live.remove(idx);
+			}
+		}
+
+		for (idx, new_key) in new_keys.iter().enumerate() {
+			let current = live.iter().position(|old_idx| {
+				old_idx.map(|old_idx| &old_keys[old_idx]) == Some(new_key)
+			});
+			match current {
+				Some(current) => {
+					if current != idx {
+						self.patches.push(RsxPatch::Move {
+							path: child_path(current),
+							to: idx as u32,
+						});
+						let item = live.remove(current);
+						live.insert(idx, item);
+					}
+					let old_idx = live[idx].expect("inserted nodes are unique");
+					self.diff_node(&child_path(idx), old[old_idx], new[idx]);
+				}
+				None => {
+					self.patches.push(RsxPatch::Insert {
+						path: child_path(idx),
+						node: render(new[idx]),
+					});
+					live.insert(idx, None);
+				}
+			}
+		}
+	}
+
+	/// diff two nodes at the same location
+	fn diff_node(&mut self, path: &[u32], old: &RsxNode, new: &RsxNode) {
+		match (old, new) {
+			(
+				RsxNode::Text { value: old, .. },
+				RsxNode::Text { value: new, .. },
+			) => {
+				if old != new {
+					self.patches.push(RsxPatch::SetText {
+						path: path.to_vec(),
+						value: new.clone(),
+					});
+				}
+			}
+			(RsxNode::Element(old), RsxNode::Element(new))
+				if old.tag == new.tag
+					&& old.self_closing == new.self_closing =>
+			{
+				self.diff_attributes(path, old, new);
+				self.diff_children(
+					path,
+					&flatten(&old.children),
+					&flatten(&new.children),
+				);
+			}
+			(
+				RsxNode::Comment { value: old, .. },
+				RsxNode::Comment { value: new, .. },
+			)
+			| (
+				RsxNode::RawHtml { value: old, .. },
+				RsxNode::RawHtml { value: new, .. },
+			) if old == new => {}
+			(RsxNode::Doctype { .. }, RsxNode::Doctype { .. }) => {}
+			_ => {
+				self.patches.push(RsxPatch::Remove {
+					path: path.to_vec(),
+				});
+				self.patches.push(RsxPatch::Insert {
+					path: path.to_vec(),
+					node: render(new),
+				});
+			}
+		}
+	}
+
+	fn diff_attributes(
+		&mut self,
+		path: &[u32],
+		old: &RsxElement,
+		new: &RsxElement,
+	) {
+		let old = attribute_pairs(&old.attributes);
+		let new = attribute_pairs(&new.attributes);
+		for (key, value) in new.iter() {
+			if !old.contains(&(*key, *value)) {
+				self.patches.push(RsxPatch::SetAttribute {
+					path: path.to_vec(),
+					key: key.to_string(),
+					value: value.map(|v| v.to_string()),
+				});
+			}
+		}
+		for (key, _) in old.iter() {
+			if !new.iter().any(|(new_key, _)| new_key == key) {
+				self.patches.push(RsxPatch::RemoveAttribute {
+					path: path.to_vec(),
+					key: key.to_string(),
+				});
+			}
+		}
+	}
+}
+
+/// collect the html nodes, skipping fragments, blocks and components
+fn flatten(node: &RsxNode) -> Vec<&RsxNode> {
+	fn walk<'a>(node: &'a RsxNode, out: &mut Vec<&'a RsxNode>) {
+		match node {
+			RsxNode::Fragment { nodes, .. } => {
+				for node in nodes {
+					walk(node, out);
+				}
+			}
+			RsxNode::Block(block) => walk(&block.initial.node, out),
+			RsxNode::Component(component) => walk(&component.root.node, out),
+			_ => out.push(node),
+		}
+	}
+	let mut out = Vec::new();
+	walk(node, &mut out);
+	out
+}
+
+/// mutable version of [`flatten`]
+fn flatten_mut(node: &mut RsxNode) -> Vec<&mut RsxNode> {
+	fn walk<'a>(node: &'a mut RsxNode, out: &mut Vec<&'a mut RsxNode>) {
+		match node {
+			RsxNode::Fragment { nodes, .. } => {
+				for node in nodes {
+					walk(node, out);
+				}
+			}
+			RsxNode::Block(block) => walk(&mut block.initial.node, out),
+			RsxNode::Component(component) => {
+				walk(&mut component.root.node, out)
+			}
+			_ => out.push(node),
+		}
+	}
+	let mut out = Vec::new();
+	walk(node, &mut out);
+	out
+}
+
+/// The number of html nodes rendered by this node,
+/// ie the number of siblings rendered by a block.
+pub(crate) fn html_len(node: &RsxNode) -> u32 { flatten(node).len() as u32 }
+
+/// Find the node at the path of an [RsxPatch] in the tree
+/// that was diffed against, ie the node of an insert.
+pub(crate) fn node_at_path_mut<'a>(
+	node: &'a mut RsxNode,
+	path: &[u32],
+) -> Option<&'a mut RsxNode> {
+	let (idx, parent_path) = path.split_last()?;
+	let mut node = node;
+	for idx in parent_path {
+		match flatten_mut(node).into_iter().nth(*idx as usize)? {
+			RsxNode::Element(el) => node = &mut el.children,
+			_ => return None,
+		}
+	}
+	flatten_mut(node).into_iter().nth(*idx as usize)
+}
+
+fn keys<'a>(nodes: &[&'a RsxNode]) -> Vec<DiffKey<'a>> {
+	let mut keys: Vec<DiffKey<'a>> = Vec::with_capacity(nodes.len());
+	for node in nodes.iter().copied() {
+		let (tag, idx) = match node {
+			RsxNode::Element(el) => {
+				if let Some(key) = key_attribute(el) {
+					keys.push(DiffKey::Key(key));
+					continue;
+				}
+				(el.tag.as_str(), el.idx)
+			}
+			RsxNode::Text { idx, .. }
+			| RsxNode::RawHtml { idx, .. }
+			| RsxNode::Comment { idx, .. }
+			| RsxNode::Doctype { idx } => ("", *idx),
+			_ => ("", RsxIdx::default()),
+		};
+		let kind = node.as_ref();
+		let nth = keys
+			.iter()
+			.filter(|key| {
+				matches!(key, DiffKey::Node { kind: k, tag: t, idx: i, .. }
+					if *k == kind && *t == tag && *i == idx)
+			})
+			.count();
+		keys.push(DiffKey::Node {
+			kind,
+			tag,
+			idx,
+			nth,
+		});
+	}
+	keys
+}
+
+fn key_attribute(el: &RsxElement) -> Option<&str> {
+	el.attributes.iter().find_map(|attr| match attr {
+		RsxAttribute::KeyValue { key, value } if key == "key" => {
+			Some(value.as_str())
+		}
+		RsxAttribute::BlockValue { key, initial, .. } if key == "key" => {
+			Some(initial.as_str())
+		}
+		_ => None,
+	})
+}
+
+/// flatten attributes into key value pairs, event handlers and
+/// the `key` of [For] items are excluded as they are not stored in the html.
+pub(crate) fn attribute_pairs(
+	attrs: &[RsxAttribute],
+) -> Vec<(&str, Option<&str>)> {
+	let mut out = Vec::new();
+	for attr in attrs {
+		match attr {
+			RsxAttribute::KeyValue { key, .. }
+			| RsxAttribute::BlockValue { key, .. }
+				if key == "key" => {}
+			RsxAttribute::Key { key } => out.push((key.as_str(), None)),
+			RsxAttribute::KeyValue { key, value } => {
+				out.push((key.as_str(), Some(value.as_str())))
+			}
+			RsxAttribute::BlockValue { key, .. } if key.starts_with("on") => {}
+			RsxAttribute::BlockValue { key, initial, .. } => {
+				out.push((key.as_str(), Some(initial.as_str())))
+			}
+			RsxAttribute::Block { initial, .. } => {
+				out.extend(attribute_pairs(initial))
+			}
+		}
+	}
+	out
+}
+
+/// owned version of [`attribute_pairs`], used by runtimes to
+/// track the previous value of attribute blocks.
+pub(crate) fn owned_attribute_pairs(
+	attrs: &[RsxAttribute],
+) -> Vec<(String, Option<String>)> {
+	attribute_pairs(attrs)
+		.into_iter()
+		.map(|(key, value)| (key.to_string(), value.map(|v| v.to_string())))
+		.collect()
+}
+
+/// render a flattened node, which will always be a single html node
+fn render(node: &RsxNode) -> HtmlNode {
+	let mut mapper = RsxToHtml::default();
+	mapper.no_beet_attributes = true;
+	mapper
+		.map_node(node)
+		.pop()
+		.unwrap_or_else(|| HtmlNode::Text(String::new()))
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[test]
+	fn unchanged() {
+		expect(RsxDiff::diff(
+			&rsx! { <div class="a">hello<br/></div> },
+			&rsx! { <div class="a">hello<br/></div> },
+		))
+		.to_be(vec![]);
+	}
+
+	#[test]
+	fn text() {
+		let node = |value: &'static str| rsx! { <div>{value}</div> };
+		expect(RsxDiff::diff(node("foo"), node("bar"))).to_be(vec![
+			RsxPatch::SetText {
+				path: vec![0, 0],
+				value: "bar".into(),
+			},
+		]);
+	}
+
+	#[test]
+	fn attributes() {
+		expect(RsxDiff::diff(
+			&rsx! { <div class="a" hidden/> },
+			&rsx! { <div class="b" id="c"/> },
+		))
+		.to_be(vec![
+			RsxPatch::SetAttribute {
+				path: vec![0],
+				key: "class".into(),
+				value: Some("b".into()),
+			},
+			RsxPatch::SetAttribute {
+				path: vec![0],
+				key: "id".into(),
+				value: Some("c".into()),
+			},
+			RsxPatch::RemoveAttribute {
+				path: vec![0],
+				key: "hidden".into(),
+			},
+		]);
+	}
+
+	#[test]
+	fn insert_remove() {
+		let node = |show: bool| {
+			let inner = move || {
+				if show {
+					rsx! { <b>bold</b> }
+				} else {
+					rsx! { <i>italic</i> }
+				}
+			};
+			rsx! { <div>{inner}</div> }
+		};
+		expect(RsxDiff::diff(node(true), node(false))).to_be(vec![
+			RsxPatch::Remove { path: vec![0, 0] },
+			RsxPatch::Insert {
+				path: vec![0, 0],
+				node: HtmlNode::Element(HtmlElementNode {
+					tag: "i".into(),
+					namespace: Default::default(),
+					self_closing: false,
+					attributes: vec![],
+					children: vec![HtmlNode::Text("italic".into())],
+				}),
+			},
+		]);
+	}
+
+	#[test]
+	fn keyed() {
+		let list = |items: Vec<&'static str>| {
+			let items = move || {
+				items
+					.iter()
+					.map(|item| {
+						let item = *item;
+						rsx! { <li key=item>{item}</li> }
+					})
+					.collect::<Vec<_>>()
+			};
+			rsx! { <ul>{items}</ul> }
+		};
+		let patches =
+			RsxDiff::diff(list(vec!["a", "b", "c"]), list(vec!["c", "a"]));
+		expect(patches).to_be(vec![
+			RsxPatch::Remove { path: vec![0, 1] },
+			RsxPatch::Move {
+				path: vec![0, 1],
+				to: 0,
+			},
+		]);
+	}
+
+	#[test]
+	fn offset() {
+		expect(
+			RsxPatch::Move {
+				path: vec![1],
+				to: 0,
+			}
+			.with_offset(2),
+		)
+		.to_be(RsxPatch::Move {
+			path: vec![3],
+			to: 2,
+		});
+	}
+}
@@ -19,6 +19,11 @@ pub enum RsxNode {
 		idx: RsxIdx,
 		value: String,
 	},
+	/// trusted html that will not be escaped, see [RawHtml]
+	RawHtml {
+		idx: RsxIdx,
+		value: String,
+	},
 	/// a rust block that returns text
 	Block(RsxBlock),
 	/// A transparent node that simply contains children
@@ -71,6 +76,7 @@ impl RsxNode {
 			RsxNode::Doctype { .. }
 			| RsxNode::Comment { .. }
 			| RsxNode::Text { .. }
+			| RsxNode::RawHtml { .. }
 			| RsxNode::Element(_) => true,
 			_ => false,
 		}
@@ -80,15 +86,52 @@ impl RsxNode {
 	/// # Panics
 	/// If the register function fails
 	pub fn register_effects(&mut self) {
+		self.register_effects_where(|_| true);
+	}
+
+	/// Register the effects of nodes at locations matching the predicate,
+	/// see [RsxNode::register_island_effects]
+	pub fn register_effects_where(
+		&mut self,
+		predicate: impl Fn(TreeLocation) -> bool,
+	) {
+		let mut num_nodes = 0;
+		self.visit_effects(TreeIdx::default(), |loc| {
+			num_nodes = num_nodes.max(*loc.tree_idx + 1);
+			predicate(loc)
+		});
+		// inserted nodes must not reuse the tree indices of this tree
+		DomTarget::reserve_tree_idx(num_nodes);
+		// suspended content is not awaited by interactive trees
+		Suspense::evict(self);
+	}
+
+	/// Register the effects of a node rendered with
+	/// [RsxToHtml::with_tree_idx], ie a node inserted by a block.
+	pub fn register_effects_from(&mut self, start: TreeIdx) {
+		self.visit_effects(start, |_| true);
+	}
+
+	fn visit_effects(
+		&mut self,
+		start: TreeIdx,
+		mut predicate: impl FnMut(TreeLocation) -> bool,
+	) {
 		TreeLocationVisitor::visit_mut(self, |loc, node| {
-			// println!(
-			// 	"registering effect at loc: {:?}:{:?}",
-			// 	loc,
-			// 	node.discriminant()
-			// );
+			let loc = TreeLocation::new(
+				*loc.tree_idx + *start,
+				*loc.parent_idx + *start,
+				loc.child_idx,
+			);
+			if !predicate(loc) {
+				return;
+			}
 
 			match node {
-				RsxNode::Block(RsxBlock { effect, .. }) => {
+				RsxNode::Block(RsxBlock {
+					effect, initial, ..
+				}) => {
+					DomTarget::register_block(loc, initial);
 					effect.take().register(loc).unwrap();
 				}
 				RsxNode::Element(e) => {
@@ -169,6 +212,9 @@ pub struct RsxComponent {
 	pub tracker: RustyTracker,
 	/// the root returned by [Component::render]
 	pub root: Box<RsxRoot>,
+	/// Set by `client:load` etc, rendering this component as
+	/// an island, see [ClientIsland]
+	pub client_directive: Option<ClientDirective>,
 	// /// the children passed in by this component's parent:
 	// ///
 	// /// `rsx! { <MyComponent>slot_children</MyComponent> }`
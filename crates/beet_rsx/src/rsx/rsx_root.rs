@@ -21,27 +21,49 @@ pub struct RsxRoot {
 
 impl RsxRoot {
 	/// This is the method used by routers,
-	/// applies styles and slots, returning an HtmlDocument.
+	/// applies styles, slots and error boundaries, returning an HtmlDocument
+	/// with any [Head] elements hoisted.
+	///
+	/// Errors not caught by an [ErrorBoundary] are returned as [RsxErrors].
+	#[cfg_attr(not(feature = "css"), allow(unused_mut))]
 	pub fn build_document(mut self) -> Result<HtmlDocument> {
 		#[cfg(feature = "css")]
 		ScopedStyle::default().apply(&mut self)?;
+		self.build_unstyled_document()
+	}
+
+	/// [Self::build_document] without applying styles, used when
+	/// styles are applied separately, ie [StyleSheets::build_document].
+	pub(crate) fn build_unstyled_document(mut self) -> Result<HtmlDocument> {
 		SlotsVisitor::apply(&mut self)?;
+		ErrorBoundary::resolve(&mut self).into_result()?;
 		let html = RsxToHtml::default().map_node(&self);
-		let doc = html.into_document();
+		Suspense::evict(&self);
+		#[cfg(feature = "serde")]
+		ResumableState::clear_tracked();
+		let mut doc = html.into_document();
+		doc.hoist_head();
 		Ok(doc)
 	}
 	/// convenience method usually for testing:
 	/// - [ScopedStyle::apply]
 	/// - [SlotsVisitor::apply]
+	/// - [ErrorBoundary::resolve]
 	/// - [RsxToHtml::map_node]
 	/// - [HtmlNode::render]
 	///
 	/// ## Panics
-	/// If the slots cannot be applied.
+	/// If the slots cannot be applied or there are uncaught errors.
 	pub fn apply_and_render(mut self) -> String {
 		#[cfg(feature = "css")]
 		ScopedStyle::default().apply(&mut self).unwrap();
 		SlotsVisitor::apply(&mut self).unwrap();
+		if let Err(errors) = ErrorBoundary::resolve(&mut self).into_result() {
+			panic!("{errors}");
+		}
+		Suspense::evict(&self);
+		#[cfg(feature = "serde")]
+		ResumableState::clear_tracked();
 		RsxToHtml::render_body(&self)
 	}
 }
@@ -99,3 +121,18 @@ impl<T: FnOnce() -> U, U: IntoRsxRoot<M2>, M2> IntoRsxRoot<(M2, FuncIntoRsx)>
 {
 	fn into_root(self) -> RsxRoot { self().into_root() }
 }
+pub struct VecIntoRsx;
+impl<T: IntoRsxRoot<M>, M> IntoRsxRoot<(M, VecIntoRsx)> for Vec<T> {
+	fn into_root(self) -> RsxRoot {
+		RsxRoot {
+			location: RsxMacroLocation::default(),
+			node: RsxNode::Fragment {
+				idx: RsxIdx::default(),
+				nodes: self
+					.into_iter()
+					.map(|item| item.into_root().node)
+					.collect(),
+			},
+		}
+	}
+}
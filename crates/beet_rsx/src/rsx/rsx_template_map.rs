@@ -76,6 +76,7 @@ mod test {
 	use crate::as_beet::*;
 	use sweet::prelude::*;
 
+	#[derive(Props)]
 	struct MyComponent {
 		value: usize,
 	}
@@ -21,6 +21,10 @@ pub enum RsxTemplateNode {
 		idx: RsxIdx,
 		value: String,
 	},
+	RawHtml {
+		idx: RsxIdx,
+		value: String,
+	},
 	Fragment {
 		idx: RsxIdx,
 		items: Vec<Self>,
@@ -45,6 +49,8 @@ pub enum RsxTemplateNode {
 		/// the hydrated part has the juicy details
 		tracker: RustyTracker,
 		tag: String,
+		/// mapped from [RsxComponent::client_directive]
+		client_directive: Option<ClientDirective>,
 		/// mapped from [RsxComponent::slot_children]
 		slot_children: Box<Self>,
 	},
@@ -101,6 +107,7 @@ impl RsxTemplateNode {
 			RsxTemplateNode::Doctype { idx } => *idx,
 			RsxTemplateNode::Comment { idx, .. } => *idx,
 			RsxTemplateNode::Text { idx, .. } => *idx,
+			RsxTemplateNode::RawHtml { idx, .. } => *idx,
 			RsxTemplateNode::Fragment { idx, .. } => *idx,
 			RsxTemplateNode::RustBlock { idx, .. } => *idx,
 			RsxTemplateNode::Element { idx, .. } => *idx,
@@ -123,10 +130,11 @@ impl RsxTemplateNode {
 				tracker,
 				// ignore root, its a seperate tree
 				root: _,
-
+				client_directive,
 				slot_children,
 			}) => Ok(Self::Component {
 				idx: *idx,
+				client_directive: *client_directive,
 				// location: node.location.clone(),
 				// node: Box::new(Self::from_rsx_node(node)?),
 				slot_children: Box::new(Self::from_rsx_node(slot_children)?),
@@ -162,6 +170,10 @@ impl RsxTemplateNode {
 				idx: *idx,
 				value: value.clone(),
 			}),
+			RsxNode::RawHtml { idx, value } => Ok(Self::RawHtml {
+				idx: *idx,
+				value: value.clone(),
+			}),
 			RsxNode::Comment { idx, value } => Ok(Self::Comment {
 				idx: *idx,
 				value: value.clone(),
@@ -170,6 +182,76 @@ impl RsxTemplateNode {
 		}
 	}
 
+	/// Create a template from parsed html, see [HtmlParser].
+	/// Indices are assigned depth first, and like the rsx macros
+	/// a single child is not wrapped in a fragment.
+	/// Html has no rusty parts so the template can be converted
+	/// to an [RsxNode] with an empty rusty map.
+	pub fn from_html_nodes(nodes: &[HtmlNode]) -> Self {
+		Self::from_html_inner(nodes, &mut 0)
+	}
+
+	fn from_html_inner(nodes: &[HtmlNode], idx_incr: &mut RsxIdx) -> Self {
+		let mut next_idx = || {
+			let idx = *idx_incr;
+			*idx_incr += 1;
+			idx
+		};
+		if nodes.len() != 1 {
+			let idx = next_idx();
+			return Self::Fragment {
+				idx,
+				items: nodes
+					.iter()
+					.map(|node| {
+						Self::from_html_inner(
+							std::slice::from_ref(node),
+							idx_incr,
+						)
+					})
+					.collect(),
+			};
+		}
+		let idx = next_idx();
+		match &nodes[0] {
+			HtmlNode::Doctype => Self::Doctype { idx },
+			HtmlNode::Comment(value) => Self::Comment {
+				idx,
+				value: value.clone(),
+			},
+			HtmlNode::Text(value) => Self::Text {
+				idx,
+				value: value.clone(),
+			},
+			HtmlNode::RawHtml(value) => Self::RawHtml {
+				idx,
+				value: value.clone(),
+			},
+			HtmlNode::Element(el) => Self::Element {
+				idx,
+				tag: el.tag.clone(),
+				self_closing: el.self_closing,
+				attributes: el
+					.attributes
+					.iter()
+					.map(|attr| match &attr.value {
+						Some(value) => RsxTemplateAttribute::KeyValue {
+							key: attr.key.clone(),
+							value: value.clone(),
+						},
+						None => RsxTemplateAttribute::Key {
+							key: attr.key.clone(),
+						},
+					})
+					.collect(),
+				children: Box::new(Self::from_html_inner(
+					&el.children,
+					idx_incr,
+				)),
+			},
+		}
+	}
+
 	/// drain the effect map into an RsxNode
 	/// We need the [`RsxTemplateMap`] to apply the template
 	/// for nested components
@@ -183,6 +265,9 @@ impl RsxTemplateNode {
 			RsxTemplateNode::Text { value, idx } => {
 				Ok(RsxNode::Text { idx, value })
 			}
+			RsxTemplateNode::RawHtml { value, idx } => {
+				Ok(RsxNode::RawHtml { idx, value })
+			}
 			RsxTemplateNode::Comment { value, idx } => {
 				Ok(RsxNode::Comment { idx, value })
 			}
@@ -197,6 +282,7 @@ impl RsxTemplateNode {
 			RsxTemplateNode::Component {
 				tracker,
 				tag,
+				client_directive,
 				slot_children,
 				idx,
 			} => {
@@ -223,6 +309,7 @@ impl RsxTemplateNode {
 					tag: tag.clone(),
 					tracker,
 					root: Box::new(root),
+					client_directive,
 					slot_children: Box::new(
 						slot_children.into_rsx_node(template_map, rusty_map)?,
 					),
@@ -413,6 +500,23 @@ mod test {
 	use crate::prelude::*;
 	use sweet::prelude::*;
 
+	#[test]
+	fn from_html() {
+		let html = "<div class=\"foo\" hidden><p>hello</p><br/>world</div>";
+		let nodes = HtmlParser::default().parse(html).unwrap();
+		let template = RsxTemplateNode::from_html_nodes(&nodes);
+		let mut indices = Vec::new();
+		template.visit(|node| indices.push(node.rsx_idx()));
+		expect(indices).to_be(vec![0, 1, 2, 3, 4, 5, 6]);
+		let node = template
+			.into_rsx_node(
+				&RsxTemplateMap(Default::default()),
+				&mut Default::default(),
+			)
+			.unwrap();
+		expect(RsxToHtml::default().map_node(&node).render()).to_be(html);
+	}
+
 	#[test]
 	fn simple() {
 		let tracker = RustyTracker::new(0, 15046980652419922415);
@@ -477,6 +581,7 @@ mod test {
 							idx: 4,
 							tracker: component_tracker,
 							tag: "MyComponent".to_string(),
+							client_directive: None,
 							slot_children: Box::new(RsxTemplateNode::Element {
 								idx: 5,
 								tag: "div".to_string(),
@@ -105,6 +105,7 @@ pub trait RsxVisitor {
 	fn visit_doctype(&mut self, idx: RsxIdx) {}
 	fn visit_comment(&mut self, idx: RsxIdx, comment: &str) {}
 	fn visit_text(&mut self, idx: RsxIdx, text: &str) {}
+	fn visit_raw_html(&mut self, idx: RsxIdx, html: &str) {}
 	fn visit_block(&mut self, block: &RsxBlock) {}
 	fn visit_component(&mut self, component: &RsxComponent) {}
 	fn visit_element(&mut self, element: &RsxElement) {}
@@ -119,6 +120,7 @@ pub trait RsxVisitor {
 			}
 			RsxNode::Comment { idx, value } => self.visit_comment(*idx, value),
 			RsxNode::Text { idx, value } => self.visit_text(*idx, value),
+			RsxNode::RawHtml { idx, value } => self.visit_raw_html(*idx, value),
 			RsxNode::Block(b) => {
 				self.visit_block(b);
 				if !self.ignore_block_node_initial() {
@@ -180,6 +182,7 @@ pub trait RsxVisitorMut {
 	fn visit_doctype(&mut self, idx: RsxIdx) {}
 	fn visit_comment(&mut self, idx: RsxIdx, comment: &mut str) {}
 	fn visit_text(&mut self, idx: RsxIdx, text: &mut str) {}
+	fn visit_raw_html(&mut self, idx: RsxIdx, html: &mut str) {}
 	fn visit_block(&mut self, block: &mut RsxBlock) {}
 	fn visit_component(&mut self, component: &mut RsxComponent) {}
 	fn visit_element(&mut self, element: &mut RsxElement) {}
@@ -194,6 +197,7 @@ pub trait RsxVisitorMut {
 			}
 			RsxNode::Comment { idx, value } => self.visit_comment(*idx, value),
 			RsxNode::Text { idx, value } => self.visit_text(*idx, value),
+			RsxNode::RawHtml { idx, value } => self.visit_raw_html(*idx, value),
 			RsxNode::Fragment { nodes, .. } => {
 				for child in nodes {
 					self.walk_node(child);
@@ -241,6 +245,7 @@ mod test {
 	use sweet::prelude::*;
 
 
+	#[derive(Props)]
 	struct Child;
 	impl Component for Child {
 		fn render(self) -> RsxRoot {
@@ -16,20 +16,35 @@ use parcel_selectors::attr::ParsedCaseSensitivity;
 /// - In release mode the css will be minified
 /// - Scope rules:
 /// 	- `<style scope:global/>` will not be scoped at all
+/// 	- `<style scope:cascade/>` also applies to slot children, excluding nested components
 ///
+/// When created with [ScopedStyle::extract] the styles are removed from
+/// the tree and collected, see [StyleSheets]. Styles with a `critical`
+/// attribute, ie `<style critical>`, are left inline.
 pub struct ScopedStyle {
 	/// the attribute to use as a selector for the component,
 	/// defaults to "data-styleid"
 	attr: String,
+	/// the attribute to use as a selector for cascading styles,
+	/// defaults to "data-styleid-cascade"
+	cascade_attr: String,
 	/// an index used to track the current component being styled
 	idx: usize,
+	/// remove non-critical styles from the tree, using a hash of the
+	/// styles as the id so that it is consistent between documents
+	extract: bool,
+	/// the css of removed styles
+	extracted: Vec<String>,
 }
 
 impl Default for ScopedStyle {
 	fn default() -> Self {
 		ScopedStyle {
 			attr: "data-styleid".to_string(),
+			cascade_attr: "data-styleid-cascade".to_string(),
 			idx: 0,
+			extract: false,
+			extracted: Vec::new(),
 		}
 	}
 }
@@ -39,24 +54,43 @@ enum Scope {
 	#[default]
 	Component,
 	Global,
-	// Cascade (eargerly apply slots?)
+	/// Applied to the component and its slot children
+	Cascade,
 }
 impl Scope {
 	pub fn from_element(el: &mut RsxElement) -> Self {
-		if el.contains_attr_key("scope:global") {
-			el.attributes.retain(|attr| match attr {
-				RsxAttribute::Key { key } => key != "scope:global",
-				_ => true,
-			});
-
+		let scope = if el.contains_attr_key("scope:global") {
 			Scope::Global
+		} else if el.contains_attr_key("scope:cascade") {
+			Scope::Cascade
 		} else {
 			Scope::Component
-		}
+		};
+		el.attributes.retain(|attr| match attr {
+			RsxAttribute::Key { key } => {
+				key != "scope:global" && key != "scope:cascade"
+			}
+			_ => true,
+		});
+		scope
 	}
 }
 
 impl ScopedStyle {
+	/// Remove styles from the tree, they can be taken
+	/// with [ScopedStyle::take_extracted].
+	pub fn extract() -> Self {
+		Self {
+			extract: true,
+			..Default::default()
+		}
+	}
+
+	/// Take the deduplicated css of all extracted styles
+	pub fn take_extracted(&mut self) -> Vec<String> {
+		std::mem::take(&mut self.extracted)
+	}
+
 	/// Applies scoped style to:
 	/// 1. root node
 	/// 2. all component nodes
@@ -71,8 +105,16 @@ impl ScopedStyle {
 		// visit all components
 		VisitRsxComponentMut::walk(&mut root.node, |component| {
 			// 2. apply to component node
-			if let Err(err) = self.apply_inner(&mut component.root) {
-				parse_err = Err(err);
+			match self.apply_inner(&mut component.root) {
+				Ok(Some(cascade_id)) => {
+					self.tag_elements(
+						&mut component.slot_children,
+						&cascade_id,
+						true,
+					);
+				}
+				Ok(None) => {}
+				Err(err) => parse_err = Err(err),
 			};
 			// 3. apply to component slot children
 			if let Err(err) = self.apply_inner(&mut component.slot_children) {
@@ -82,57 +124,158 @@ impl ScopedStyle {
 		parse_err
 	}
 
-	/// 1. apply the idx to all style bodies
+	/// 1. apply the id to all style bodies
 	/// 2. if contains style, apply tag to all elements in the component
-	fn apply_inner(&mut self, node: &mut RsxNode) -> ParseResult<()> {
+	///
+	/// Returns the id if any cascading styles were found
+	fn apply_inner(
+		&mut self,
+		node: &mut RsxNode,
+	) -> ParseResult<Option<String>> {
 		let mut parse_err = Ok(());
 
 		let opts = VisitRsxOptions::ignore_component();
 		let mut component_scope_found = false;
+		let mut cascade_scope_found = false;
+		let id = self.style_id(node);
+		let mut extracted = Vec::new();
 
 		// 1. apply to style bodies
-		VisitRsxElementMut::walk_with_opts(node, opts.clone(), |el| {
-			if el.tag == "style" {
-				let scope = Scope::from_element(el);
-				if scope == Scope::Component {
-					component_scope_found = true;
+		VisitRsxNodeMut::walk_with_opts(node, opts.clone(), |node| {
+			let RsxNode::Element(el) = node else {
+				return;
+			};
+			if el.tag != "style" {
+				return;
+			}
+			let scope = Scope::from_element(el);
+			match scope {
+				Scope::Component => component_scope_found = true,
+				Scope::Cascade => cascade_scope_found = true,
+				Scope::Global => {}
+			}
+			// currently only recurse top level style children, we could create another
+			// visitor to go deeper if we start supporting style body components
+			if let RsxNode::Text { value, .. } = &mut *el.children {
+				if let Err(err) = self.apply_styles(value, scope, &id) {
+					parse_err = Err(err);
 				}
-				// currently only recurse top level style children, we could create another
-				// visitor to go deeper if we start supporting style body components
-				if let RsxNode::Text { value, .. } = &mut *el.children {
-					if let Err(err) = self.apply_styles(value, scope) {
-						parse_err = Err(err);
-					}
+			}
+			if el.contains_attr_key("critical") {
+				el.attributes.retain(
+					|attr| !matches!(attr, RsxAttribute::Key { key } if key == "critical"),
+				);
+			} else if self.extract {
+				if let RsxNode::Text { value, .. } = &*el.children {
+					extracted.push(value.clone());
 				}
+				*node = RsxNode::default();
 			}
 		});
+		for css in extracted {
+			if !self.extracted.contains(&css) {
+				self.extracted.push(css);
+			}
+		}
 		// 2. tag elements if *any* component scoped styles were found
 		if component_scope_found {
-			VisitRsxElementMut::walk_with_opts(node, opts.clone(), |el| {
+			self.tag_elements(node, &id, false);
+		}
+		if cascade_scope_found {
+			self.tag_elements(node, &id, true);
+		}
+		if component_scope_found || cascade_scope_found {
+			self.idx += 1;
+		}
+		parse_err?;
+		Ok(cascade_scope_found.then_some(id))
+	}
+
+	/// The id is the index of the component, or a hash of its
+	/// styles when extracting.
+	fn style_id(&self, node: &RsxNode) -> String {
+		if !self.extract {
+			return self.idx.to_string();
+		}
+		let mut css = String::new();
+		VisitRsxElement::walk_with_opts(
+			node,
+			VisitRsxOptions::ignore_component(),
+			|el| {
+				if el.tag == "style" {
+					if let RsxNode::Text { value, .. } = &*el.children {
+						css.push_str(value);
+					}
+				}
+			},
+		);
+		format!("{:x}", rapidhash::rapidhash(css.as_bytes()) as u32)
+	}
+
+	/// Add the id to all elements, cascading ids are a space
+	/// separated list as elements may be in multiple slots
+	fn tag_elements(&self, node: &mut RsxNode, id: &str, cascade: bool) {
+		let opts = VisitRsxOptions::ignore_component();
+		VisitRsxElementMut::walk_with_opts(node, opts, |el| {
+			if !cascade {
 				el.attributes.push(RsxAttribute::KeyValue {
 					key: self.attr.to_string(),
-					value: self.idx.to_string(),
+					value: id.to_string(),
 				});
-			});
-			self.idx += 1;
-		}
-		parse_err
+				return;
+			}
+			let existing =
+				el.attributes.iter_mut().find_map(|attr| match attr {
+					RsxAttribute::KeyValue { key, value }
+						if *key == self.cascade_attr =>
+					{
+						Some(value)
+					}
+					_ => None,
+				});
+			match existing {
+				Some(value) => {
+					if !value.split(' ').any(|v| v == id) {
+						value.push(' ');
+						value.push_str(id);
+					}
+				}
+				None => el.attributes.push(RsxAttribute::KeyValue {
+					key: self.cascade_attr.to_string(),
+					value: id.to_string(),
+				}),
+			}
+		});
 	}
-	fn apply_styles(&self, css: &mut String, scope: Scope) -> ParseResult<()> {
+
+	fn apply_styles(
+		&self,
+		css: &mut String,
+		scope: Scope,
+		id: &str,
+	) -> ParseResult<()> {
 		// Parse the stylesheet
 		let mut stylesheet = StyleSheet::parse(css, ParserOptions::default())
 			.map_err(|e| ParseError::Serde(e.to_string()))?;
 
-		if scope == Scope::Component {
+		let selector = match scope {
+			Scope::Component => Some((&self.attr, AttrSelectorOperator::Equal)),
+			Scope::Cascade => {
+				Some((&self.cascade_attr, AttrSelectorOperator::Includes))
+			}
+			Scope::Global => None,
+		};
+
+		if let Some((attr, operator)) = selector {
 			stylesheet.rules.0.iter_mut().for_each(|rule| {
 				// we only care about style rules
 				if let lightningcss::rules::CssRule::Style(style_rule) = rule {
 					style_rule.selectors.0.iter_mut().for_each(|selector| {
 						selector.append(
 						lightningcss::selector::Component::AttributeInNoNamespace {
-							local_name: self.attr.clone().into(),
-							operator: AttrSelectorOperator::Equal,
-							value: self.idx.to_string().into(),
+							local_name: attr.clone().into(),
+							operator,
+							value: id.to_string().into(),
 							case_sensitivity:
 								ParsedCaseSensitivity::CaseSensitive,
 							never_matches: false,
@@ -166,6 +309,7 @@ mod test {
 	use crate::as_beet::*;
 	use sweet::prelude::*;
 
+	#[derive(Props)]
 	struct Child;
 
 	impl Component for Child {
@@ -246,4 +390,57 @@ mod test {
 		}.apply_and_render())
 			.to_be("<div data-styleid=\"0\"><style data-styleid=\"0\">span[data-styleid=\"0\"] {\n  color: #00f;\n}\n</style><br data-styleid=\"1\"/><style data-styleid=\"1\">span[data-styleid=\"1\"] {\n  color: red;\n}\n</style></div>");
 	}
+
+	#[derive(Props)]
+	struct Card;
+	impl Component for Card {
+		fn render(self) -> RsxRoot {
+			rsx! {
+				<div>
+					<style scope:cascade>p { color: red; }</style>
+					<slot />
+				</div>
+			}
+		}
+	}
+
+	#[test]
+	fn cascade_scope() {
+		expect(
+			rsx! {
+				<Card>
+					<p>hello</p>
+					<Card><p>nested</p></Card>
+				</Card>
+			}
+			.apply_and_render(),
+		)
+		.to_be("<div data-styleid-cascade=\"0\"><style data-styleid-cascade=\"0\">p[data-styleid-cascade~=\"0\"] {\n  color: red;\n}\n</style><p data-styleid-cascade=\"0\">hello</p><div data-styleid-cascade=\"1\"><style data-styleid-cascade=\"1\">p[data-styleid-cascade~=\"1\"] {\n  color: red;\n}\n</style><p data-styleid-cascade=\"1\">nested</p></div></div>");
+	}
+
+	#[test]
+	fn extract() {
+		let mut scoped_style = ScopedStyle::extract();
+		let mut root = rsx! {
+			<div>
+				<Child/>
+				<Child/>
+				<style scope:global critical>body { margin: 0; }</style>
+			</div>
+		};
+		scoped_style.apply(&mut root).unwrap();
+		let extracted = scoped_style.take_extracted();
+		expect(extracted.len()).to_be(1);
+		expect(&extracted[0]).to_contain("span[data-styleid=\"");
+		let html = RsxToHtml::render_body(&root);
+		// ids are a hash of the styles so they are the same for each instance
+		let id = extracted[0].split('"').nth(1).unwrap();
+		expect(
+			html.matches(&format!("<div data-styleid=\"{id}\">"))
+				.count(),
+		)
+		.to_be(2);
+		expect(&html).not().to_contain("span");
+		expect(&html).to_contain("<style>body {\n  margin: 0;\n}\n</style>");
+	}
 }
@@ -7,6 +7,7 @@ use thiserror::Error;
 /// ```
 /// # use beet_rsx::as_beet::*;
 ///
+/// #[derive(Props)]
 /// struct MyComponent;
 ///
 /// impl Component for MyComponent {
@@ -104,6 +105,7 @@ impl SlotsVisitor {
 				RsxNode::Doctype { .. }
 				| RsxNode::Comment { .. }
 				| RsxNode::Text { .. }
+				| RsxNode::RawHtml { .. }
 				| RsxNode::Block(_) => {
 					// taking a mutable node results in its children not being visited
 					default_slots.push(std::mem::take(node));
@@ -205,6 +207,7 @@ mod test {
 
 	#[test]
 	fn works() {
+		#[derive(Props)]
 		struct MyComponent;
 
 		impl Component for MyComponent {
@@ -233,6 +236,7 @@ mod test {
 	}
 	#[test]
 	fn recursive() {
+		#[derive(Props)]
 		struct MyComponent;
 
 		impl Component for MyComponent {
@@ -0,0 +1,125 @@
+use crate::prelude::*;
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Collects the styles of many documents into deduplicated stylesheets
+/// with content hashed file names, so that component styles
+/// are not repeated in every page.
+///
+/// Each document links the stylesheets it uses in its head,
+/// the stylesheets should then be written relative to the root of the
+/// site, ie the `dst_dir` of a router.
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let mut sheets = StyleSheets::default();
+/// let doc = sheets
+/// 	.build_document(rsx! { <div><style>div { color: red; }</style></div> })
+/// 	.unwrap();
+/// let (path, css) = sheets.iter().next().unwrap();
+/// assert!(doc.head.render().contains(&format!("href=\"/{path}\"")));
+/// assert!(css.contains("color: red"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StyleSheets {
+	/// The directory of the stylesheets relative to the site root,
+	/// defaults to `styles`
+	pub dir: String,
+	/// The stylesheets keyed by file name
+	sheets: BTreeMap<String, String>,
+}
+
+impl Default for StyleSheets {
+	fn default() -> Self {
+		Self {
+			dir: "styles".to_string(),
+			sheets: Default::default(),
+		}
+	}
+}
+
+impl StyleSheets {
+	/// Build a document like [RsxRoot::build_document], except the styles
+	/// are extracted with [ScopedStyle::extract] and linked in the head.
+	pub fn build_document(
+		&mut self,
+		mut root: RsxRoot,
+	) -> Result<HtmlDocument> {
+		let mut scoped_style = ScopedStyle::extract();
+		scoped_style.apply(&mut root)?;
+		let mut doc = root.build_unstyled_document()?;
+		for css in scoped_style.take_extracted() {
+			let name =
+				format!("{:016x}.css", rapidhash::rapidhash(css.as_bytes()));
+			let href = format!("/{}/{}", self.dir, name);
+			self.sheets.entry(name).or_insert(css);
+			doc.head.push(
+				HtmlElementNode {
+					tag: "link".to_string(),
+					namespace: Default::default(),
+					self_closing: true,
+					attributes: vec![
+						HtmlAttribute {
+							key: "rel".to_string(),
+							value: Some("stylesheet".to_string()),
+						},
+						HtmlAttribute {
+							key: "href".to_string(),
+							value: Some(href),
+						},
+					],
+					children: Vec::new(),
+				}
+				.into(),
+			);
+		}
+		Ok(doc)
+	}
+
+	/// Iterate over the path of each stylesheet relative to the site root,
+	/// and its css
+	pub fn iter(&self) -> impl Iterator<Item = (String, &str)> {
+		self.sheets
+			.iter()
+			.map(|(name, css)| (format!("{}/{}", self.dir, name), css.as_str()))
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[derive(Props)]
+	struct Button;
+	impl Component for Button {
+		fn render(self) -> RsxRoot {
+			rsx! {
+				<button>
+					<style>button { color: red; }</style>
+					<style scope:global critical>body { margin: 0; }</style>
+				</button>
+			}
+		}
+	}
+
+	#[test]
+	fn dedupes() {
+		let mut sheets = StyleSheets::default();
+		let doc1 = sheets
+			.build_document(rsx! { <div><Button/><Button/></div> })
+			.unwrap();
+		let doc2 = sheets.build_document(rsx! { <Button/> }).unwrap();
+		expect(sheets.iter().count()).to_be(1);
+		let (path, css) = sheets.iter().next().unwrap();
+		expect(css).to_contain("button[data-styleid=\"");
+		let link = format!("<link rel=\"stylesheet\" href=\"/{path}\"/>");
+		expect(doc1.head.render()).to_be(link.clone());
+		expect(doc2.head.render()).to_be(link);
+		// styles are not inlined, except critical styles
+		let body = doc1.render_body();
+		expect(&body).not().to_contain("color: red");
+		expect(&body).to_contain("<style data-styleid=\"");
+		expect(&body).to_contain("body {");
+	}
+}
@@ -0,0 +1,225 @@
+use crate::prelude::*;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+
+/// A boxed future resolving to rsx, as returned by async components.
+pub type RsxFuture = Pin<Box<dyn Future<Output = RsxRoot>>>;
+
+thread_local! {
+	static SUSPENDED: RefCell<HashMap<u64, RsxFuture>> = RefCell::new(HashMap::default());
+	static NEXT_ID: Cell<u64> = Cell::new(0);
+}
+
+/// A component that awaits data before rendering, the future is suspended
+/// and resolved by [`RsxRoot::resolve_suspense`] or streamed by
+/// [`RsxToHtmlStream`].
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// #[derive(Props)]
+/// struct MyComponent;
+/// impl AsyncComponent for MyComponent {
+/// 	async fn render(self) -> RsxRoot {
+/// 		let data = async { "hello" }.await;
+/// 		rsx! { <div>{data}</div> }
+/// 	}
+/// }
+/// let node = rsx! { <MyComponent/> };
+/// ```
+pub trait AsyncComponent {
+	fn render(self) -> impl Future<Output = RsxRoot>;
+}
+
+/// Renders the `fallback` in place of the `content` until it resolves.
+/// In the rsx tree this is a placeholder element, the future
+/// is stored until it is taken by the renderer.
+/// Blocks must be `Clone` so suspense is usually created in a closure.
+/// ```
+/// # use beet_rsx::as_beet::*;
+/// let content = || {
+/// 	Suspense::new(rsx! { <p>loading</p> }, async { rsx! { <p>done</p> } })
+/// };
+/// let node = rsx! { <div>{content}</div> };
+/// ```
+pub struct Suspense {
+	pub fallback: RsxRoot,
+	pub content: RsxFuture,
+}
+
+impl Suspense {
+	/// The tag of the placeholder element
+	pub const TAG: &'static str = "beet-suspense";
+
+	pub fn new<M>(
+		fallback: impl IntoRsxRoot<M>,
+		content: impl 'static + Future<Output = RsxRoot>,
+	) -> Self {
+		Self {
+			fallback: fallback.into_root(),
+			content: Box::pin(content),
+		}
+	}
+
+	/// Take the future for a suspense boundary, returning `None`
+	/// if it has already been taken.
+	pub fn take(id: u64) -> Option<RsxFuture> {
+		SUSPENDED.with(|suspended| suspended.borrow_mut().remove(&id))
+	}
+
+	/// Drop the futures of all suspense boundaries in the tree. This is
+	/// called when the fallbacks are rendered without streaming, ie by
+	/// [RsxRoot::build_document] and when registering effects, so that
+	/// futures which will never be awaited are not kept alive.
+	pub fn evict(node: &RsxNode) {
+		for id in Self::collect_ids(node) {
+			Self::take(id);
+		}
+	}
+
+	/// Returns the id of the suspense boundary if this node is a placeholder
+	pub fn placeholder_id(node: &RsxNode) -> Option<u64> {
+		let RsxNode::Element(el) = node else {
+			return None;
+		};
+		if el.tag != Self::TAG {
+			return None;
+		}
+		let key = HtmlConstants::default().suspense_key;
+		el.attributes.iter().find_map(|attr| match attr {
+			RsxAttribute::KeyValue { key: k, value } if k == key => {
+				value.parse().ok()
+			}
+			_ => None,
+		})
+	}
+
+	/// Collect the ids of all suspense boundaries in the tree
+	pub fn collect_ids(node: &RsxNode) -> Vec<u64> {
+		let mut ids = Vec::new();
+		VisitRsxNode::walk(node, |node| {
+			if let Some(id) = Self::placeholder_id(node) {
+				ids.push(id);
+			}
+		});
+		ids
+	}
+}
+
+impl IntoRsxRoot<Suspense> for Suspense {
+	fn into_root(self) -> RsxRoot {
+		let id = NEXT_ID.with(|next| {
+			let id = next.get();
+			next.set(id + 1);
+			id
+		});
+		SUSPENDED
+			.with(|suspended| suspended.borrow_mut().insert(id, self.content));
+		RsxRoot {
+			location: self.fallback.location,
+			node: RsxNode::Element(RsxElement {
+				idx: RsxIdx::default(),
+				tag: Self::TAG.to_string(),
+				attributes: vec![RsxAttribute::KeyValue {
+					key: HtmlConstants::default().suspense_key.to_string(),
+					value: id.to_string(),
+				}],
+				children: Box::new(self.fallback.node),
+				self_closing: false,
+			}),
+		}
+	}
+}
+
+/// Futures are suspended with an empty fallback,
+/// this allows [`AsyncComponent`] to be used directly in rsx.
+pub struct FutureIntoRsx;
+impl<T: 'static + Future<Output = RsxRoot>> IntoRsxRoot<FutureIntoRsx> for T {
+	fn into_root(self) -> RsxRoot { Suspense::new((), self).into_root() }
+}
+
+impl RsxRoot {
+	/// Await all suspended content in the tree concurrently, replacing each
+	/// placeholder with its content. This is used for static rendering where
+	/// there is no need to stream fallbacks.
+	pub async fn resolve_suspense(mut self) -> RsxRoot {
+		loop {
+			let ids = Suspense::collect_ids(&self.node);
+			if ids.is_empty() {
+				return self;
+			}
+			// placeholders with missing futures are left as fallbacks
+			let mut resolved =
+				futures::future::join_all(ids.into_iter().filter_map(|id| {
+					Suspense::take(id)
+						.map(|content| async move { (id, content.await) })
+				}))
+				.await
+				.into_iter()
+				.collect::<HashMap<_, _>>();
+			if resolved.is_empty() {
+				return self;
+			}
+			VisitRsxNodeMut::new(|node| {
+				if let Some(content) = Suspense::placeholder_id(node)
+					.and_then(|id| resolved.remove(&id))
+				{
+					*node = content.node;
+				}
+			})
+			.walk_node(&mut self.node);
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod test {
+	use crate::as_beet::*;
+	use sweet::prelude::*;
+
+	#[derive(Props)]
+	struct Greeting {
+		name: &'static str,
+	}
+	impl AsyncComponent for Greeting {
+		async fn render(self) -> RsxRoot {
+			let name = async { self.name }.await;
+			rsx! { <p>hello {name}</p> }
+		}
+	}
+
+	#[sweet::test]
+	async fn resolves() {
+		let nested = || {
+			Suspense::new("loading", async {
+				rsx! { <Greeting name="bar" /> }
+			})
+		};
+		let root = rsx! {
+			<div>
+				<Greeting name="foo" />
+				{nested}
+			</div>
+		};
+		expect(Suspense::collect_ids(&root).len()).to_be(2);
+		expect(RsxToHtml::render_body(&root)).to_contain(
+			"\"></beet-suspense><beet-suspense data-beet-suspense=\"",
+		);
+		expect(RsxToHtml::render_body(&root))
+			.to_contain("\">loading</beet-suspense></div>");
+		let root = root.resolve_suspense().await;
+		expect(RsxToHtml::render_body(&root)).to_be(
+			"<div data-beet-rsx-idx=\"0\"><p data-beet-rsx-idx=\"3\">hello foo</p><p data-beet-rsx-idx=\"11\">hello bar</p></div>",
+		);
+	}
+
+	#[test]
+	fn evicts() {
+		let root = rsx! { <Greeting name="foo" /> };
+		let id = Suspense::collect_ids(&root)[0];
+		root.build_document().unwrap();
+		// fallbacks were rendered so the future will never be awaited
+		expect(Suspense::take(id).is_none()).to_be_true();
+	}
+}
@@ -113,6 +113,7 @@ impl TextBlockEncoder {
 			.split(".")
 			.map(|i| {
 				i.split("-")
+					.filter(|i| !i.is_empty())
 					.map(|i| i.parse())
 					.collect::<Result<Vec<usize>, _>>()
 			})
@@ -171,6 +172,7 @@ impl CollapsedNode {
 			}
 			RsxNode::Doctype { .. } => out.push(CollapsedNode::Break),
 			RsxNode::Comment { .. } => out.push(CollapsedNode::Break),
+			RsxNode::RawHtml { .. } => out.push(CollapsedNode::Break),
 			RsxNode::Element(_) => out.push(CollapsedNode::Break),
 		}
 		return out;
@@ -220,6 +222,7 @@ mod test {
 	use crate::as_beet::*;
 	use sweet::prelude::*;
 
+	#[derive(Props)]
 	struct Adjective;
 	impl Component for Adjective {
 		fn render(self) -> RsxRoot {
@@ -37,6 +37,10 @@ impl TreeIdx {
 pub struct TreeIdxIncr(u32);
 
 impl TreeIdxIncr {
+	/// Start incrementing from this index instead of zero, used for nodes
+	/// inserted after the document was rendered.
+	pub fn new(start: TreeIdx) -> Self { Self(start.0) }
+
 	/// Call this before visiting any node.
 	pub fn next(&mut self) -> TreeIdx {
 		let idx = self.0;
@@ -48,6 +48,7 @@ impl TreeLocationMap {
 			.next()
 			.ok_or_else(|| ParseError::Serde("missing rusty locations".into()))?
 			.split(";")
+			.filter(|s| !s.is_empty())
 			.map(|s| {
 				let mut parts = s.split('*');
 				let key = parts
@@ -65,6 +66,7 @@ impl TreeLocationMap {
 			.next()
 			.ok_or_else(|| ParseError::Serde("missing text encoders".into()))?
 			.split(";")
+			.filter(|s| !s.is_empty())
 			.map(|s| {
 				let mut parts = s.split('*');
 				let key = parts
@@ -129,6 +131,10 @@ mod test {
 		let csv = map.to_csv();
 		let map2 = TreeLocationMap::from_csv(&csv).unwrap();
 		expect(&map2).to_be(&map);
+		// empty maps are valid, ie static parts of islands
+		let empty = TreeLocationMap::default();
+		expect(TreeLocationMap::from_csv(&empty.to_csv()).unwrap())
+			.to_be(empty);
 		// println!("{:#?}", map);
 
 		expect(map.collapsed_elements).to_be(
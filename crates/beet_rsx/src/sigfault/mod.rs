@@ -7,10 +7,16 @@ pub use signal::*;
 
 
 
-/// a woefully basic implementation of signals, intended
-/// only for testing and as an example implementation for
-/// authors of actual reactivity libraries.
-/// It aint a segfault, but it's not great.
+/// The default runtime of the rsx macro, a basic implementation of
+/// signals where each [`signal`] runs its subscribed [`effect`]s directly.
+/// It supports resuming server rendered blocks with [`resumable_signal`],
+/// `bind:` directives and disposing the effects of nodes removed by
+/// control flow, which is enough for simple pages and islands.
+///
+/// It has no derived values, batching or ownership so every update
+/// runs each subscriber, for anything more complex prefer the
+/// [`ReactiveRuntime`](crate::reactive::ReactiveRuntime) with `runtime:reactive`.
+/// It aint a segfault.
 pub struct Sigfault;
 
 impl Sigfault {
@@ -25,18 +31,22 @@ impl Sigfault {
 		tracker: RustyTracker,
 		block: impl 'static + Clone + IntoRsxRoot<M>,
 	) -> RsxNode {
+		let (initial, tracked) = track_initial(|| block.clone().into_root());
 		RsxNode::Block(RsxBlock {
 			idx,
-			initial: Box::new(block.clone().into_root()),
+			initial: Box::new(initial),
 			effect: Effect::new(
 				Box::new(move |loc: TreeLocation| {
-					effect(move || {
-						let block = block.clone();
-						DomTarget::with(move |target| {
-							let node = block.clone().into_root();
-							target.update_rsx_node(node.node, loc).unwrap()
-						});
-					});
+					match tracked {
+						Some(tracked) => tracked.effect(dispose_removed(
+							loc,
+							resumed_block_effect(loc, block),
+						)),
+						None => effect(dispose_removed(
+							loc,
+							block_effect(loc, block),
+						)),
+					}
 					Ok(())
 				}),
 				tracker,
@@ -54,11 +64,23 @@ impl Sigfault {
 		tracker: RustyTracker,
 		mut block: impl 'static + FnMut() -> Vec<RsxAttribute>,
 	) -> RsxAttribute {
+		let (initial, tracked) = track_initial(&mut block);
+		// the initial value has already been rendered
+		let mut prev =
+			tracked.as_ref().map(|_| owned_attribute_pairs(&initial));
 		RsxAttribute::Block {
-			initial: block(),
+			initial,
 			effect: Effect::new(
-				Box::new(|_loc| {
-					todo!();
+				Box::new(move |loc| {
+					register_effect(loc, tracked, move || {
+						let next = owned_attribute_pairs(&block());
+						if let Some(prev) = &prev {
+							DomTarget::update_attributes(prev, &next, loc)
+								.unwrap();
+						}
+						prev = Some(next);
+					});
+					Ok(())
 				}),
 				tracker,
 			),
@@ -76,18 +98,26 @@ impl Sigfault {
 		tracker: RustyTracker,
 		block: impl 'static + Clone + IntoSigfaultAttrVal<M>,
 	) -> RsxAttribute {
+		let (initial, tracked) =
+			track_initial(|| block.clone().into_sigfault_val());
 		RsxAttribute::BlockValue {
 			key: key.to_string(),
-			initial: block.clone().into_sigfault_val(),
+			initial,
 			effect: Effect::new(
 				Box::new(move |loc| {
-					effect(move || {
+					// the initial value has already been rendered
+					let mut first_run = tracked.is_none();
+					register_effect(loc, tracked, move || {
 						let value = block.clone().into_sigfault_val();
-						println!(
-							"would update attribute for {}\n{key}: {value}",
-							loc.tree_idx
-						);
-						todo!();
+						if first_run {
+							first_run = false;
+							return;
+						}
+						DomTarget::with(|target| {
+							target
+								.update_rsx_attribute(key, Some(&value), loc)
+								.unwrap()
+						});
 					});
 					Ok(())
 				}),
@@ -95,8 +125,92 @@ impl Sigfault {
 			),
 		}
 	}
+
+	/// Used by [`RstmlToRsx`] when it encounters a `bind:value` directive,
+	/// the setter is registered by [`EventRegistry::register_bind_value`].
+	pub fn parse_bind_value<T: BindValue>(
+		tracker: RustyTracker,
+		get: impl 'static + Fn() -> T,
+	) -> RsxAttribute {
+		Self::parse_attribute_block(tracker, move || {
+			vec![RsxAttribute::KeyValue {
+				key: "value".to_string(),
+				value: get().to_bind_value(),
+			}]
+		})
+	}
+
+	/// Used by [`RstmlToRsx`] when it encounters a `bind:checked` directive,
+	/// the setter is registered by [`EventRegistry::register_bind_checked`].
+	pub fn parse_bind_checked(
+		tracker: RustyTracker,
+		get: impl 'static + Fn() -> bool,
+	) -> RsxAttribute {
+		Self::parse_attribute_block(tracker, move || checked_attribute(get()))
+	}
+
+	/// Used by [`RstmlToRsx`] when it encounters a `bind:group` directive,
+	/// where `value` is the value of the element.
+	/// The setter is registered by [`EventRegistry::register_bind_group`].
+	pub fn parse_bind_group<T: BindGroup>(
+		tracker: RustyTracker,
+		get: impl 'static + Fn() -> T,
+		value: String,
+	) -> RsxAttribute {
+		Self::parse_attribute_block(tracker, move || {
+			checked_attribute(get().is_checked(&value))
+		})
+	}
 }
 
+/// Compute the initial value of an effect, when resuming the effect is
+/// subscribed by this instead of its first run, see [is_resuming].
+fn track_initial<O>(func: impl FnOnce() -> O) -> (O, Option<TrackedEffect>) {
+	if is_resuming() {
+		let (out, tracked) = tracked_effect(func);
+		(out, Some(tracked))
+	} else {
+		(func(), None)
+	}
+}
+
+/// Set the callback of the tracked effect, or create an effect
+/// if the initial value was not tracked.
+fn register_effect(
+	loc: TreeLocation,
+	tracked: Option<TrackedEffect>,
+	callback: impl 'static + FnMut(),
+) {
+	match tracked {
+		Some(tracked) => tracked.effect(dispose_removed(loc, callback)),
+		None => effect(dispose_removed(loc, callback)),
+	}
+}
+
+/// Dispose the effect once its node is removed, see [DomTarget::is_removed]
+fn dispose_removed(
+	loc: TreeLocation,
+	mut func: impl 'static + FnMut(),
+) -> impl 'static + FnMut() {
+	move || {
+		if DomTarget::is_removed(loc) {
+			dispose_effect();
+		} else {
+			func();
+		}
+	}
+}
+
+/// `checked` is a boolean attribute so it is removed when false
+fn checked_attribute(checked: bool) -> Vec<RsxAttribute> {
+	if checked {
+		vec![RsxAttribute::Key {
+			key: "checked".to_string(),
+		}]
+	} else {
+		vec![]
+	}
+}
 
 pub trait IntoSigfaultAttrVal<M> {
 	fn into_sigfault_val(self) -> String;
@@ -118,6 +232,7 @@ impl<T: FnOnce() -> U, U: IntoSigfaultAttrVal<M2>, M2>
 mod test {
 	use super::signal;
 	use crate::as_beet::*;
+	use std::rc::Rc;
 	use sweet::prelude::*;
 
 	#[test]
@@ -125,7 +240,7 @@ mod test {
 		let (get, set) = signal(7);
 
 		let mut rsx = rsx! { <div>value is {get}</div> };
-		DomTarget::set(RsDomTarget::new(&rsx));
+		DomTarget::set(RsDomTarget::new(&mut rsx));
 
 		rsx.register_effects();
 		expect(&DomTarget::with(|h| h.render()))
@@ -137,4 +252,267 @@ mod test {
 		expect(&DomTarget::with(|h| h.render()))
 			.to_contain("<div data-beet-rsx-idx=\"0\">value is 9</div>");
 	}
+
+	#[test]
+	fn patches() {
+		let (get, set) = signal(vec!["a", "b"]);
+		let items = move || {
+			get()
+				.into_iter()
+				.map(|item| rsx! { <li>{item}</li> })
+				.collect::<Vec<_>>()
+		};
+		let mut rsx = rsx! { <ul>{items}</ul> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<ul data-beet-rsx-idx=\"0\"><li data-beet-rsx-idx=\"3\">a</li><li data-beet-rsx-idx=\"6\">b</li></ul>",
+		);
+		set(vec!["c"]);
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<ul data-beet-rsx-idx=\"0\"><li data-beet-rsx-idx=\"3\">c</li></ul>",
+		);
+		set(vec!["c", "d"]);
+		expect(&DomTarget::with(|h| h.render())).to_contain(
+			"<ul data-beet-rsx-idx=\"0\"><li data-beet-rsx-idx=\"3\">c</li><li data-beet-rsx-idx=\"9\">d</li></ul>",
+		);
+	}
+
+	#[test]
+	fn sibling_blocks() {
+		let list = |get: Rc<dyn Fn() -> Vec<&'static str>>| {
+			move || {
+				get()
+					.into_iter()
+					.map(|item| rsx! { <li>{item}</li> })
+					.collect::<Vec<_>>()
+			}
+		};
+		let (get, set) = signal(vec!["a"]);
+		let (get2, set2) = signal(vec!["x"]);
+		let first = list(Rc::new(get));
+		let second = list(Rc::new(get2));
+		let mut rsx = rsx! { <ul>{first}<li>static</li>{second}</ul> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		let items = || {
+			DomTarget::with(|target| {
+				HtmlDocument::parse(&target.render())
+					.unwrap()
+					.query_selector_all("li")
+					.unwrap()
+					.into_iter()
+					.map(|li| li.text_content())
+					.collect::<Vec<_>>()
+			})
+		};
+		expect(items()).to_be(vec!["a", "static", "x"]);
+		set(vec!["a", "b"]);
+		set2(vec!["y", "z"]);
+		expect(items()).to_be(vec!["a", "b", "static", "y", "z"]);
+		set(vec![]);
+		set2(vec!["w"]);
+		expect(items()).to_be(vec!["static", "w"]);
+		set(vec!["c"]);
+		expect(items()).to_be(vec!["c", "static", "w"]);
+	}
+
+	#[test]
+	fn inserted_effects() {
+		let (items, set_items) = signal(vec![1]);
+		let (count, set_count) = signal(0);
+		let count2 = count.clone();
+		let list = move || {
+			let (count, set_count) = (count.clone(), set_count.clone());
+			For::new(
+				items.clone(),
+				|item| *item,
+				move |item| {
+					let (count, set_count) = (count.clone(), set_count.clone());
+					let id = format!("button-{item}");
+					rsx! {
+						<button id={id} onclick={move |_| set_count(count() + item)}>
+							{item}
+						</button>
+					}
+				},
+			)
+		};
+		let mut rsx = rsx! { <div>{list}</div> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		set_items(vec![1, 2]);
+		// inserted nodes are rendered with tree indices and their
+		// effects are registered
+		expect(DomTarget::with(|target| target.render()))
+			.not()
+			.to_contain("onclick");
+		DomTarget::dispatch("#button-2", "click", ()).unwrap();
+		expect(count2()).to_be(2);
+		DomTarget::dispatch("#button-1", "click", ()).unwrap();
+		expect(count2()).to_be(3);
+	}
+
+	#[test]
+	fn attribute_value() {
+		let (get, set) = signal(7);
+
+		let mut rsx = rsx! { <div count={get}/> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div count=\"7\" data-beet-rsx-idx=\"0\"/>");
+		set(8);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div count=\"8\" data-beet-rsx-idx=\"0\"/>");
+	}
+
+	#[test]
+	fn attribute_block() {
+		let (get, set) = signal(true);
+		let attrs = move || {
+			if get() {
+				vec![RsxAttribute::Key {
+					key: "hidden".into(),
+				}]
+			} else {
+				vec![RsxAttribute::KeyValue {
+					key: "class".into(),
+					value: "visible".into(),
+				}]
+			}
+		};
+
+		let mut rsx = rsx! { <div {attrs}/> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div hidden data-beet-rsx-idx=\"0\"/>");
+		set(false);
+		expect(&DomTarget::with(|h| h.render()))
+			.to_contain("<div data-beet-rsx-idx=\"0\" class=\"visible\"/>");
+	}
+
+	#[test]
+	fn bind_value() {
+		let (get, set) = signal(1);
+		let get2 = get.clone();
+		let mut rsx = rsx! { <input type="number" bind:value={(get, set)}/> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		let html = || DomTarget::with(|h| h.render());
+		expect(html()).to_contain("value=\"1\"");
+		DomTarget::dispatch("input", "input", "2").unwrap();
+		expect(get2()).to_be(2);
+		expect(html()).to_contain("value=\"2\"");
+		// invalid numbers are ignored
+		DomTarget::dispatch("input", "input", "-").unwrap();
+		expect(get2()).to_be(2);
+	}
+
+	#[test]
+	fn bind_checked() {
+		let (get, set) = signal(false);
+		let get2 = get.clone();
+		let mut rsx =
+			rsx! { <input type="checkbox" bind:checked={(get, set)}/> };
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		let html = || DomTarget::with(|h| h.render());
+		expect(html()).not().to_contain("checked");
+		DomTarget::dispatch("input", "change", true).unwrap();
+		expect(get2()).to_be_true();
+		expect(html()).to_contain("checked");
+		DomTarget::dispatch("input", "change", false).unwrap();
+		expect(html()).not().to_contain("checked");
+	}
+
+	#[test]
+	fn bind_group() {
+		let (get, set) = signal("a".to_string());
+		let (get2, set2) = (get.clone(), set.clone());
+		let (toppings, set_toppings) = signal(vec![1]);
+		let (toppings2, set_toppings2) =
+			(toppings.clone(), set_toppings.clone());
+		let mut rsx = rsx! {
+			<form>
+				<input id="a" type="radio" value="a" bind:group={(get, set)}/>
+				<input id="b" type="radio" value="b" bind:group={(get2, set2)}/>
+				<input id="one" type="checkbox" value="1" bind:group={(toppings, set_toppings)}/>
+				<input id="two" type="checkbox" value={2} bind:group={(toppings2, set_toppings2)}/>
+			</form>
+		};
+		DomTarget::set(RsDomTarget::new(&mut rsx));
+		rsx.register_effects();
+		let checked = |selector: &str| {
+			DomTarget::with(|target| {
+				HtmlDocument::parse(&target.render())
+					.unwrap()
+					.query_selector(selector)
+					.unwrap()
+					.unwrap()
+					.has_attribute("checked")
+			})
+		};
+		expect(checked("#a")).to_be_true();
+		expect(checked("#b")).to_be_false();
+		DomTarget::dispatch("#b", "change", true).unwrap();
+		expect(checked("#a")).to_be_false();
+		expect(checked("#b")).to_be_true();
+
+		expect(checked("#one")).to_be_true();
+		DomTarget::dispatch("#two", "change", true).unwrap();
+		DomTarget::dispatch("#one", "change", false).unwrap();
+		expect(checked("#one")).to_be_false();
+		expect(checked("#two")).to_be_true();
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn resumed() {
+		use super::resumable_signal;
+		use std::cell::Cell;
+		let app = |initial: u32, renders: Rc<Cell<u32>>| {
+			let (get, set) = resumable_signal(initial);
+			let get2 = get.clone();
+			let attrs = move || {
+				vec![RsxAttribute::KeyValue {
+					key: "class".into(),
+					value: format!("count-{}", get2()),
+				}]
+			};
+			let block = move || {
+				renders.set(renders.get() + 1);
+				get()
+			};
+			(rsx! { <div {attrs}>value is {block}</div> }, set)
+		};
+		ResumableState::clear();
+		// server
+		let (mut rsx, _) = app(2, Default::default());
+		let html = RsxToResumableHtml::default().map_root(&mut rsx).render();
+		// client
+		ResumableState::load_document(&HtmlDocument::parse(&html).unwrap())
+			.unwrap();
+		let renders = Rc::new(Cell::new(0));
+		let (mut rsx, set) = app(1, renders.clone());
+		DomTarget::set(RsDomTarget::from_html(&rsx, &html).unwrap());
+		rsx.register_effects();
+		ResumableState::clear();
+		// effects were subscribed by the initial render
+		expect(renders.get()).to_be(1);
+		let render = || DomTarget::with(|target| target.render());
+		expect(render()).to_contain(
+			"<div class=\"count-2\" data-beet-rsx-idx=\"0\">value is 2</div>",
+		);
+		set(3);
+		expect(renders.get()).to_be(2);
+		expect(render()).to_contain(
+			"<div class=\"count-3\" data-beet-rsx-idx=\"0\">value is 3</div>",
+		);
+		set(4);
+		expect(render()).to_contain(
+			"<div class=\"count-4\" data-beet-rsx-idx=\"0\">value is 4</div>",
+		);
+	}
 }
@@ -1,8 +1,10 @@
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 thread_local! {
 	static EFFECT_CALLBACK: RefCell<Option<Rc<RefCell<dyn FnMut()>>>> = RefCell::new(None);
+	static DISPOSE_EFFECT: Cell<bool> = const { Cell::new(false) };
 }
 
 /// an absolute minimal implementation of a signal
@@ -30,8 +32,15 @@ impl<T: Clone> Signal<T> {
 
 	fn set_value(&self, new_val: T) {
 		*self.value.borrow_mut() = new_val;
-		for callback in self.subscribers.borrow().iter() {
+		// callbacks may create effects that subscribe to this signal
+		let subscribers = self.subscribers.borrow().clone();
+		for callback in subscribers.iter() {
 			callback.borrow_mut()();
+			if DISPOSE_EFFECT.with(|dispose| dispose.take()) {
+				self.subscribers
+					.borrow_mut()
+					.retain(|other| !Rc::ptr_eq(other, callback));
+			}
 		}
 	}
 }
@@ -42,10 +51,46 @@ where
 	F: FnMut() + 'static,
 {
 	let callback = Rc::new(RefCell::new(callback));
-	EFFECT_CALLBACK
-		.with(|current| *current.borrow_mut() = Some(callback.clone()));
+	// effects may be created by other effects, ie inserted block nodes
+	let prev = EFFECT_CALLBACK
+		.with(|current| current.borrow_mut().replace(callback.clone()));
 	callback.borrow_mut()();
-	EFFECT_CALLBACK.with(|current| *current.borrow_mut() = None);
+	EFFECT_CALLBACK.with(|current| *current.borrow_mut() = prev);
+	// subscriptions are only removed by a triggering signal
+	DISPOSE_EFFECT.with(|dispose| dispose.set(false));
+}
+
+/// Called by a running effect to unsubscribe it from the signal that
+/// triggered it, it will be unsubscribed from other signals
+/// the next time they trigger it, ie when its node was removed.
+pub fn dispose_effect() { DISPOSE_EFFECT.with(|dispose| dispose.set(true)); }
+
+/// An effect subscribed to the signals read by [tracked_effect],
+/// it does nothing until its callback is set.
+pub struct TrackedEffect(Rc<RefCell<Option<Box<dyn FnMut()>>>>);
+
+impl TrackedEffect {
+	/// Set the callback, unlike [effect] it is not called immediately
+	/// as the tracked function already computed the current value.
+	pub fn effect(self, callback: impl 'static + FnMut()) {
+		*self.0.borrow_mut() = Some(Box::new(callback));
+	}
+}
+
+/// Call the function, subscribing a [TrackedEffect] to any signals it reads.
+pub fn tracked_effect<O>(func: impl FnOnce() -> O) -> (O, TrackedEffect) {
+	let inner: Rc<RefCell<Option<Box<dyn FnMut()>>>> = Default::default();
+	let inner2 = inner.clone();
+	let callback: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(move || {
+		if let Some(callback) = inner2.borrow_mut().as_mut() {
+			callback();
+		}
+	}));
+	let prev =
+		EFFECT_CALLBACK.with(|current| current.borrow_mut().replace(callback));
+	let out = func();
+	EFFECT_CALLBACK.with(|current| *current.borrow_mut() = prev);
+	(out, TrackedEffect(inner))
 }
 
 pub fn signal<T: Clone + 'static>(
@@ -68,6 +113,20 @@ pub fn signal<T: Clone + 'static>(
 	)
 }
 
+/// A [signal] that is tracked by the [ResumableState](crate::prelude::ResumableState),
+/// so on the client it resumes from the value rendered by the server.
+#[cfg(feature = "serde")]
+pub fn resumable_signal<
+	T: 'static + Clone + serde::Serialize + serde::de::DeserializeOwned,
+>(
+	value: T,
+) -> (impl Fn() -> T + Clone, impl Fn(T) + Clone) {
+	use crate::prelude::ResumableState;
+	let (get, set) = signal(ResumableState::resume(value));
+	ResumableState::track(get.clone());
+	(get, set)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -96,4 +155,24 @@ mod tests {
 		expect(get_count()).to_be(2);
 		expect(*effect_called.borrow()).to_be(3);
 	}
+
+	#[test]
+	fn disposes() {
+		let (get_count, set_count) = signal(0);
+		let effect_called = Rc::new(RefCell::new(0));
+		let effect_called_clone = effect_called.clone();
+
+		effect(move || {
+			*effect_called_clone.borrow_mut() += 1;
+			if get_count() > 1 {
+				dispose_effect();
+			}
+		});
+		set_count(1);
+		expect(*effect_called.borrow()).to_be(2);
+		set_count(2);
+		expect(*effect_called.borrow()).to_be(3);
+		set_count(3);
+		expect(*effect_called.borrow()).to_be(3);
+	}
 }
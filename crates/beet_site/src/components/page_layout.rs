@@ -3,6 +3,7 @@ use beet::prelude::*;
 
 
 
+#[derive(Props)]
 pub struct PageLayout {
 	pub title: String,
 }
@@ -10,8 +10,9 @@ use bevy::prelude::*;
 pub fn insert_on_asset_event_plugin<T: Component + Clone, A: Asset>(
 	app: &mut App,
 ) {
+	let schedule = app.tick_schedule();
 	app.add_systems(
-		Update,
+		schedule,
 		(
 			insert_on_asset_status::<T, A>,
 			insert_on_asset_event::<T, A>,
@@ -19,8 +19,9 @@ use bevy::prelude::*;
 /// - [`RotateToVelocity2d`]
 /// - [`RotateToVelocity3d`]
 pub fn movement_plugin(app: &mut App) {
+	let schedule = app.tick_schedule();
 	app.add_systems(
-		Update,
+		schedule,
 		(
 			(
 				integrate_force,
@@ -13,5 +13,6 @@ use bevy::prelude::*;
 /// Add all systems and types for procedural animation actions:
 /// - [`PlayProceduralAnimation`]
 pub fn procedural_animation_plugin(app: &mut App) {
-	app.add_systems(Update, play_procedural_animation.in_set(TickSet));
+	let schedule = app.tick_schedule();
+	app.add_systems(schedule, play_procedural_animation.in_set(TickSet));
 }
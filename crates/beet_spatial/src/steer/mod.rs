@@ -29,8 +29,9 @@ type M = GroupSteerAgent;
 /// Required Resources:
 /// - [`Time`]
 pub fn steer_plugin(app: &mut App) {
+	let schedule = app.tick_schedule();
 	app.add_systems(
-		Update,
+		schedule,
 		(
 			find_steer_target,
 			end_on_arrive,
@@ -18,6 +18,7 @@ async fn main() {
 		.run();
 }
 
+#[derive(Props)]
 struct Counter {
 	initial: i32,
 }